@@ -7,6 +7,14 @@ use sc_service::ChainType;
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<RuntimeGenesisConfig>;
 
+/// Build the development genesis config from the runtime's `DEV_GENESIS_OUTPUTS`.
+/// The outputs are validated by their constraint checkers when the genesis storage is built.
+fn development_genesis_config() -> RuntimeGenesisConfig {
+    let outputs = serde_json::from_str(DEV_GENESIS_OUTPUTS)
+        .expect("The development genesis outputs should be valid JSON.");
+    RuntimeGenesisConfig::new(outputs).with_strict_validation()
+}
+
 // /// Generate a crypto pair from seed.
 // pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
 // 	TPublic::Pair::from_string(&format!("//{}", seed), None)
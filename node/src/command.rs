@@ -51,7 +51,16 @@ pub fn run() -> sc_cli::Result<()> {
         Some(Subcommand::Key(cmd)) => cmd.run(&cli),
         Some(Subcommand::BuildSpec(cmd)) => {
             let runner = cli.create_runner(cmd)?;
-            runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+            runner.sync_run(|config| {
+                // Build the genesis storage first so that an invalid genesis config is reported
+                // here, rather than only when a node is launched from the resulting spec.
+                config
+                    .chain_spec
+                    .as_storage_builder()
+                    .build_storage()
+                    .map_err(sc_cli::Error::Input)?;
+                cmd.run(config.chain_spec, config.network)
+            })
         }
         Some(Subcommand::CheckBlock(cmd)) => {
             let runner = cli.create_runner(cmd)?;
@@ -8,11 +8,16 @@
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
-use node_template_runtime::opaque::Block;
+use node_template_runtime::{opaque::Block, OuterVerifier};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use tuxedo_core::utxo_set::TuxedoUtxoApi;
+
+mod tuxedo;
+
+pub use tuxedo::{Tuxedo, TuxedoApiServer};
 
 pub use sc_rpc_api::DenyUnsafe;
 
@@ -28,7 +33,7 @@ pub struct FullDeps<C, P> {
 
 /// Instantiate all full RPC extensions.
 pub fn create_full<C, P>(
-    _deps: FullDeps<C, P>,
+    deps: FullDeps<C, P>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
     C: ProvideRuntimeApi<Block>
@@ -38,9 +43,14 @@ where
         + Sync
         + 'static,
     C::Api: BlockBuilder<Block>,
+    C::Api: TuxedoUtxoApi<Block, OuterVerifier>,
     P: TransactionPool + 'static,
 {
-    let module = RpcModule::new(());
+    let mut module = RpcModule::new(());
+    let FullDeps { client, .. } = deps;
+
+    module.merge(Tuxedo::new(client).into_rpc())?;
+
     // Extend this RPC with a custom API by using the following syntax.
     // `YourRpcStruct` should have a reference to a client, which is needed
     // to call into the runtime.
@@ -0,0 +1,111 @@
+//! RPC methods for querying the Tuxedo UTXO set.
+//!
+//! These are served under the `tuxedo_` namespace so that wallets and other clients
+//! can look up UTXOs without knowing how they are laid out in storage.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+    core::{Error as JsonRpseeError, RpcResult},
+    proc_macros::rpc,
+    types::error::{CallError, ErrorObject},
+};
+use node_template_runtime::{opaque::Block, OuterVerifier, Output};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use tuxedo_core::{types::OutputRef, utxo_set::TuxedoUtxoApi};
+
+/// The error code returned when the runtime could not be queried.
+const RUNTIME_ERROR: i32 = 1;
+
+/// UTXO queries. Every method takes an optional block hash and defaults to the best block.
+///
+/// The node only keeps the current UTXO set, not the history of spent outputs. So none of
+/// these methods can tell a spent UTXO apart from one that never existed.
+#[rpc(server, namespace = "tuxedo")]
+pub trait TuxedoApi<BlockHash> {
+    /// Fetch a single UTXO, including its verifier and its payload's type id.
+    /// Returns `null` if there is no such UTXO.
+    #[method(name = "getUtxo")]
+    fn get_utxo(&self, output_ref: OutputRef, at: Option<BlockHash>) -> RpcResult<Option<Output>>;
+
+    /// Fetch several UTXOs at once. The results are in the same order as the requested refs.
+    #[method(name = "getUtxos")]
+    fn get_utxos(
+        &self,
+        output_refs: Vec<OutputRef>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<Option<Output>>>;
+
+    /// Check whether a UTXO has been spent, which is the case when it is not in the set.
+    /// This also returns `true` for refs that never existed.
+    #[method(name = "isSpent")]
+    fn is_spent(&self, output_ref: OutputRef, at: Option<BlockHash>) -> RpcResult<bool>;
+}
+
+/// Serves the UTXO queries by calling into the runtime.
+pub struct Tuxedo<C> {
+    client: Arc<C>,
+}
+
+impl<C> Tuxedo<C> {
+    /// Create a new instance of the Tuxedo RPC handler.
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+}
+
+impl<C> TuxedoApiServer<<Block as BlockT>::Hash> for Tuxedo<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: TuxedoUtxoApi<Block, OuterVerifier>,
+{
+    fn get_utxo(
+        &self,
+        output_ref: OutputRef,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<Output>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        self.client
+            .runtime_api()
+            .peek_utxo(at, output_ref)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_utxos(
+        &self,
+        output_refs: Vec<OutputRef>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<Option<Output>>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let api = self.client.runtime_api();
+
+        output_refs
+            .into_iter()
+            .map(|output_ref| {
+                api.peek_utxo(at, output_ref)
+                    .map_err(runtime_error_into_rpc_err)
+            })
+            .collect()
+    }
+
+    fn is_spent(
+        &self,
+        output_ref: OutputRef,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<bool> {
+        Ok(self.get_utxo(output_ref, at)?.is_none())
+    }
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> JsonRpseeError {
+    CallError::Custom(ErrorObject::owned(
+        RUNTIME_ERROR,
+        "Unable to query the UTXO set.",
+        Some(format!("{:?}", err)),
+    ))
+    .into()
+}
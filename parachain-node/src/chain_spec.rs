@@ -6,6 +6,14 @@ use serde::{Deserialize, Serialize};
 /// Specialized `ChainSpec` for the normal parachain runtime.
 pub type ChainSpec = sc_service::GenericChainSpec<RuntimeGenesisConfig, Extensions>;
 
+/// Build the development genesis config from the runtime's `DEV_GENESIS_OUTPUTS`.
+/// The outputs are validated by their constraint checkers when the genesis storage is built.
+fn development_genesis_config() -> RuntimeGenesisConfig {
+    let outputs = serde_json::from_str(DEV_GENESIS_OUTPUTS)
+        .expect("The development genesis outputs should be valid JSON.");
+    RuntimeGenesisConfig::new(outputs).with_strict_validation()
+}
+
 /// The extensions for the [`ChainSpec`].
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ChainSpecGroup, ChainSpecExtension)]
 #[serde(deny_unknown_fields)]
@@ -117,7 +117,16 @@ pub fn run() -> Result<()> {
     match &cli.subcommand {
         Some(Subcommand::BuildSpec(cmd)) => {
             let runner = cli.create_runner(cmd)?;
-            runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+            runner.sync_run(|config| {
+                // Build the genesis storage first so that an invalid genesis config is reported
+                // here, rather than only when a node is launched from the resulting spec.
+                config
+                    .chain_spec
+                    .as_storage_builder()
+                    .build_storage()
+                    .map_err(sc_cli::Error::Input)?;
+                cmd.run(config.chain_spec, config.network)
+            })
         }
         Some(Subcommand::CheckBlock(cmd)) => {
             construct_async_run!(|components, cli, cmd, config| {
@@ -1,6 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Ident, ItemEnum};
+use syn::{parse_macro_input, DeriveInput, Expr, Ident, ItemEnum};
 
 /// Automatically implements `From` for each type in an aggregate type enum.
 ///
@@ -75,7 +75,19 @@ pub fn tuxedo_verifier(_: TokenStream, body: TokenStream) -> TokenStream {
     let original_code = ast.clone();
 
     let outer_type = ast.ident;
-    let variants = ast.variants.into_iter().map(|v| v.ident);
+    let variant_type_pairs = ast.variants.into_iter().map(|variant| {
+        (
+            variant.ident,
+            variant
+                .fields
+                .into_iter()
+                .next()
+                .expect("exactly one field per variant")
+                .ty,
+        )
+    });
+    let variants = variant_type_pairs.clone().map(|(v, _t)| v);
+    let inner_types = variant_type_pairs.map(|(_v, t)| t);
 
     let output = quote! {
 
@@ -83,11 +95,23 @@ pub fn tuxedo_verifier(_: TokenStream, body: TokenStream) -> TokenStream {
         #[tuxedo_core::aggregate]
         #original_code
 
-        impl tuxedo_core::Verifier for #outer_type {
-            fn verify(&self, simplified_tx: &[u8], redeemer: &[u8]) -> bool {
+        impl<C> tuxedo_core::Verifier<#outer_type, C> for #outer_type
+        where
+            #(
+                #inner_types: tuxedo_core::Verifier<#outer_type, C>,
+            )*
+        {
+            fn verify_input(
+                &self,
+                simplified_tx: &[u8],
+                transaction: &tuxedo_core::types::Transaction<#outer_type, C>,
+                input_index: usize,
+                block_height: u32,
+                redeemer: &[u8],
+            ) -> bool {
                 match self {
                     #(
-                        Self::#variants(inner) => inner.verify(simplified_tx, redeemer),
+                        Self::#variants(inner) => inner.verify_input(simplified_tx, transaction, input_index, block_height, redeemer),
                     )*
                 }
             }
@@ -139,6 +163,10 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
     inherent_hooks_name.push_str("InherentHooks");
     let inherent_hooks = Ident::new(&inherent_hooks_name, outer_type.span());
 
+    let mut type_id_tests_name = outer_type.to_string();
+    type_id_tests_name.push_str("UtxoTypeIdTests");
+    let type_id_tests = Ident::new(&type_id_tests_name, outer_type.span());
+
     let vis = ast.vis;
 
     // TODO there must be a better way to do this, right?
@@ -147,11 +175,16 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
     let inner_types4 = inner_types.clone();
     let inner_types6 = inner_types.clone();
     let inner_types7 = inner_types.clone();
+    let inner_types8 = inner_types.clone();
+    let inner_types9 = inner_types.clone();
+    let inner_types10 = inner_types.clone();
     let variants2 = variants.clone();
     let variants3 = variants.clone();
     let variants4 = variants.clone();
     let variants5 = variants.clone();
     let variants6 = variants.clone();
+    let variants7 = variants.clone();
+    let variants8 = variants.clone();
 
     let output = quote! {
         // Preserve the original enum, and write the From impls
@@ -163,7 +196,11 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
         /// It is a combined error type for the errors of each individual checker.
         ///
         /// This type is accessible downstream as `<OuterConstraintChecker as ConstraintChecker>::Error`
-        #[derive(Debug)]
+        ///
+        /// The first byte of its encoding is the index of the checker that failed, and the following
+        /// byte(s) are the inner error. This is what allows the error to be reported to the pool
+        /// and decoded by clients of the dry-run runtime API.
+        #[derive(Debug, parity_scale_codec::Encode, parity_scale_codec::Decode)]
         #vis enum #error_type {
             #(
                 #variants(<#inner_types as tuxedo_core::ConstraintChecker<#verifier>>::Error),
@@ -186,7 +223,10 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
             fn create_inherents(
                 authoring_inherent_data: &InherentData,
                 previous_inherents: Vec<(tuxedo_core::types::Transaction<#verifier, #outer_type>, sp_core::H256)>,
-            ) -> Vec<tuxedo_core::types::Transaction<#verifier, #outer_type>>  {
+            ) -> Result<
+                Vec<tuxedo_core::types::Transaction<#verifier, #outer_type>>,
+                tuxedo_core::types::BlockExecutionError<#error_type>,
+            > {
 
                 let mut all_inherents = Vec::new();
 
@@ -204,6 +244,7 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
                             .collect();
 
                         let inherents = <#inner_types3 as tuxedo_core::ConstraintChecker<#verifier>>::InherentHooks::create_inherents(authoring_inherent_data, previous_inherents)
+                            .map_err(|e| e.map_checker_error(#error_type::#variants3))?
                             .iter()
                             .map(|tx| tx.transform::<#outer_type>())
                             .collect::<Vec<_>>();
@@ -212,7 +253,7 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
                 )*
 
                 // Return the aggregate of all inherent extrinsics from all constituent constraint checkers.
-                all_inherents
+                Ok(all_inherents)
             }
 
             fn check_inherents(
@@ -271,10 +312,11 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
                 inputs: &[tuxedo_core::types::Output<#verifier>],
                 peeks: &[tuxedo_core::types::Output<#verifier>],
                 outputs: &[tuxedo_core::types::Output<#verifier>],
+                evictions: &[tuxedo_core::types::Output<#verifier>],
             ) -> Result<TransactionPriority, Self::Error> {
                 match self {
                     #(
-                        Self::#variants5(inner) => inner.check(inputs, peeks, outputs).map_err(|e| Self::Error::#variants5(e)),
+                        Self::#variants5(inner) => inner.check(inputs, peeks, outputs, evictions).map_err(|e| Self::Error::#variants5(e)),
                     )*
                 }
             }
@@ -288,7 +330,130 @@ pub fn tuxedo_constraint_checker(attrs: TokenStream, body: TokenStream) -> Token
 
             }
 
+            fn allows_evictions(&self) -> bool {
+                match self {
+                    #(
+                        Self::#variants8(inner) => <#inner_types10 as tuxedo_core::ConstraintChecker<#verifier>>::allows_evictions(inner),
+                    )*
+                }
+            }
+
+            fn inherent_placement(&self) -> tuxedo_core::inherents::InherentPlacement {
+                match self {
+                    #(
+                        Self::#variants7(inner) => <#inner_types8 as tuxedo_core::ConstraintChecker<#verifier>>::inherent_placement(inner),
+                    )*
+                }
+            }
+
+            fn utxo_types() -> Vec<tuxedo_core::dynamic_typing::UtxoTypeInfo> {
+                let mut all_types = Vec::new();
+                #(
+                    all_types.extend(<#inner_types9 as tuxedo_core::ConstraintChecker<#verifier>>::utxo_types());
+                )*
+                all_types
+            }
+
         }
+
+        /// Type ids are only four bytes, so make sure that no two payload types
+        /// used by the aggregated constraint checkers share one.
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #type_id_tests {
+            use super::*;
+
+            #[test]
+            fn utxo_type_ids_are_unique() {
+                let types = <#outer_type as tuxedo_core::ConstraintChecker<#verifier>>::utxo_types();
+                if let Some((a, b)) = tuxedo_core::dynamic_typing::find_type_id_collision(&types) {
+                    panic!("UTXO payload types {} and {} share the type id {:?}", a.0, b.0, a.1);
+                }
+            }
+        }
+    };
+
+    output.into()
+}
+
+/// Derives the `UtxoData` trait, which is required for any type stored in a UTXO.
+///
+/// By default the type id is a hash of the type's module path and name, so types with the
+/// same name in different pieces get different ids. Generic types get the same id for every
+/// instantiation, so they should supply their own.
+///
+/// The id can be set explicitly, for example to keep an id that is already in use on chain.
+/// ```ignore
+/// #[derive(Encode, Decode, UtxoData)]
+/// #[utxo_data(type_id = *b"amoe")]
+/// pub struct AmoebaDetails { /* ... */ }
+/// ```
+///
+/// Types that implement `VersionedUtxoData` must be marked `#[utxo_data(versioned)]` so that
+/// extracting them from data stored as a previous version upgrades it. The attribute also
+/// implements `UpgradesPreviousVersions`, without which `VersionedUtxoData` does not compile.
+#[proc_macro_derive(UtxoData, attributes(utxo_data))]
+pub fn derive_utxo_data(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut type_id: Option<Expr> = None;
+    let mut versioned = false;
+    for attr in ast.attrs.iter().filter(|a| a.path().is_ident("utxo_data")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("type_id") {
+                type_id = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("versioned") {
+                versioned = true;
+                Ok(())
+            } else {
+                Err(meta
+                    .error("unsupported utxo_data attribute, expected `type_id` or `versioned`"))
+            }
+        });
+        if let Err(e) = parsed {
+            return e.to_compile_error().into();
+        }
+    }
+
+    let type_id = type_id.unwrap_or_else(|| {
+        syn::parse_quote! {
+            tuxedo_core::dynamic_typing::type_id_from_name(
+                concat!(module_path!(), "::", stringify!(#name))
+            )
+        }
+    });
+
+    let extract_previous_version = versioned.then(|| {
+        quote! {
+            fn extract_previous_version(
+                data: &tuxedo_core::dynamic_typing::DynamicallyTypedData,
+            ) -> Result<Self, tuxedo_core::dynamic_typing::DynamicTypingError> {
+                tuxedo_core::dynamic_typing::extract_previous_version::<Self>(data)
+            }
+
+            fn previous_versions() -> Vec<tuxedo_core::dynamic_typing::UtxoTypeInfo> {
+                tuxedo_core::dynamic_typing::previous_versions::<Self>()
+            }
+        }
+    });
+
+    let upgrades_previous_versions = versioned.then(|| {
+        quote! {
+            impl #impl_generics tuxedo_core::dynamic_typing::UpgradesPreviousVersions for #name #ty_generics #where_clause {}
+        }
+    });
+
+    let output = quote! {
+        impl #impl_generics tuxedo_core::dynamic_typing::UtxoData for #name #ty_generics #where_clause {
+            const TYPE_ID: [u8; 4] = #type_id;
+
+            #extract_previous_version
+        }
+
+        #upgrades_previous_versions
     };
 
     output.into()
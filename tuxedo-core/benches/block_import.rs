@@ -0,0 +1,197 @@
+//! A baseline benchmark for the time it takes to apply a block full of signed transfers.
+//!
+//! The executive verifies every input eagerly, one signature at a time. This benchmark applies
+//! a block of 1000 transactions that each spend two sr25519-protected outputs, and separately
+//! times the 2000 signature verifications on their own. If a batch verification host function
+//! becomes available again, the executive can be changed to use it and measured against this.
+//!
+//! Run it with `cargo bench -p tuxedo-core --bench block_import`.
+
+use std::time::{Duration, Instant};
+
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::{sr25519::Pair, Pair as _, H256};
+use sp_io::TestExternalities;
+use sp_runtime::{
+    generic,
+    traits::{BlakeTwo256, Header as _},
+    transaction_validity::TransactionPriority,
+};
+use tuxedo_core::{
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
+    types::{Input, Output, OutputRef, Transaction},
+    utxo_set::{TransparentUtxoSet, UtxoSet},
+    verifier::Sr25519Signature,
+    Executive, SimpleConstraintChecker,
+};
+
+/// The number of transactions in the benchmarked block.
+const TRANSACTIONS: u32 = 1000;
+
+/// The number of times the block is applied. The fastest run is reported.
+const RUNS: u32 = 5;
+
+/// A constraint checker that accepts every transaction, so that only the executive's own work,
+/// which is dominated by signature verification, is measured.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, Clone, PartialEq, Eq, TypeInfo)]
+struct AcceptEverything;
+
+impl SimpleConstraintChecker for AcceptEverything {
+    type Error = ();
+
+    fn check(
+        &self,
+        _input_data: &[DynamicallyTypedData],
+        _peek_data: &[DynamicallyTypedData],
+        _output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
+    ) -> Result<TransactionPriority, ()> {
+        Ok(0)
+    }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<Note>()
+    }
+}
+
+/// The payload of every output in the benchmark.
+#[derive(Encode, Decode, UtxoData)]
+#[utxo_data(type_id = *b"bnch")]
+struct Note(u32);
+
+type BenchTransaction = Transaction<Sr25519Signature, AcceptEverything>;
+type BenchHeader = generic::Header<u32, BlakeTwo256>;
+type BenchBlock = generic::Block<BenchHeader, BenchTransaction>;
+type BenchExecutive = Executive<BenchBlock, Sr25519Signature, AcceptEverything>;
+type BenchUtxoSet = TransparentUtxoSet<Sr25519Signature>;
+
+fn output_ref(transaction: u32, index: u32) -> OutputRef {
+    OutputRef {
+        tx_hash: H256::from_low_u64_be(transaction.into()),
+        index,
+    }
+}
+
+/// Store two outputs owned by `owner` for each transaction, and build the signed transactions
+/// that spend them.
+fn setup(owner: &Pair) -> Vec<BenchTransaction> {
+    let verifier = Sr25519Signature::new(H256::from_slice(owner.public().as_ref()));
+
+    (0..TRANSACTIONS)
+        .map(|i| {
+            for index in 0..2 {
+                let output = Output {
+                    payload: Note(i).into(),
+                    verifier: verifier.clone(),
+                };
+                BenchUtxoSet::store_utxo(output_ref(i, index), &output);
+            }
+
+            let mut transaction = Transaction {
+                inputs: (0..2)
+                    .map(|index| Input {
+                        output_ref: output_ref(i, index),
+                        redeemer: Vec::new(),
+                    })
+                    .collect(),
+                peeks: Vec::new(),
+                outputs: vec![Output {
+                    payload: Note(i).into(),
+                    verifier: verifier.clone(),
+                }],
+                checker: AcceptEverything,
+                evictions: Vec::new(),
+                valid_until: None,
+            };
+
+            let signature = owner.sign(&transaction.encode()).encode();
+            for input in transaction.inputs.iter_mut() {
+                input.redeemer = signature.clone();
+            }
+            transaction
+        })
+        .collect()
+}
+
+/// Apply every transaction to a fresh block and report how long it took.
+fn apply_block(owner: &Pair) -> Duration {
+    TestExternalities::default().execute_with(|| {
+        let transactions = setup(owner);
+        BenchExecutive::open_block(&BenchHeader::new(
+            1,
+            H256::zero(),
+            H256::zero(),
+            H256::zero(),
+            Default::default(),
+        ));
+
+        let start = Instant::now();
+        for transaction in transactions {
+            BenchExecutive::apply_extrinsic(transaction)
+                .expect("Benchmark transactions are valid")
+                .expect("Benchmark transactions are valid");
+        }
+        start.elapsed()
+    })
+}
+
+/// Verify the signatures from a block's worth of transactions on their own, one at a time,
+/// the way the executive does.
+fn verify_signatures(owner: &Pair) -> Duration {
+    TestExternalities::default().execute_with(|| {
+        let verifications: Vec<_> = setup(owner)
+            .into_iter()
+            .flat_map(|transaction| {
+                let mut stripped = transaction.clone();
+                for input in stripped.inputs.iter_mut() {
+                    input.redeemer = Vec::new();
+                }
+                let message = stripped.encode();
+                transaction
+                    .inputs
+                    .into_iter()
+                    .map(move |input| (message.clone(), input.redeemer))
+            })
+            .collect();
+        let signature = |redeemer: &[u8]| {
+            sp_core::sr25519::Signature::decode(&mut &redeemer[..])
+                .expect("Benchmark redeemers are signatures")
+        };
+
+        let start = Instant::now();
+        for (message, redeemer) in verifications.iter() {
+            assert!(sp_io::crypto::sr25519_verify(
+                &signature(redeemer),
+                message,
+                &owner.public()
+            ));
+        }
+        start.elapsed()
+    })
+}
+
+fn fastest(run: impl Fn() -> Duration) -> Duration {
+    (0..RUNS).map(|_| run()).min().unwrap_or_default()
+}
+
+fn main() {
+    let owner = Pair::from_seed(&[1; 32]);
+
+    let block = fastest(|| apply_block(&owner));
+    let signatures = fastest(|| verify_signatures(&owner));
+
+    println!(
+        "Applying {} two-input transfers took {:?} ({:?} per transaction)",
+        TRANSACTIONS,
+        block,
+        block / TRANSACTIONS
+    );
+    println!(
+        "Verifying their {} signatures alone took {:?} ({:.0}% of the block)",
+        2 * TRANSACTIONS,
+        signatures,
+        100.0 * signatures.as_secs_f64() / block.as_secs_f64()
+    );
+}
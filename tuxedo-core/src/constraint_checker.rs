@@ -5,19 +5,31 @@
 
 use sp_std::{fmt::Debug, vec::Vec};
 
-use crate::{dynamic_typing::DynamicallyTypedData, inherents::InherentInternal, types::Output};
+use crate::{
+    dynamic_typing::{DynamicallyTypedData, UtxoTypeInfo},
+    inherents::{InherentInternal, InherentPlacement},
+    types::Output,
+};
 use parity_scale_codec::{Decode, Encode};
 use sp_runtime::transaction_validity::TransactionPriority;
 
 /// A simplified constraint checker that a transaction can choose to call.
 /// Checks whether the input and output data from a transaction meets the codified constraints.
 ///
+/// Evicted data is consumed without its verifier being consulted, so evictions are rejected
+/// before the checker is called unless it sets `ALLOWS_EVICTIONS`. A checker that does must itself
+/// decide whether removing that state is justified.
+///
 /// Additional transient information may be passed to the constraint checker by including it in the fields
 /// of the constraint checker struct itself. Information passed in this way does not come from state, nor
 /// is it stored in state.
 pub trait SimpleConstraintChecker: Debug + Encode + Decode + Clone {
-    /// The error type that this constraint checker may return
-    type Error: Debug;
+    /// The error type that this constraint checker may return.
+    /// It is encoded to report which variant was hit when a transaction is invalid.
+    type Error: Debug + Encode + Decode;
+
+    /// Whether transactions calling this checker may evict state.
+    const ALLOWS_EVICTIONS: bool = false;
 
     /// The actual check validation logic
     fn check(
@@ -25,7 +37,14 @@ pub trait SimpleConstraintChecker: Debug + Encode + Decode + Clone {
         input_data: &[DynamicallyTypedData],
         peek_data: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error>;
+
+    /// The payload types this constraint checker reads or writes.
+    /// Aggregate constraint checkers use this to make sure no two payload types share a type id.
+    ///
+    /// There is deliberately no default, so that no checker is left out of that check by accident.
+    fn utxo_types() -> Vec<UtxoTypeInfo>;
 }
 
 /// A single constraint checker that a transaction can choose to call. Checks whether the input
@@ -39,8 +58,9 @@ pub trait SimpleConstraintChecker: Debug + Encode + Decode + Clone {
 /// of the constraint checker struct itself. Information passed in this way does not come from state, nor
 /// is it stored in state.
 pub trait ConstraintChecker<V>: Debug + Encode + Decode + Clone {
-    /// The error type that this constraint checker may return
-    type Error: Debug;
+    /// The error type that this constraint checker may return.
+    /// It is encoded to report which variant was hit when a transaction is invalid.
+    type Error: Debug + Encode + Decode;
 
     /// Optional Associated Inherent processing logic. If this transaction type is not an inherent, use ().
     /// If it is an inherent, use Self, and implement the TuxedoInherent trait.
@@ -52,11 +72,35 @@ pub trait ConstraintChecker<V>: Debug + Encode + Decode + Clone {
         inputs: &[Output<V>],
         peeks: &[Output<V>],
         outputs: &[Output<V>],
+        evictions: &[Output<V>],
     ) -> Result<TransactionPriority, Self::Error>;
 
     /// Tells whether this extrinsic is an inherent or not.
     /// If you return true here, you must provide the correct inherent hooks above.
     fn is_inherent(&self) -> bool;
+
+    /// Tells whether this transaction may evict state. Evicted outputs are consumed without
+    /// consulting their verifiers, so a checker that allows evictions must itself decide whether
+    /// removing that state is justified.
+    ///
+    /// By default, evictions are not allowed.
+    fn allows_evictions(&self) -> bool {
+        false
+    }
+
+    /// Tells whether this inherent is executed at the beginning or the end of the block.
+    /// This is only consulted when `is_inherent` returns true.
+    ///
+    /// By default, inherents are opening inherents.
+    fn inherent_placement(&self) -> InherentPlacement {
+        InherentPlacement::Opening
+    }
+
+    /// The payload types this constraint checker reads or writes.
+    /// Aggregate constraint checkers use this to make sure no two payload types share a type id.
+    ///
+    /// There is deliberately no default, so that no checker is left out of that check by accident.
+    fn utxo_types() -> Vec<UtxoTypeInfo>;
 }
 
 // This blanket implementation makes it so that any type that chooses to
@@ -73,6 +117,7 @@ impl<T: SimpleConstraintChecker, V> ConstraintChecker<V> for T {
         inputs: &[Output<V>],
         peeks: &[Output<V>],
         outputs: &[Output<V>],
+        evictions: &[Output<V>],
     ) -> Result<TransactionPriority, Self::Error> {
         // Extract the input data
         let input_data: Vec<DynamicallyTypedData> =
@@ -86,13 +131,25 @@ impl<T: SimpleConstraintChecker, V> ConstraintChecker<V> for T {
         let output_data: Vec<DynamicallyTypedData> =
             outputs.iter().map(|o| o.payload.clone()).collect();
 
+        // Extract the evicted data
+        let evicted_data: Vec<DynamicallyTypedData> =
+            evictions.iter().map(|o| o.payload.clone()).collect();
+
         // Call the simple constraint checker
-        SimpleConstraintChecker::check(self, &input_data, &peek_data, &output_data)
+        SimpleConstraintChecker::check(self, &input_data, &peek_data, &output_data, &evicted_data)
     }
 
     fn is_inherent(&self) -> bool {
         false
     }
+
+    fn allows_evictions(&self) -> bool {
+        T::ALLOWS_EVICTIONS
+    }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        <T as SimpleConstraintChecker>::utxo_types()
+    }
 }
 
 /// Utilities for writing constraint-checker-related unit tests
@@ -102,7 +159,11 @@ pub mod testing {
     use serde::{Deserialize, Serialize};
 
     use super::*;
-    use crate::{types::Output, verifier::TestVerifier};
+    use crate::{
+        dynamic_typing::{testing::Bogus, utxo_type_info},
+        types::Output,
+        verifier::TestVerifier,
+    };
 
     /// A testing checker that passes (with zero priority) or not depending on
     /// the boolean value enclosed.
@@ -112,6 +173,15 @@ pub mod testing {
         pub checks: bool,
         /// Whether this constraint checker is an inherent.
         pub inherent: bool,
+        /// Whether this inherent is executed at the end of the block rather than the beginning.
+        /// It is not encoded so that the encodings of existing test transactions,
+        /// and the roots calculated from them, are unaffected.
+        #[codec(skip)]
+        pub closing: bool,
+        /// Whether this constraint checker allows evictions.
+        /// It is not encoded for the same reason as `closing`.
+        #[codec(skip)]
+        pub evicts: bool,
     }
 
     impl ConstraintChecker<TestVerifier> for TestConstraintChecker {
@@ -123,6 +193,7 @@ pub mod testing {
             _input_data: &[Output<TestVerifier>],
             _peek_data: &[Output<TestVerifier>],
             _output_data: &[Output<TestVerifier>],
+            _evicted_data: &[Output<TestVerifier>],
         ) -> Result<TransactionPriority, ()> {
             if self.checks {
                 Ok(0)
@@ -134,6 +205,22 @@ pub mod testing {
         fn is_inherent(&self) -> bool {
             self.inherent
         }
+
+        fn allows_evictions(&self) -> bool {
+            self.evicts
+        }
+
+        fn inherent_placement(&self) -> InherentPlacement {
+            if self.closing {
+                InherentPlacement::Closing
+            } else {
+                InherentPlacement::Opening
+            }
+        }
+
+        fn utxo_types() -> Vec<UtxoTypeInfo> {
+            utxo_type_info::<Bogus>()
+        }
     }
 
     #[test]
@@ -141,8 +228,10 @@ pub mod testing {
         let result = TestConstraintChecker {
             checks: true,
             inherent: false,
+            closing: false,
+            evicts: false,
         }
-        .check(&[], &[], &[]);
+        .check(&[], &[], &[], &[]);
         assert_eq!(result, Ok(0));
     }
 
@@ -151,8 +240,10 @@ pub mod testing {
         let result = TestConstraintChecker {
             checks: false,
             inherent: false,
+            closing: false,
+            evicts: false,
         }
-        .check(&[], &[], &[]);
+        .check(&[], &[], &[], &[]);
         assert_eq!(result, Err(()));
     }
 }
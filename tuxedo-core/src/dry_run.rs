@@ -0,0 +1,42 @@
+//! A runtime API for dry-running Tuxedo transactions.
+//!
+//! Submitting a transaction to the pool only reports a compact `InvalidTransaction` when something
+//! goes wrong. Wallets and other clients can use this API to validate a transaction against the
+//! state of a given block before broadcasting it, and learn exactly why it would fail.
+
+use crate::types::{Output, OutputRef, Transaction, UtxoError};
+use parity_scale_codec::{Codec, Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sp_runtime::transaction_validity::TransactionPriority;
+use sp_std::vec::Vec;
+
+/// Everything that was resolved while dry-running a valid transaction.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct DryRunReport<V> {
+    /// The outputs that the transaction's inputs refer to, in the order of the inputs.
+    pub inputs: Vec<Output<V>>,
+    /// The outputs that the transaction's peeks refer to, in the order of the peeks.
+    pub peeks: Vec<Output<V>>,
+    /// The outputs that the transaction's evictions refer to, in the order of the evictions.
+    pub evictions: Vec<Output<V>>,
+    /// The priority the transaction would have in the pool.
+    pub priority: TransactionPriority,
+    /// The output refs that would be created if the transaction were applied.
+    pub created: Vec<OutputRef>,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Validate a transaction against the state of the block at which this API is called,
+    /// without committing anything to storage.
+    ///
+    /// The error type is the runtime's aggregated constraint checker error so that clients can decode it.
+    pub trait TuxedoDryRunApi<V, C, E>
+    where
+        V: Codec,
+        C: Codec,
+        E: Codec,
+    {
+        /// Dry-run the given transaction, returning either a report or the full reason it is invalid.
+        fn dry_run_transaction(transaction: Transaction<V, C>) -> Result<DryRunReport<V>, UtxoError<E>>;
+    }
+}
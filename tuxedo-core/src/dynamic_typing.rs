@@ -28,6 +28,11 @@
 //! against the type into which the data is being decoded. Currently this read-time checking
 //! is the job of the piece developer, although that may be able to improve in the future.
 //!
+//! The type identifier is usually derived with `#[derive(UtxoData)]`, which hashes the type's
+//! module path and name. Types whose id is already in use on chain can keep it with
+//! `#[utxo_data(type_id = *b"abcd")]`. Four bytes still leave room for collisions, so each
+//! runtime's aggregate constraint checker gets a test that no two payload types share an id.
+//!
 //! # Comparison with `sp_std::any`
 //!
 //! The Rust standard library, and also the `sp-std` crate offer utilities for dynamic typing
@@ -37,7 +42,7 @@
 //! ## In favor of `sp_std::any`
 //!
 //! * The compiler guarantees unique type ids for every type, whereas this utility
-//!   requires the developer to avoid collisions (hopefully macros can improve this slightly)
+//!   can only detect collisions among the types a runtime declares
 //! * Using that crate would be less code for Tuxedo developers to maintain
 //!
 //! ## In favor of this custom utility
@@ -49,7 +54,7 @@
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
-use sp_std::vec::Vec;
+use sp_std::{vec, vec::Vec};
 
 /// A piece of encoded data with a type id associated
 /// Strongly typed data can be extracted
@@ -64,24 +69,124 @@ pub struct DynamicallyTypedData {
 /// use the newtype pattern: https://doc.rust-lang.org/book/ch19-04-advanced-types.html.
 /// Using a new type allows strong type disambiguation between bespoke use-cases in which
 /// the same primitive may be stored.
+///
+/// Prefer `#[derive(UtxoData)]` over implementing this trait by hand.
 pub trait UtxoData: Encode + Decode {
-    //TODO Not great that it is up to the runtime dev to enforce uniqueness
-    // Maybe macros can help... Doesn't frame somehow pass info about the string in construct runtime to the pallet-level storage items?
-    /// A unique identifier for this type. For now choosing this value and making sure it
-    /// really is unique is the problem of the developer. Ideally this would be better.
+    /// A unique identifier for this type. It is stored alongside the encoded data, so it must
+    /// never change once the type is in use on chain.
     const TYPE_ID: [u8; 4];
+
+    /// Extract this type from data that was stored as one of its previous versions.
+    /// This is called by `extract` when the type id does not match `TYPE_ID`.
+    ///
+    /// Types without previous versions should keep this default, which reports the wrong type.
+    /// Versioned types implement `VersionedUtxoData` and derive with `#[utxo_data(versioned)]`.
+    fn extract_previous_version(_data: &DynamicallyTypedData) -> Result<Self, DynamicTypingError> {
+        Err(DynamicTypingError::WrongType)
+    }
+
+    /// The names and type ids of this type's previous versions, most recent first.
+    /// `extract` accepts data stored as any of them, so they are checked for collisions too.
+    ///
+    /// Types without previous versions should keep this default, which lists none.
+    fn previous_versions() -> Vec<UtxoTypeInfo> {
+        Vec::new()
+    }
+}
+
+/// A `UtxoData` type whose `extract_previous_version` defers to `VersionedUtxoData`.
+///
+/// This is implemented by `#[utxo_data(versioned)]`. Types that implement `UtxoData` by hand
+/// should only implement it if they call `extract_previous_version` and `previous_versions`
+/// the same way.
+pub trait UpgradesPreviousVersions: UtxoData {}
+
+/// A `UtxoData` type that replaces an earlier type whose data may still be stored in UTXOs.
+///
+/// To change the shape of a type that is in use on chain, rename the old type (eg `KittyDataV1`)
+/// while keeping its type id, and give the new type a different id. Then implement this trait on
+/// the new type. Extracting the new type from old data will transparently upgrade it. The previous
+/// version may itself be versioned, in which case upgrades are applied one version at a time.
+///
+/// Old data is only upgraded as it is read. To rewrite all stored UTXOs, see
+/// `migration::MigratePayloads`.
+///
+/// The new type must derive `UtxoData` with `#[utxo_data(versioned)]`, which implements the
+/// `UpgradesPreviousVersions` supertrait. Without it, `extract` would never consult this trait.
+pub trait VersionedUtxoData: UpgradesPreviousVersions {
+    /// The version of this type that came immediately before it.
+    type Previous: UtxoData;
+
+    /// Convert the previous version into this one.
+    fn upgrade(previous: Self::Previous) -> Self;
+}
+
+/// Extract a versioned type from data stored as any of its previous versions.
+/// This is how `#[utxo_data(versioned)]` implements `UtxoData::extract_previous_version`.
+pub fn extract_previous_version<T: VersionedUtxoData>(
+    data: &DynamicallyTypedData,
+) -> Result<T, DynamicTypingError> {
+    data.extract::<T::Previous>().map(T::upgrade)
+}
+
+/// List the previous versions of a versioned type, most recent first.
+/// This is how `#[utxo_data(versioned)]` implements `UtxoData::previous_versions`.
+pub fn previous_versions<T: VersionedUtxoData>() -> Vec<UtxoTypeInfo> {
+    utxo_type_info::<T::Previous>()
+}
+
+pub use aggregator::UtxoData;
+
+/// Derive a type id from a type's name, as `#[derive(UtxoData)]` does.
+///
+/// The name should be fully qualified (eg `my_piece::MyData`) to avoid collisions between
+/// types with the same name in different pieces. This is a 32 bit FNV-1a hash.
+pub const fn type_id_from_name(name: &str) -> [u8; 4] {
+    let bytes = name.as_bytes();
+    let mut hash: u32 = 0x811c9dc5;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x01000193);
+        i += 1;
+    }
+    hash.to_be_bytes()
+}
+
+/// The name and type id of a payload type. Used to detect type id collisions.
+pub type UtxoTypeInfo = (&'static str, [u8; 4]);
+
+/// Get the name and type id of a payload type, followed by those of its previous versions.
+pub fn utxo_type_info<T: UtxoData>() -> Vec<UtxoTypeInfo> {
+    let mut types = vec![(sp_std::any::type_name::<T>(), T::TYPE_ID)];
+    types.extend(T::previous_versions());
+    types
+}
+
+/// Find two different payload types that share a type id, if there are any.
+///
+/// The same type may appear several times, because several constraint checkers can
+/// operate on the same payload type.
+pub fn find_type_id_collision(types: &[UtxoTypeInfo]) -> Option<(UtxoTypeInfo, UtxoTypeInfo)> {
+    types.iter().enumerate().find_map(|(i, a)| {
+        types[i + 1..]
+            .iter()
+            .find(|b| a.1 == b.1 && a.0 != b.0)
+            .map(|b| (*a, *b))
+    })
 }
 
 impl DynamicallyTypedData {
     /// Extracts strongly typed data from an Output, iff the output contains the type of data
-    /// specified. If the contained data is not the specified type, or decoding fails, this errors.
+    /// specified, or one of its previous versions. If the contained data is not the specified type,
+    /// or decoding fails, this errors.
     pub fn extract<T: UtxoData>(&self) -> Result<T, DynamicTypingError> {
         // The first four bytes represent the type id that that was encoded. If they match the type
-        // we are trying to decode into, we continue, otherwise we error out.
+        // we are trying to decode into, we continue, otherwise we try the type's previous versions.
         if self.type_id == <T as UtxoData>::TYPE_ID {
             T::decode(&mut &self.data[..]).map_err(|_| DynamicTypingError::DecodingFailed)
         } else {
-            Err(DynamicTypingError::WrongType)
+            T::extract_previous_version(self)
         }
     }
 }
@@ -173,6 +278,114 @@ mod tests {
         assert_eq!(extracted_b, Ok(original_b));
     }
 
+    /// A second version of `Byte` that holds two bytes.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    struct Pair(u8, u8);
+
+    impl UtxoData for Pair {
+        const TYPE_ID: [u8; 4] = *b"pair";
+
+        fn extract_previous_version(
+            data: &DynamicallyTypedData,
+        ) -> Result<Self, DynamicTypingError> {
+            extract_previous_version::<Self>(data)
+        }
+
+        fn previous_versions() -> Vec<UtxoTypeInfo> {
+            previous_versions::<Self>()
+        }
+    }
+
+    impl UpgradesPreviousVersions for Pair {}
+
+    impl VersionedUtxoData for Pair {
+        type Previous = Byte;
+
+        fn upgrade(previous: Byte) -> Self {
+            Pair(previous.0, 0)
+        }
+    }
+
+    /// A third version of `Byte` that holds a u16 and a byte.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    struct Wide(u16, u8);
+
+    impl UtxoData for Wide {
+        const TYPE_ID: [u8; 4] = *b"wide";
+
+        fn extract_previous_version(
+            data: &DynamicallyTypedData,
+        ) -> Result<Self, DynamicTypingError> {
+            extract_previous_version::<Self>(data)
+        }
+
+        fn previous_versions() -> Vec<UtxoTypeInfo> {
+            previous_versions::<Self>()
+        }
+    }
+
+    impl UpgradesPreviousVersions for Wide {}
+
+    impl VersionedUtxoData for Wide {
+        type Previous = Pair;
+
+        fn upgrade(previous: Pair) -> Self {
+            Wide(previous.0 as u16, previous.1)
+        }
+    }
+
+    #[test]
+    fn extract_upgrades_previous_version() {
+        let dynamically_typed_b: DynamicallyTypedData = Byte(4).into();
+
+        assert_eq!(dynamically_typed_b.extract::<Pair>(), Ok(Pair(4, 0)));
+    }
+
+    #[test]
+    fn extract_upgrades_through_several_versions() {
+        let dynamically_typed_b: DynamicallyTypedData = Byte(4).into();
+
+        assert_eq!(dynamically_typed_b.extract::<Wide>(), Ok(Wide(4, 0)));
+    }
+
+    #[test]
+    fn extract_current_version_of_versioned_type() {
+        let dynamically_typed_p: DynamicallyTypedData = Pair(4, 5).into();
+
+        assert_eq!(dynamically_typed_p.extract::<Pair>(), Ok(Pair(4, 5)));
+    }
+
+    #[test]
+    fn extract_does_not_downgrade() {
+        let dynamically_typed_p: DynamicallyTypedData = Pair(4, 5).into();
+
+        assert_eq!(
+            dynamically_typed_p.extract::<Byte>(),
+            Err(DynamicTypingError::WrongType)
+        );
+    }
+
+    #[test]
+    fn extract_versioned_from_unrelated_type() {
+        let dynamically_typed_b: DynamicallyTypedData = Bogus.into();
+
+        assert_eq!(
+            dynamically_typed_b.extract::<Wide>(),
+            Err(DynamicTypingError::WrongType)
+        );
+    }
+
+    #[test]
+    fn extract_previous_version_decode_fails() {
+        let mut dynamically_typed_b: DynamicallyTypedData = Byte(4).into();
+        dynamically_typed_b.data = Vec::new();
+
+        assert_eq!(
+            dynamically_typed_b.extract::<Pair>(),
+            Err(DynamicTypingError::DecodingFailed)
+        );
+    }
+
     #[test]
     fn extract_wrong_type() {
         let original_b = Byte(4);
@@ -211,4 +424,66 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn type_id_from_name_is_fnv_1a() {
+        assert_eq!(type_id_from_name(""), 0x811c9dc5u32.to_be_bytes());
+        assert_eq!(type_id_from_name("a"), 0xe40c292cu32.to_be_bytes());
+    }
+
+    #[test]
+    fn type_id_from_name_distinguishes_paths() {
+        assert_ne!(
+            type_id_from_name("piece_a::Data"),
+            type_id_from_name("piece_b::Data")
+        );
+    }
+
+    #[test]
+    fn no_collision_among_distinct_ids() {
+        let types = [utxo_type_info::<Byte>(), utxo_type_info::<Bogus>()].concat();
+
+        assert_eq!(find_type_id_collision(&types), None);
+    }
+
+    #[test]
+    fn repeated_type_is_not_a_collision() {
+        let types = [utxo_type_info::<Byte>(), utxo_type_info::<Byte>()].concat();
+
+        assert_eq!(find_type_id_collision(&types), None);
+    }
+
+    #[test]
+    fn collision_is_found() {
+        let impostor = ("impostor::Byte", *b"byte");
+        let types = [
+            utxo_type_info::<Bogus>(),
+            utxo_type_info::<Byte>(),
+            vec![impostor],
+        ]
+        .concat();
+
+        assert_eq!(
+            find_type_id_collision(&types),
+            Some((utxo_type_info::<Byte>()[0], impostor))
+        );
+    }
+
+    #[test]
+    fn type_info_lists_previous_versions() {
+        let ids: Vec<_> = utxo_type_info::<Wide>().iter().map(|t| t.1).collect();
+
+        assert_eq!(ids, vec![*b"wide", *b"pair", *b"byte"]);
+    }
+
+    #[test]
+    fn collision_with_previous_version_is_found() {
+        let impostor = ("impostor::Byte", *b"byte");
+        let types = [utxo_type_info::<Wide>(), vec![impostor]].concat();
+
+        assert_eq!(
+            find_type_id_collision(&types),
+            Some((utxo_type_info::<Byte>()[0], impostor))
+        );
+    }
 }
@@ -8,12 +8,15 @@
 
 use crate::{
     constraint_checker::ConstraintChecker,
+    dry_run::DryRunReport,
     ensure,
-    inherents::{InherentInternal, PARENT_INHERENT_IDENTIFIER},
-    types::{DispatchResult, OutputRef, Transaction, UtxoError},
-    utxo_set::TransparentUtxoSet,
+    inherents::{InherentInternal, InherentPlacement, PARENT_INHERENT_IDENTIFIER},
+    migration::UtxoMigration,
+    priority::PriorityPolicy,
+    types::{BlockExecutionError, DispatchResult, Output, OutputRef, Transaction, UtxoError},
+    utxo_set::{TransparentUtxoSet, UtxoSet},
     verifier::Verifier,
-    EXTRINSIC_KEY, HEADER_KEY, LOG_TARGET,
+    BLOCK_HEIGHT_KEY, CLOSING_INHERENTS_KEY, EXTRINSIC_KEY, HEADER_KEY, LOG_TARGET,
 };
 use log::debug;
 use parity_scale_codec::{Decode, Encode};
@@ -21,7 +24,7 @@ use sp_api::{BlockT, HashT, HeaderT, TransactionValidity};
 use sp_core::H256;
 use sp_inherents::{CheckInherentsResult, InherentData};
 use sp_runtime::{
-    traits::BlakeTwo256,
+    traits::{BlakeTwo256, UniqueSaturatedInto},
     transaction_validity::{
         InvalidTransaction, TransactionLongevity, TransactionSource, TransactionValidityError,
         ValidTransaction,
@@ -33,10 +36,39 @@ use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 
 /// The executive. Each runtime is encouraged to make a type alias called `Executive` that fills
 /// in the proper generic types.
-pub struct Executive<B, V, C>(PhantomData<(B, V, C)>);
+///
+/// The final generic is the priority policy used for the transaction pool. If your runtime does
+/// not need a custom policy, it can be omitted and the constraint checker's priority is used as is.
+///
+/// The next generic is the storage backend for the UTXO set. It defaults to the `TransparentUtxoSet`.
+///
+/// The last generic is the runtime's UTXO migrations, which run at the start of every block.
+/// It defaults to `()`, which runs none.
+pub struct Executive<B, V, C, P = (), U = TransparentUtxoSet<V>, M = ()>(
+    PhantomData<(B, V, C, P, U, M)>,
+);
+
+/// The sections of a block, in the order they must appear.
+/// Used to make sure that inherents are in the right places when executing a block.
+///
+/// Closing inherents appear right after the opening inherents, because block proposers apply all
+/// inherents before any pool transactions. They are still executed after the user transactions,
+/// but they are created before them, so they cannot react to the user transactions of their own block.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum BlockSection {
+    OpeningInherents,
+    ClosingInherents,
+    UserTransactions,
+}
 
-impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker<V>>
-    Executive<B, V, C>
+impl<
+        B: BlockT<Extrinsic = Transaction<V, C>>,
+        V: Verifier<V, C>,
+        C: ConstraintChecker<V>,
+        P: PriorityPolicy<V, C>,
+        U: UtxoSet<V>,
+        M: UtxoMigration,
+    > Executive<B, V, C, P, U, M>
 {
     /// Does pool-style validation of a tuxedo transaction.
     /// Does not commit anything to storage.
@@ -65,9 +97,39 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
                 UtxoError::DuplicateInput
             );
 
+            // Evictions are consumed too, so they may not repeat, or overlap with the inputs.
+            let consumed_set: BTreeSet<_> = transaction
+                .inputs
+                .iter()
+                .map(|input| &input.output_ref)
+                .chain(transaction.evictions.iter())
+                .map(|o| o.encode())
+                .collect();
+            ensure!(
+                consumed_set.len() == transaction.inputs.len() + transaction.evictions.len(),
+                UtxoError::DuplicateInput
+            );
         }
 
-        
+        // Evicted utxos are consumed without consulting their verifiers,
+        // so only constraint checkers that opt in may evict anything.
+        ensure!(
+            transaction.evictions.is_empty() || transaction.checker.allows_evictions(),
+            UtxoError::EvictionsNotAllowed
+        );
+
+        // The height of the block that this transaction is, or would be, included in.
+        // It is used both for mortality and by the verifiers.
+        let inclusion_height = Self::inclusion_block_height();
+
+        // Make sure a mortal transaction has not expired, and work out how long it may stay in the pool
+        let longevity = match transaction.valid_until {
+            Some(valid_until) => {
+                ensure!(valid_until >= inclusion_height, UtxoError::Expired);
+                (valid_until - inclusion_height) as TransactionLongevity + 1
+            }
+            None => TransactionLongevity::max_value(),
+        };
 
         // Build the stripped transaction (with the redeemers stripped) and encode it
         // This will be passed to the verifiers
@@ -78,16 +140,29 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         let stripped_encoded = stripped.encode();
 
         // Check that the verifiers of all inputs are satisfied
+        //
+        // Verification is always eager, both in the pool and during block execution.
+        // We would like to use batch signature verification when executing blocks, but the
+        // `start_batch_verify`/`finish_batch_verify` and `*_batch_verify` host functions are
+        // register-only in the Substrate version we build against. They are kept for old runtimes,
+        // cannot be called by new ones, and no longer batch anything on the host side.
+        // Revisit this if a batching host function is reintroduced. The `block_import` benchmark
+        // measures eager verification of a block of transfers as a baseline to compare against.
+        //
         // Keep a Vec of the input utxos for passing to the constraint checker
         // Keep track of any missing inputs for use in the tagged transaction pool
         let mut input_utxos = Vec::new();
         let mut missing_inputs = Vec::new();
-        for input in transaction.inputs.iter() {
-            if let Some(input_utxo) = TransparentUtxoSet::<V>::peek_utxo(&input.output_ref) {
+        for (index, input) in transaction.inputs.iter().enumerate() {
+            if let Some(input_utxo) = U::peek_utxo(&input.output_ref) {
                 ensure!(
-                    input_utxo
-                        .verifier
-                        .verify(&stripped_encoded, &input.redeemer),
+                    input_utxo.verifier.verify_input(
+                        &stripped_encoded,
+                        &stripped,
+                        index,
+                        inclusion_height,
+                        &input.redeemer
+                    ),
                     UtxoError::VerifierError
                 );
                 input_utxos.push(input_utxo);
@@ -101,13 +176,26 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         // Use the same vec as previously to keep track of missing peeks
         let mut peek_utxos = Vec::new();
         for output_ref in transaction.peeks.iter() {
-            if let Some(peek_utxo) = TransparentUtxoSet::<V>::peek_utxo(output_ref) {
+            if let Some(peek_utxo) = U::peek_utxo(output_ref) {
                 peek_utxos.push(peek_utxo);
             } else {
                 missing_inputs.push(output_ref.encode());
             }
         }
 
+        // Make a Vec of the evicted utxos for passing to the constraint checker.
+        // Evicted utxos are consumed without consulting their verifiers, so it is up to
+        // the constraint checker to decide whether the eviction is justified.
+        // Missing evictions are tracked along with the missing inputs and peeks.
+        let mut evicted_utxos = Vec::new();
+        for output_ref in transaction.evictions.iter() {
+            if let Some(evicted_utxo) = U::peek_utxo(output_ref) {
+                evicted_utxos.push(evicted_utxo);
+            } else {
+                missing_inputs.push(output_ref.encode());
+            }
+        }
+
         // Make sure no outputs already exist in storage
         let tx_hash = BlakeTwo256::hash_of(&transaction.encode());
         for index in 0..transaction.outputs.len() {
@@ -122,7 +210,7 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
             );
 
             ensure!(
-                TransparentUtxoSet::<V>::peek_utxo(&output_ref).is_none(),
+                U::peek_utxo(&output_ref).is_none(),
                 UtxoError::PreExistingOutput
             );
         }
@@ -150,23 +238,31 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
                 requires: missing_inputs,
                 provides,
                 priority: 0,
-                longevity: TransactionLongevity::max_value(),
+                longevity,
                 propagate: true,
             });
         }
 
         // Call the constraint checker
-        transaction
+        let checker_priority = transaction
             .checker
-            .check(&input_utxos, &peek_utxos, &transaction.outputs)
+            .check(
+                &input_utxos,
+                &peek_utxos,
+                &transaction.outputs,
+                &evicted_utxos,
+            )
             .map_err(UtxoError::ConstraintCheckerError)?;
 
+        // Let the runtime's priority policy decide the final pool priority
+        let priority = P::priority(transaction, checker_priority);
+
         // Return the valid transaction
         Ok(ValidTransaction {
             requires: Vec::new(),
             provides,
-            priority: 0,
-            longevity: TransactionLongevity::max_value(),
+            priority,
+            longevity,
             propagate: true,
         })
     }
@@ -197,6 +293,53 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         Ok(())
     }
 
+    /// Validates a transaction against the current state without committing anything to storage,
+    /// and reports the UTXOs it resolved along the way. This backs the `TuxedoDryRunApi`.
+    /// Unlike in the pool, missing inputs are an error here, just like they are during block execution.
+    pub fn dry_run_transaction(
+        transaction: &Transaction<V, C>,
+    ) -> Result<DryRunReport<V>, UtxoError<C::Error>> {
+        let valid_transaction = Self::validate_tuxedo_transaction(transaction)?;
+        ensure!(
+            valid_transaction.requires.is_empty(),
+            UtxoError::MissingInput
+        );
+
+        // Validation already made sure that all of these are present in storage.
+        let inputs = transaction
+            .inputs
+            .iter()
+            .filter_map(|input| U::peek_utxo(&input.output_ref))
+            .collect();
+        let peeks = transaction.peeks.iter().filter_map(U::peek_utxo).collect();
+        let evictions = transaction
+            .evictions
+            .iter()
+            .filter_map(U::peek_utxo)
+            .collect();
+
+        let tx_hash = BlakeTwo256::hash_of(&transaction.encode());
+        let created = (0..transaction.outputs.len())
+            .map(|index| OutputRef {
+                tx_hash,
+                index: index as u32,
+            })
+            .collect();
+
+        Ok(DryRunReport {
+            inputs,
+            peeks,
+            evictions,
+            priority: valid_transaction.priority,
+            created,
+        })
+    }
+
+    /// Fetch a utxo from the runtime's configured UTXO set. This backs the `TuxedoUtxoApi`.
+    pub fn peek_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
+        U::peek_utxo(output_ref)
+    }
+
     /// Helper function to update the utxo set according to the given transaction.
     /// This function does absolutely no validation. It assumes that the transaction
     /// has already passed validation. Changes proposed by the transaction are written
@@ -204,7 +347,12 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
     fn update_storage(transaction: Transaction<V, C>) {
         // Remove verified UTXOs
         for input in &transaction.inputs {
-            TransparentUtxoSet::<V>::consume_utxo(&input.output_ref);
+            U::consume_utxo(&input.output_ref);
+        }
+
+        // Remove evicted UTXOs
+        for output_ref in &transaction.evictions {
+            U::consume_utxo(output_ref);
         }
 
         debug!(
@@ -217,7 +365,7 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
                 tx_hash: BlakeTwo256::hash_of(&transaction.encode()),
                 index: index as u32,
             };
-            TransparentUtxoSet::<V>::store_utxo(output_ref, output);
+            U::store_utxo(output_ref, output);
         }
     }
 
@@ -226,10 +374,44 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
     where
         B::Header: HeaderT,
     {
-        *sp_io::storage::get(HEADER_KEY)
+        *Self::block_header().number()
+    }
+
+    /// A helper function that allows tuxedo runtimes to read the current block's header,
+    /// for example to find the consensus digests that the author placed in it.
+    ///
+    /// The header is only partially filled in until the block is closed, but the parent hash,
+    /// number, and pre-runtime digests are already known.
+    pub fn block_header() -> B::Header {
+        sp_io::storage::get(HEADER_KEY)
             .and_then(|d| B::Header::decode(&mut &*d).ok())
             .expect("A header is always stored at the beginning of the block")
-            .number()
+    }
+
+    /// The height of the earliest block that a transaction being validated could be included in.
+    ///
+    /// During block authoring and execution, this is the height of the current block. In the
+    /// transaction pool, it is the block after the most recent one. This is used to check the
+    /// expiry of mortal transactions, and it is the height that is passed to verifiers, so that
+    /// height-locked inputs are not admitted to the pool before they could actually be spent.
+    fn inclusion_block_height() -> u32 {
+        sp_io::storage::get(HEADER_KEY)
+            .and_then(|d| B::Header::decode(&mut &*d).ok())
+            .map(|header| (*header.number()).unique_saturated_into())
+            .unwrap_or_else(|| {
+                // No block height is stored in the genesis state.
+                let best_height = sp_io::storage::get(BLOCK_HEIGHT_KEY)
+                    .and_then(|d| u32::decode(&mut &*d).ok())
+                    .unwrap_or_default();
+                best_height.saturating_add(1)
+            })
+    }
+
+    /// Record the height of the current block so that it is still known after the block
+    /// when mortal transactions are validated in the pool.
+    fn store_block_height(header: &<B as BlockT>::Header) {
+        let height: u32 = (*header.number()).unique_saturated_into();
+        sp_io::storage::set(BLOCK_HEIGHT_KEY, &height.encode());
     }
 
     // These next three methods are for the block authoring workflow.
@@ -244,6 +426,10 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         // Store the transient partial header for updating at the end of the block.
         // This will be removed from storage before the end of the block.
         sp_io::storage::set(HEADER_KEY, &header.encode());
+        Self::store_block_height(header);
+
+        // Migrations must run identically here and in `try_execute_block`.
+        M::on_block_start();
     }
 
     pub fn apply_extrinsic(extrinsic: <B as BlockT>::Extrinsic) -> ApplyExtrinsicResult {
@@ -260,14 +446,30 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         extrinsics.push(extrinsic.encode());
         sp_io::storage::set(EXTRINSIC_KEY, &extrinsics.encode());
 
+        // Closing inherents are noted for now and executed when the block is closed,
+        // after all user transactions.
+        if Self::is_closing_inherent(&extrinsic) {
+            let mut closing_inherents = Self::noted_closing_inherents();
+            closing_inherents.push(extrinsic);
+            sp_io::storage::set(CLOSING_INHERENTS_KEY, &closing_inherents.encode());
+            return Ok(Ok(()));
+        }
+
         // Now actually
         Self::apply_tuxedo_transaction(extrinsic)
-            .map_err(|_| TransactionValidityError::Invalid(InvalidTransaction::Custom(0)))?;
+            .map_err(|e| TransactionValidityError::Invalid(e.into()))?;
 
         Ok(Ok(()))
     }
 
     pub fn close_block() -> <B as BlockT>::Header {
+        // Execute the closing inherents now that all user transactions have been applied.
+        let closing_inherents = Self::noted_closing_inherents();
+        sp_io::storage::clear(CLOSING_INHERENTS_KEY);
+        for inherent in closing_inherents {
+            Self::apply_closing_inherent(inherent);
+        }
+
         let mut header = sp_io::storage::get(HEADER_KEY)
             .and_then(|d| <B as BlockT>::Header::decode(&mut &*d).ok())
             .expect("We initialized with header, it never got mutated, qed");
@@ -297,7 +499,24 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
 
     // This one is for the Core api. It is used to import blocks authored by foreign nodes.
 
+    /// Execute a block, panicking if it is invalid.
+    ///
+    /// The `Core` runtime API offers no way to return an error, so the typed error from
+    /// `try_execute_block` is logged and then used as the panic message.
     pub fn execute_block(block: B) {
+        if let Err(e) = Self::try_execute_block(block) {
+            log::error!(target: LOG_TARGET, "Block execution failed: {:?}", e);
+            panic!("Block execution failed: {:?}", e);
+        }
+    }
+
+    /// Execute a block, returning a typed error if it is invalid.
+    ///
+    /// This does not roll back storage changes on failure. Callers are expected to discard the
+    /// state when an error is returned, as the block import pipeline does.
+    pub fn try_execute_block(
+        block: B,
+    ) -> Result<(), BlockExecutionError<C::Error, <B as BlockT>::Hash>> {
         debug!(
             target: LOG_TARGET,
             "Entering execute_block. block: {:?}", block
@@ -307,30 +526,51 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         // info, such as the block height, available to individual pieces. This will
         // be cleared before the end of the block
         sp_io::storage::set(HEADER_KEY, &block.header().encode());
+        Self::store_block_height(block.header());
+
+        // Migrations must run identically here and in `open_block`.
+        M::on_block_start();
 
-        // Tuxedo requires that inherents are at the beginning (and soon end) of the
-        // block and not scattered throughout. We use this flag to enforce that.
-        let mut finished_with_opening_inherents = false;
+        // Tuxedo requires that all inherents are at the beginning of the block, opening inherents
+        // before closing inherents, followed by the user transactions. Inherents may never be
+        // scattered throughout the block. We track the current section to enforce that.
+        let mut section = BlockSection::OpeningInherents;
+
+        // Closing inherents are set aside and executed after all user transactions.
+        let mut closing_inherents = Vec::new();
 
         // Apply each extrinsic
-        for extrinsic in block.extrinsics() {
+        for (index, extrinsic) in block.extrinsics().iter().enumerate() {
+            let extrinsic_index = index as u32;
+
             // Enforce that inherents are in the right place
-            let current_tx_is_inherent = extrinsic.checker.is_inherent();
-            if current_tx_is_inherent && finished_with_opening_inherents {
-                panic!("Tried to execute opening inherent after switching to non-inherents.");
-            }
-            if !current_tx_is_inherent && !finished_with_opening_inherents {
-                // This is the first non-inherent, so we update our flag and continue.
-                finished_with_opening_inherents = true;
+            let next_section = if !extrinsic.checker.is_inherent() {
+                BlockSection::UserTransactions
+            } else {
+                match extrinsic.checker.inherent_placement() {
+                    InherentPlacement::Opening => BlockSection::OpeningInherents,
+                    InherentPlacement::Closing => BlockSection::ClosingInherents,
+                }
+            };
+            if next_section < section {
+                return Err(match next_section {
+                    BlockSection::OpeningInherents => {
+                        BlockExecutionError::OpeningInherentOutOfPlace { extrinsic_index }
+                    }
+                    _ => BlockExecutionError::ClosingInherentOutOfPlace { extrinsic_index },
+                });
             }
+            section = next_section;
 
-            match Self::apply_tuxedo_transaction(extrinsic.clone()) {
-                Ok(()) => debug!(
-                    target: LOG_TARGET,
-                    "Successfully executed extrinsic: {:?}", extrinsic
-                ),
-                Err(e) => panic!("{:?}", e),
+            if section == BlockSection::ClosingInherents {
+                closing_inherents.push(extrinsic);
+                continue;
             }
+            Self::execute_extrinsic(extrinsic_index, extrinsic)?;
+        }
+
+        for extrinsic in closing_inherents {
+            Self::apply_closing_inherent(extrinsic.clone());
         }
 
         // Clear the transient header out of storage
@@ -340,10 +580,12 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         let raw_state_root = &sp_io::storage::root(StateVersion::V1)[..];
         let state_root =
             <<B as BlockT>::Header as HeaderT>::Hash::decode(&mut &raw_state_root[..]).unwrap();
-        assert_eq!(
-            *block.header().state_root(),
-            state_root,
-            "state root mismatch"
+        ensure!(
+            *block.header().state_root() == state_root,
+            BlockExecutionError::StateRootMismatch {
+                expected: *block.header().state_root(),
+                actual: state_root,
+            }
         );
 
         // Print state for quick debugging
@@ -369,11 +611,59 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
             extrinsics,
             StateVersion::V0,
         );
-        assert_eq!(
-            *block.header().extrinsics_root(),
-            extrinsics_root,
-            "extrinsics root mismatch"
+        ensure!(
+            *block.header().extrinsics_root() == extrinsics_root,
+            BlockExecutionError::ExtrinsicsRootMismatch {
+                expected: *block.header().extrinsics_root(),
+                actual: extrinsics_root,
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Apply one of the extrinsics of a block that is being executed.
+    fn execute_extrinsic(
+        extrinsic_index: u32,
+        extrinsic: &Transaction<V, C>,
+    ) -> Result<(), BlockExecutionError<C::Error, <B as BlockT>::Hash>> {
+        Self::apply_tuxedo_transaction(extrinsic.clone()).map_err(|error| {
+            BlockExecutionError::ExtrinsicFailed {
+                extrinsic_index,
+                error,
+            }
+        })?;
+        debug!(
+            target: LOG_TARGET,
+            "Successfully executed extrinsic: {:?}", extrinsic
         );
+        Ok(())
+    }
+
+    /// Apply a closing inherent at the end of the block.
+    ///
+    /// Closing inherents are created before the user transactions of their block, which may have
+    /// consumed the state they expected by the time they run. The BlockBuilder runtime API cannot
+    /// return an error, so instead of making the block impossible to author, a failed closing
+    /// inherent is logged and skipped. It stays in the block body without affecting state, and
+    /// importing nodes skip it in the same way.
+    fn apply_closing_inherent(inherent: Transaction<V, C>) {
+        if let Err(e) = Self::apply_tuxedo_transaction(inherent) {
+            log::warn!(target: LOG_TARGET, "Skipping failed closing inherent: {:?}", e);
+        }
+    }
+
+    /// Whether the transaction is an inherent that is executed at the end of the block.
+    fn is_closing_inherent(transaction: &Transaction<V, C>) -> bool {
+        transaction.checker.is_inherent()
+            && transaction.checker.inherent_placement() == InherentPlacement::Closing
+    }
+
+    /// The closing inherents that have been applied so far while authoring the current block.
+    fn noted_closing_inherents() -> Vec<Transaction<V, C>> {
+        sp_io::storage::get(CLOSING_INHERENTS_KEY)
+            .and_then(|d| <Vec<Transaction<V, C>>>::decode(&mut &*d).ok())
+            .unwrap_or_default()
     }
 
     // This one is the pool api. It is used to make preliminary checks in the transaction pool
@@ -398,16 +688,15 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         let r = if tx.checker.is_inherent() {
             Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
         } else {
-            // TODO, we need a good way to map our UtxoError into the supposedly generic InvalidTransaction
-            // https://paritytech.github.io/substrate/master/sp_runtime/transaction_validity/enum.InvalidTransaction.html
-            // For now, I just make them all custom zero, and log the error variant
+            // The full error is logged, and a structured version of it is reported to the pool.
+            // See the `From<UtxoError>` impl for details on how the mapping works.
             Self::validate_tuxedo_transaction(&tx).map_err(|e| {
                 log::warn!(
                     target: LOG_TARGET,
                     "Tuxedo Transaction did not validate (in the pool): {:?}",
                     e,
                 );
-                TransactionValidityError::Invalid(InvalidTransaction::Custom(0))
+                TransactionValidityError::Invalid(e.into())
             })
         };
 
@@ -416,7 +705,14 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         r
     }
 
-    // The next two are for the standard beginning-of-block inherent extrinsics.
+    // The next two are for the standard inherent extrinsics.
+
+    /// Create the inherent extrinsics for a block that is being authored locally.
+    ///
+    /// The returned extrinsics are in block order: all opening inherents come first, followed by
+    /// all closing inherents. The block builder applies them all before any user transactions, as
+    /// the standard proposer does. Closing inherents are nonetheless only executed once the block
+    /// is closed, after all user transactions.
     pub fn inherent_extrinsics(data: sp_inherents::InherentData) -> Vec<<B as BlockT>::Extrinsic> {
         debug!(
             target: LOG_TARGET,
@@ -430,20 +726,18 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
             .expect("Parent block should be present among authoring inherent data.");
 
         // Extract the inherents from the previous block, which can be found at the beginning of the extrinsics list.
-        // The parent is already imported, so we know it is valid and we know its inherents came first.
+        // The parent is already imported, so we know it is valid and we know its inherents are in the right places.
         // We also annotate each transaction with its original hash for purposes of constructing output refs later.
         // This is necessary because the transaction hash changes as we unwrap layers of aggregation,
         // and we need an original universal transaction id.
-        let previous_blocks_inherents: Vec<(<B as BlockT>::Extrinsic, H256)> = parent
-            .extrinsics()
-            .iter()
-            .cloned()
-            .take_while(|tx| tx.checker.is_inherent())
-            .map(|tx| {
-                let id = BlakeTwo256::hash_of(&tx.encode());
-                (tx, id)
-            })
-            .collect();
+        let previous_blocks_inherents: Vec<(<B as BlockT>::Extrinsic, H256)> =
+            Self::block_inherents(parent.extrinsics())
+                .into_iter()
+                .map(|tx| {
+                    let id = BlakeTwo256::hash_of(&tx.encode());
+                    (tx, id)
+                })
+                .collect();
 
         debug!(
             target: LOG_TARGET,
@@ -451,7 +745,18 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
         );
 
         // Call into constraint checker's own inherent hooks to create the actual transactions
-        C::InherentHooks::create_inherents(&data, previous_blocks_inherents)
+        // The BlockBuilder runtime API cannot return an error, so a failure here is fatal to authoring.
+        let inherents = C::InherentHooks::create_inherents(&data, previous_blocks_inherents)
+            .unwrap_or_else(|e| {
+                log::error!(target: LOG_TARGET, "Failed to create inherents: {:?}", e);
+                panic!("Failed to create inherents: {:?}", e)
+            });
+
+        // Put the opening inherents first and the closing inherents last.
+        let (opening, closing): (Vec<_>, Vec<_>) = inherents
+            .into_iter()
+            .partition(|tx| tx.checker.inherent_placement() == InherentPlacement::Opening);
+        opening.into_iter().chain(closing).collect()
     }
 
     pub fn check_inherents(block: B, data: InherentData) -> sp_inherents::CheckInherentsResult {
@@ -462,34 +767,38 @@ impl<B: BlockT<Extrinsic = Transaction<V, C>>, V: Verifier, C: ConstraintChecker
 
         let mut result = CheckInherentsResult::new();
 
-        // Tuxedo requires that all inherents come at the beginning of the block.
-        // (Soon we will also allow them at the end, but never throughout the body.)
-        // (TODO revise this logic once that is implemented.)
+        // Tuxedo requires that inherents come at the beginning of the block, but never throughout the body.
         // At this off-chain pre-check stage, we assume that requirement is upheld.
         // It will be verified later once we are executing on-chain.
-        let inherents: Vec<Transaction<V, C>> = block
-            .extrinsics()
-            .iter()
-            .cloned()
-            .take_while(|tx| tx.checker.is_inherent())
-            .collect();
+        let inherents = Self::block_inherents(block.extrinsics());
 
         C::InherentHooks::check_inherents(&data, inherents, &mut result);
 
         result
     }
+
+    /// Collect the inherents from a block's extrinsics, in block order.
+    /// Both opening and closing inherents are found at the beginning of the block.
+    fn block_inherents(extrinsics: &[Transaction<V, C>]) -> Vec<Transaction<V, C>> {
+        extrinsics
+            .iter()
+            .take_while(|tx| tx.checker.is_inherent())
+            .cloned()
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use sp_core::H256;
     use sp_io::TestExternalities;
-    use sp_runtime::transaction_validity::ValidTransactionBuilder;
+    use sp_runtime::transaction_validity::{TransactionPriority, ValidTransactionBuilder};
 
     use crate::{
         constraint_checker::testing::TestConstraintChecker,
         dynamic_typing::{testing::Bogus, UtxoData},
         types::{Input, Output},
+        utxo_set::{utxo_storage_key, ChildTrieUtxoSet},
         verifier::TestVerifier,
     };
 
@@ -500,6 +809,56 @@ mod tests {
     pub type TestBlock = sp_runtime::generic::Block<TestHeader, TestTransaction>;
     pub type TestExecutive = Executive<TestBlock, TestVerifier, TestConstraintChecker>;
 
+    /// A priority policy that adds a bonus for each peek on top of the checker's priority.
+    /// Used to make sure the executive consults the configured policy.
+    struct PeekBonusPolicy;
+
+    impl PriorityPolicy<TestVerifier, TestConstraintChecker> for PeekBonusPolicy {
+        fn priority(
+            transaction: &TestTransaction,
+            checker_priority: TransactionPriority,
+        ) -> TransactionPriority {
+            checker_priority + 10 * transaction.peeks.len() as TransactionPriority
+        }
+    }
+
+    type PeekBonusExecutive =
+        Executive<TestBlock, TestVerifier, TestConstraintChecker, PeekBonusPolicy>;
+
+    type ChildTrieExecutive = Executive<
+        TestBlock,
+        TestVerifier,
+        TestConstraintChecker,
+        (),
+        ChildTrieUtxoSet<TestVerifier>,
+    >;
+
+    /// A migration that counts the blocks it has run in, under the key `b"migrated_blocks"`.
+    /// Used to make sure the executive runs migrations when authoring and when importing.
+    struct CountingMigration;
+
+    impl UtxoMigration for CountingMigration {
+        fn on_block_start() {
+            let count = sp_io::storage::get(b"migrated_blocks")
+                .and_then(|d| u32::decode(&mut &*d).ok())
+                .unwrap_or_default();
+            sp_io::storage::set(b"migrated_blocks", &(count + 1).encode());
+        }
+    }
+
+    type MigratingExecutive = Executive<
+        TestBlock,
+        TestVerifier,
+        TestConstraintChecker,
+        (),
+        TransparentUtxoSet<TestVerifier>,
+        CountingMigration,
+    >;
+
+    fn migrated_blocks() -> Option<u32> {
+        sp_io::storage::get(b"migrated_blocks").and_then(|d| u32::decode(&mut &*d).ok())
+    }
+
     /// Construct a mock OutputRef from a transaction number and index in that transaction.
     ///
     /// When setting up tests, it is often useful to have some Utxos in the storage
@@ -520,6 +879,8 @@ mod tests {
         inputs: Vec<Input>,
         peeks: Vec<OutputRef>,
         outputs: Vec<Output<TestVerifier>>,
+        evictions: Vec<OutputRef>,
+        valid_until: Option<u32>,
     }
 
     impl TestTransactionBuilder {
@@ -538,14 +899,37 @@ mod tests {
             self
         }
 
+        fn with_eviction(mut self, eviction: OutputRef) -> Self {
+            self.evictions.push(eviction);
+            self
+        }
+
+        fn valid_until(mut self, height: u32) -> Self {
+            self.valid_until = Some(height);
+            self
+        }
+
         fn build(self, checks: bool, inherent: bool) -> TestTransaction {
             TestTransaction {
                 inputs: self.inputs,
                 peeks: self.peeks,
                 outputs: self.outputs,
-                checker: TestConstraintChecker { checks, inherent },
+                checker: TestConstraintChecker {
+                    checks,
+                    inherent,
+                    closing: false,
+                    evicts: !self.evictions.is_empty(),
+                },
+                evictions: self.evictions,
+                valid_until: self.valid_until,
             }
         }
+
+        fn build_closing_inherent(self) -> TestTransaction {
+            let mut transaction = self.build(true, true);
+            transaction.checker.closing = true;
+            transaction
+        }
     }
 
     /// Builds test externalities using a minimal builder pattern.
@@ -553,6 +937,7 @@ mod tests {
     struct ExternalityBuilder {
         utxos: Vec<(OutputRef, Output<TestVerifier>)>,
         pre_header: Option<TestHeader>,
+        block_height: Option<u32>,
         noted_extrinsics: Vec<Vec<u8>>,
     }
 
@@ -605,6 +990,15 @@ mod tests {
             self
         }
 
+        /// Store the height of the most recent block, as if it had already been executed.
+        ///
+        /// This is the situation in the transaction pool, where there is no pre-header,
+        /// but the height of the block being built upon is known.
+        fn with_block_height(mut self, height: u32) -> Self {
+            self.block_height = Some(height);
+            self
+        }
+
         /// Add a noted extrinsic to the state.
         ///
         /// In normal block authoring, extrinsics are noted in state as they are
@@ -625,7 +1019,7 @@ mod tests {
 
             // Write all the utxos
             for (output_ref, output) in self.utxos {
-                ext.insert(output_ref.encode(), output.encode());
+                ext.insert(utxo_storage_key(&output_ref), output.encode());
             }
 
             // Write the pre-header
@@ -633,6 +1027,11 @@ mod tests {
                 ext.insert(HEADER_KEY.to_vec(), pre_header.encode());
             }
 
+            // Write the block height
+            if let Some(height) = self.block_height {
+                ext.insert(BLOCK_HEIGHT_KEY.to_vec(), height.encode());
+            }
+
             // Write the noted extrinsics
             ext.insert(EXTRINSIC_KEY.to_vec(), self.noted_extrinsics.encode());
 
@@ -696,6 +1095,26 @@ mod tests {
             });
     }
 
+    #[test]
+    fn validate_uses_priority_policy() {
+        let output_ref = mock_output_ref(0, 0);
+
+        ExternalityBuilder::default()
+            .with_utxo(output_ref.clone(), Bogus, true)
+            .build()
+            .execute_with(|| {
+                let tx = TestTransactionBuilder::default()
+                    .with_peek(output_ref)
+                    .build(true, false);
+
+                let vt = PeekBonusExecutive::validate_tuxedo_transaction(&tx).unwrap();
+
+                let expected_result = ValidTransactionBuilder::default().priority(10).into();
+
+                assert_eq!(vt, expected_result);
+            });
+    }
+
     #[test]
     fn validate_with_output_works() {
         ExternalityBuilder::default().build().execute_with(|| {
@@ -811,6 +1230,107 @@ mod tests {
             });
     }
 
+    #[test]
+    fn validate_with_eviction_skips_verifier() {
+        let output_ref = mock_output_ref(0, 0);
+
+        ExternalityBuilder::default()
+            .with_utxo(output_ref.clone(), Bogus, false)
+            .build()
+            .execute_with(|| {
+                let tx = TestTransactionBuilder::default()
+                    .with_eviction(output_ref)
+                    .build(true, false);
+
+                let vt = TestExecutive::validate_tuxedo_transaction(&tx).unwrap();
+
+                let expected_result = ValidTransactionBuilder::default().into();
+
+                assert_eq!(vt, expected_result);
+            });
+    }
+
+    #[test]
+    fn validate_with_eviction_not_allowed_fails() {
+        let output_ref = mock_output_ref(0, 0);
+
+        ExternalityBuilder::default()
+            .with_utxo(output_ref.clone(), Bogus, true)
+            .build()
+            .execute_with(|| {
+                let mut tx = TestTransactionBuilder::default()
+                    .with_eviction(output_ref)
+                    .build(true, false);
+                tx.checker.evicts = false;
+
+                let vt = TestExecutive::validate_tuxedo_transaction(&tx);
+
+                assert_eq!(vt, Err(UtxoError::EvictionsNotAllowed));
+            });
+    }
+
+    #[test]
+    fn validate_with_missing_eviction_works() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let output_ref = mock_output_ref(0, 0);
+
+            let tx = TestTransactionBuilder::default()
+                .with_eviction(output_ref.clone())
+                .build(true, false);
+
+            let vt = TestExecutive::validate_tuxedo_transaction(&tx).unwrap();
+
+            let expected_result = ValidTransactionBuilder::default()
+                .and_requires(output_ref)
+                .into();
+
+            assert_eq!(vt, expected_result);
+        });
+    }
+
+    #[test]
+    fn validate_with_duplicate_eviction_fails() {
+        let output_ref = mock_output_ref(0, 0);
+
+        ExternalityBuilder::default()
+            .with_utxo(output_ref.clone(), Bogus, false)
+            .build()
+            .execute_with(|| {
+                let tx = TestTransactionBuilder::default()
+                    .with_eviction(output_ref.clone())
+                    .with_eviction(output_ref)
+                    .build(true, false);
+
+                let result = TestExecutive::validate_tuxedo_transaction(&tx);
+
+                assert_eq!(result, Err(UtxoError::DuplicateInput));
+            });
+    }
+
+    #[test]
+    fn validate_with_eviction_of_input_fails() {
+        let output_ref = mock_output_ref(0, 0);
+
+        ExternalityBuilder::default()
+            .with_utxo(output_ref.clone(), Bogus, true)
+            .build()
+            .execute_with(|| {
+                let input = Input {
+                    output_ref: output_ref.clone(),
+                    redeemer: Vec::new(),
+                };
+
+                let tx = TestTransactionBuilder::default()
+                    .with_input(input)
+                    .with_eviction(output_ref)
+                    .build(true, false);
+
+                let result = TestExecutive::validate_tuxedo_transaction(&tx);
+
+                assert_eq!(result, Err(UtxoError::DuplicateInput));
+            });
+    }
+
     #[test]
     fn validate_with_unsatisfied_verifier_fails() {
         let output_ref = mock_output_ref(0, 0);
@@ -875,14 +1395,117 @@ mod tests {
     }
 
     #[test]
-    fn apply_empty_works() {
-        ExternalityBuilder::default().build().execute_with(|| {
-            let tx = TestTransactionBuilder::default().build(true, false);
-
-            let vt = TestExecutive::apply_tuxedo_transaction(tx);
+    fn validate_mortal_transaction_sets_longevity() {
+        ExternalityBuilder::default()
+            .with_block_height(10)
+            .build()
+            .execute_with(|| {
+                // The earliest the transaction could be included is block 11, so it may be
+                // included in blocks 11 through 14.
+                let tx = TestTransactionBuilder::default()
+                    .valid_until(14)
+                    .build(true, false);
+                let vt = TestExecutive::validate_tuxedo_transaction(&tx).unwrap();
 
-            assert_eq!(vt, Ok(()));
-        });
+                let expected_result = ValidTransactionBuilder::default().longevity(4).into();
+                assert_eq!(vt, expected_result);
+            });
+    }
+
+    #[test]
+    fn validate_mortal_transaction_with_missing_input_sets_longevity() {
+        ExternalityBuilder::default()
+            .with_block_height(10)
+            .build()
+            .execute_with(|| {
+                let output_ref = mock_output_ref(0, 0);
+                let tx = TestTransactionBuilder::default()
+                    .with_input(Input {
+                        output_ref: output_ref.clone(),
+                        redeemer: Vec::new(),
+                    })
+                    .valid_until(11)
+                    .build(true, false);
+                let vt = TestExecutive::validate_tuxedo_transaction(&tx).unwrap();
+
+                let expected_result = ValidTransactionBuilder::default()
+                    .and_requires(output_ref)
+                    .longevity(1)
+                    .into();
+                assert_eq!(vt, expected_result);
+            });
+    }
+
+    #[test]
+    fn validate_expired_transaction_fails() {
+        ExternalityBuilder::default()
+            .with_block_height(10)
+            .build()
+            .execute_with(|| {
+                let tx = TestTransactionBuilder::default()
+                    .valid_until(10)
+                    .build(true, false);
+                let result = TestExecutive::validate_tuxedo_transaction(&tx);
+
+                assert_eq!(result, Err(UtxoError::Expired));
+            });
+    }
+
+    #[test]
+    fn validate_mortal_transaction_in_genesis_state_works() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            // No block has been executed, so the transaction could be included in block 1.
+            let tx = TestTransactionBuilder::default()
+                .valid_until(1)
+                .build(true, false);
+            let vt = TestExecutive::validate_tuxedo_transaction(&tx).unwrap();
+
+            let expected_result = ValidTransactionBuilder::default().longevity(1).into();
+            assert_eq!(vt, expected_result);
+        });
+    }
+
+    #[test]
+    fn validate_transaction_reports_bad_proof_to_pool() {
+        let output_ref = mock_output_ref(0, 0);
+
+        ExternalityBuilder::default()
+            .with_utxo(output_ref.clone(), Bogus, false)
+            .build()
+            .execute_with(|| {
+                let input = Input {
+                    output_ref,
+                    redeemer: Vec::new(),
+                };
+
+                let tx = TestTransactionBuilder::default()
+                    .with_input(input)
+                    .build(true, false);
+
+                let result = TestExecutive::validate_transaction(
+                    TransactionSource::External,
+                    tx,
+                    H256::zero(),
+                );
+
+                assert_eq!(
+                    result,
+                    Err(TransactionValidityError::Invalid(
+                        InvalidTransaction::BadProof
+                    ))
+                );
+            });
+    }
+
+    #[test]
+    fn apply_empty_works() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let tx = TestTransactionBuilder::default().build(true, false);
+
+            let vt = TestExecutive::apply_tuxedo_transaction(tx);
+
+            assert_eq!(vt, Ok(()));
+        });
     }
 
     #[test]
@@ -919,6 +1542,112 @@ mod tests {
         });
     }
 
+    #[test]
+    fn apply_mortal_transaction_in_last_valid_block_works() {
+        ExternalityBuilder::default()
+            .with_pre_header(H256::zero(), 6)
+            .build()
+            .execute_with(|| {
+                let tx = TestTransactionBuilder::default()
+                    .valid_until(6)
+                    .build(true, false);
+                let vt = TestExecutive::apply_tuxedo_transaction(tx);
+
+                assert_eq!(vt, Ok(()));
+            });
+    }
+
+    #[test]
+    fn apply_expired_transaction_fails() {
+        ExternalityBuilder::default()
+            .with_pre_header(H256::zero(), 6)
+            .build()
+            .execute_with(|| {
+                let tx = TestTransactionBuilder::default()
+                    .valid_until(5)
+                    .build(true, false);
+                let vt = TestExecutive::apply_tuxedo_transaction(tx);
+
+                assert_eq!(vt, Err(UtxoError::Expired));
+            });
+    }
+
+    #[test]
+    fn dry_run_reports_resolved_utxos() {
+        let input_ref = mock_output_ref(0, 0);
+        let peek_ref = mock_output_ref(0, 1);
+        let output = Output {
+            payload: Bogus.into(),
+            verifier: TestVerifier { verifies: false },
+        };
+
+        ExternalityBuilder::default()
+            .with_utxo(input_ref.clone(), Bogus, true)
+            .with_utxo(peek_ref.clone(), Bogus, false)
+            .build()
+            .execute_with(|| {
+                let input = Input {
+                    output_ref: input_ref,
+                    redeemer: Vec::new(),
+                };
+
+                let tx = TestTransactionBuilder::default()
+                    .with_input(input)
+                    .with_peek(peek_ref)
+                    .with_output(output)
+                    .build(true, false);
+                let tx_hash = BlakeTwo256::hash_of(&tx.encode());
+
+                let report = TestExecutive::dry_run_transaction(&tx).unwrap();
+
+                let expected_report = DryRunReport {
+                    inputs: vec![Output {
+                        payload: Bogus.into(),
+                        verifier: TestVerifier { verifies: true },
+                    }],
+                    peeks: vec![Output {
+                        payload: Bogus.into(),
+                        verifier: TestVerifier { verifies: false },
+                    }],
+                    evictions: Vec::new(),
+                    valid_until: None,
+                    priority: 0,
+                    created: vec![OutputRef { tx_hash, index: 0 }],
+                };
+
+                assert_eq!(report, expected_report);
+            });
+    }
+
+    #[test]
+    fn dry_run_with_missing_input_fails() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let input = Input {
+                output_ref: mock_output_ref(0, 0),
+                redeemer: Vec::new(),
+            };
+
+            let tx = TestTransactionBuilder::default()
+                .with_input(input)
+                .build(true, false);
+
+            let result = TestExecutive::dry_run_transaction(&tx);
+
+            assert_eq!(result, Err(UtxoError::MissingInput));
+        });
+    }
+
+    #[test]
+    fn dry_run_with_constraint_error_fails() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let tx = TestTransactionBuilder::default().build(false, false);
+
+            let result = TestExecutive::dry_run_transaction(&tx);
+
+            assert_eq!(result, Err(UtxoError::ConstraintCheckerError(())));
+        });
+    }
+
     #[test]
     fn update_storage_consumes_input() {
         let output_ref = mock_output_ref(0, 0);
@@ -940,7 +1669,27 @@ mod tests {
                 TestExecutive::update_storage(tx);
 
                 // Check whether the Input is still in storage
-                assert!(!sp_io::storage::exists(&output_ref.encode()));
+                assert!(!sp_io::storage::exists(&utxo_storage_key(&output_ref)));
+            });
+    }
+
+    #[test]
+    fn update_storage_consumes_eviction() {
+        let output_ref = mock_output_ref(0, 0);
+
+        ExternalityBuilder::default()
+            .with_utxo(output_ref.clone(), Bogus, false)
+            .build()
+            .execute_with(|| {
+                let tx = TestTransactionBuilder::default()
+                    .with_eviction(output_ref.clone())
+                    .build(true, false);
+
+                // Commit the tx to storage
+                TestExecutive::update_storage(tx);
+
+                // Check whether the evicted utxo is still in storage
+                assert!(!sp_io::storage::exists(&utxo_storage_key(&output_ref)));
             });
     }
 
@@ -963,12 +1712,66 @@ mod tests {
             TestExecutive::update_storage(tx);
 
             // Check whether the Output has been written to storage and the proper value is stored
-            let stored_bytes = sp_io::storage::get(&output_ref.encode()).unwrap();
+            let stored_bytes = sp_io::storage::get(&utxo_storage_key(&output_ref)).unwrap();
             let stored_value = Output::decode(&mut &stored_bytes[..]).unwrap();
             assert_eq!(stored_value, output);
         });
     }
 
+    #[test]
+    fn apply_transaction_uses_configured_utxo_set() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let input_ref = mock_output_ref(0, 0);
+            let input_utxo = Output {
+                payload: Bogus.into(),
+                verifier: TestVerifier { verifies: true },
+            };
+            ChildTrieUtxoSet::<TestVerifier>::store_utxo(input_ref.clone(), &input_utxo);
+
+            let output = Output {
+                payload: Bogus.into(),
+                verifier: TestVerifier { verifies: false },
+            };
+            let tx = TestTransactionBuilder::default()
+                .with_input(Input {
+                    output_ref: input_ref.clone(),
+                    redeemer: Vec::new(),
+                })
+                .with_output(output.clone())
+                .build(true, false);
+            let tx_hash = BlakeTwo256::hash_of(&tx.encode());
+            let output_ref = OutputRef { tx_hash, index: 0 };
+
+            assert_eq!(ChildTrieExecutive::apply_tuxedo_transaction(tx), Ok(()));
+
+            // The input was consumed and the output was stored in the child trie
+            assert_eq!(
+                ChildTrieUtxoSet::<TestVerifier>::peek_utxo(&input_ref),
+                None
+            );
+            assert_eq!(
+                ChildTrieUtxoSet::<TestVerifier>::peek_utxo(&output_ref),
+                Some(output)
+            );
+            assert!(!sp_io::storage::exists(&utxo_storage_key(&output_ref)));
+        });
+    }
+
+    #[test]
+    fn peek_utxo_uses_configured_utxo_set() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let output_ref = mock_output_ref(0, 0);
+            let output = Output {
+                payload: Bogus.into(),
+                verifier: TestVerifier { verifies: true },
+            };
+            ChildTrieUtxoSet::<TestVerifier>::store_utxo(output_ref.clone(), &output);
+
+            assert_eq!(ChildTrieExecutive::peek_utxo(&output_ref), Some(output));
+            assert_eq!(TestExecutive::peek_utxo(&output_ref), None);
+        });
+    }
+
     #[test]
     fn open_block_works() {
         let header = TestHeader {
@@ -990,6 +1793,50 @@ mod tests {
 
             // Make sure the header that came out is the same one that went in.
             assert_eq!(retrieved_header, header);
+
+            // Make sure the block height is recorded for after the block
+            assert_eq!(
+                sp_io::storage::get(BLOCK_HEIGHT_KEY).map(|d| d.to_vec()),
+                Some(5u32.encode())
+            );
+        });
+    }
+
+    #[test]
+    fn open_block_runs_migrations() {
+        let header = TestHeader {
+            parent_hash: H256::repeat_byte(5),
+            number: 5,
+            state_root: H256::repeat_byte(6),
+            extrinsics_root: H256::repeat_byte(7),
+            digest: Default::default(),
+        };
+
+        ExternalityBuilder::default().build().execute_with(|| {
+            MigratingExecutive::open_block(&header);
+
+            assert_eq!(migrated_blocks(), Some(1));
+        });
+    }
+
+    #[test]
+    fn execute_block_runs_migrations() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let b = TestBlock {
+                header: TestHeader {
+                    parent_hash: H256::zero(),
+                    number: 6,
+                    state_root: H256::zero(),
+                    extrinsics_root: H256::zero(),
+                    digest: Default::default(),
+                },
+                extrinsics: Vec::new(),
+            };
+
+            // The roots are wrong, but the migration runs before they are checked.
+            let _ = MigratingExecutive::try_execute_block(b);
+
+            assert_eq!(migrated_blocks(), Some(1));
         });
     }
 
@@ -1019,8 +1866,13 @@ mod tests {
 
             let apply_result = TestExecutive::apply_extrinsic(tx.clone());
 
-            // Make sure the returned result is an error
-            assert!(apply_result.is_err());
+            // Make sure the returned result is an error reporting the constraint checker failure
+            assert_eq!(
+                apply_result,
+                Err(TransactionValidityError::Invalid(
+                    InvalidTransaction::Custom(0)
+                ))
+            );
 
             // TODO Do we actually want to note transactions that ultimately reject?
             // Make sure the transaction is noted in storage
@@ -1074,7 +1926,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: array_bytes::hex_n_into_unchecked(
                         "03170a2e7597b7b7e3d84c05391d139a62b157e78786d8c082f29dcf4c111314",
@@ -1096,7 +1948,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: array_bytes::hex_n_into_unchecked(
                         "d609af1c51521f5891054014cf667619067a93f4bca518b398f5a39aeb270cca",
@@ -1111,7 +1963,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "ConstraintCheckerError(())")]
     fn execute_block_invalid_transaction() {
         ExternalityBuilder::default().build().execute_with(|| {
             let b = TestBlock {
@@ -1119,7 +1970,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: array_bytes::hex_n_into_unchecked(
                         "03170a2e7597b7b7e3d84c05391d139a62b157e78786d8c082f29dcf4c111314",
@@ -1129,12 +1980,17 @@ mod tests {
                 extrinsics: vec![TestTransactionBuilder::default().build(false, false)],
             };
 
-            TestExecutive::execute_block(b);
+            assert_eq!(
+                TestExecutive::try_execute_block(b),
+                Err(BlockExecutionError::ExtrinsicFailed {
+                    extrinsic_index: 0,
+                    error: UtxoError::ConstraintCheckerError(()),
+                })
+            );
         });
     }
 
     #[test]
-    #[should_panic(expected = "state root mismatch")]
     fn execute_block_state_root_mismatch() {
         ExternalityBuilder::default().build().execute_with(|| {
             let b = TestBlock {
@@ -1150,12 +2006,19 @@ mod tests {
                 extrinsics: Vec::new(),
             };
 
-            TestExecutive::execute_block(b);
+            assert_eq!(
+                TestExecutive::try_execute_block(b),
+                Err(BlockExecutionError::StateRootMismatch {
+                    expected: H256::zero(),
+                    actual: array_bytes::hex_n_into_unchecked(
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
+                    ),
+                })
+            );
         });
     }
 
     #[test]
-    #[should_panic(expected = "extrinsics root mismatch")]
     fn execute_block_extrinsic_root_mismatch() {
         ExternalityBuilder::default().build().execute_with(|| {
             let b = TestBlock {
@@ -1163,7 +2026,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: H256::zero(),
                     digest: Default::default(),
@@ -1171,6 +2034,35 @@ mod tests {
                 extrinsics: Vec::new(),
             };
 
+            assert_eq!(
+                TestExecutive::try_execute_block(b),
+                Err(BlockExecutionError::ExtrinsicsRootMismatch {
+                    expected: H256::zero(),
+                    actual: array_bytes::hex_n_into_unchecked(
+                        "03170a2e7597b7b7e3d84c05391d139a62b157e78786d8c082f29dcf4c111314",
+                    ),
+                })
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Block execution failed: StateRootMismatch")]
+    fn execute_block_panics_with_typed_error() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let b = TestBlock {
+                header: TestHeader {
+                    parent_hash: H256::zero(),
+                    number: 6,
+                    state_root: H256::zero(),
+                    extrinsics_root: array_bytes::hex_n_into_unchecked(
+                        "03170a2e7597b7b7e3d84c05391d139a62b157e78786d8c082f29dcf4c111314",
+                    ),
+                    digest: Default::default(),
+                },
+                extrinsics: Vec::new(),
+            };
+
             TestExecutive::execute_block(b);
         });
     }
@@ -1183,7 +2075,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: array_bytes::hex_n_into_unchecked(
                         "799fc6d36f68fc83ae3408de607006e02836181e91701aa3a8021960b1f3507c",
@@ -1205,7 +2097,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: array_bytes::hex_n_into_unchecked(
                         "bf3e98799022bee8f0a55659af5f498717736ae012d2aff6274cdb7c2b0d78e9",
@@ -1223,9 +2115,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "Tried to execute opening inherent after switching to non-inherents."
-    )]
     fn execute_block_inherents_must_be_first() {
         ExternalityBuilder::default().build().execute_with(|| {
             let b = TestBlock {
@@ -1233,7 +2122,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: array_bytes::hex_n_into_unchecked(
                         "df64890515cd8ef5a8e736248394f7c72a1df197bd400a4e31affcaf6e051984",
@@ -1246,14 +2135,14 @@ mod tests {
                 ],
             };
 
-            TestExecutive::execute_block(b);
+            assert_eq!(
+                TestExecutive::try_execute_block(b),
+                Err(BlockExecutionError::OpeningInherentOutOfPlace { extrinsic_index: 1 })
+            );
         });
     }
 
     #[test]
-    #[should_panic(
-        expected = "Tried to execute opening inherent after switching to non-inherents."
-    )]
     fn execute_block_inherents_must_all_be_first() {
         ExternalityBuilder::default().build().execute_with(|| {
             let b = TestBlock {
@@ -1261,7 +2150,7 @@ mod tests {
                     parent_hash: H256::zero(),
                     number: 6,
                     state_root: array_bytes::hex_n_into_unchecked(
-                        "858174d563f845dbb4959ea64816bd8409e48cc7e65db8aa455bc98d61d24071",
+                        "6cd7bdf992c939a4d9380fbed024644426749f5065d4b5cdd9a6338eef08d438",
                     ),
                     extrinsics_root: array_bytes::hex_n_into_unchecked(
                         "0x36601deae36de127b974e8498e118e348a50aa4aa94bc5713e29c56e0d37e44f",
@@ -1275,7 +2164,205 @@ mod tests {
                 ],
             };
 
+            assert_eq!(
+                TestExecutive::try_execute_block(b),
+                Err(BlockExecutionError::OpeningInherentOutOfPlace { extrinsic_index: 2 })
+            );
+        });
+    }
+
+    /// Author a block containing the given extrinsics, so that its roots need not be hardcoded.
+    fn author_block(extrinsics: Vec<TestTransaction>) -> TestBlock {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let pre_header = TestHeader {
+                parent_hash: H256::zero(),
+                number: 6,
+                state_root: H256::zero(),
+                extrinsics_root: H256::zero(),
+                digest: Default::default(),
+            };
+            TestExecutive::open_block(&pre_header);
+            for extrinsic in extrinsics.iter() {
+                TestExecutive::apply_extrinsic(extrinsic.clone())
+                    .unwrap()
+                    .unwrap();
+            }
+            TestBlock {
+                header: TestExecutive::close_block(),
+                extrinsics,
+            }
+        })
+    }
+
+    #[test]
+    fn execute_block_closing_inherent_after_opening_inherent_works() {
+        let b = author_block(vec![
+            TestTransactionBuilder::default().build(true, true),
+            TestTransactionBuilder::default().build_closing_inherent(),
+            TestTransactionBuilder::default().build(true, false),
+        ]);
+
+        // Authoring clears the noted extrinsics, so execute the block in fresh externalities.
+        TestExternalities::default().execute_with(|| {
+            TestExecutive::execute_block(b);
+        });
+    }
+
+    #[test]
+    fn execute_block_closing_inherent_only_works() {
+        let b = author_block(vec![
+            TestTransactionBuilder::default().build_closing_inherent()
+        ]);
+
+        // Authoring clears the noted extrinsics, so execute the block in fresh externalities.
+        TestExternalities::default().execute_with(|| {
+            TestExecutive::execute_block(b);
+        });
+    }
+
+    #[test]
+    fn closing_inherent_executes_after_user_transactions() {
+        // The user transaction creates an output that the closing inherent, which comes earlier
+        // in the block body, consumes.
+        let user_transaction = TestTransactionBuilder::default()
+            .with_output((Bogus, TestVerifier { verifies: true }).into())
+            .build(true, false);
+        let created = OutputRef {
+            tx_hash: BlakeTwo256::hash_of(&user_transaction.encode()),
+            index: 0,
+        };
+        let closing_inherent = TestTransactionBuilder::default()
+            .with_input(Input {
+                output_ref: created.clone(),
+                redeemer: Vec::new(),
+            })
+            .build_closing_inherent();
+
+        let b = author_block(vec![closing_inherent, user_transaction]);
+
+        // Authoring clears the noted extrinsics, so execute the block in fresh externalities.
+        TestExternalities::default().execute_with(|| {
             TestExecutive::execute_block(b);
+
+            assert!(TransparentUtxoSet::<TestVerifier>::peek_utxo(&created).is_none());
         });
     }
+
+    #[test]
+    fn failed_closing_inherent_is_skipped() {
+        let output = Output {
+            payload: Bogus.into(),
+            verifier: TestVerifier { verifies: true },
+        };
+        let mut failing_inherent = TestTransactionBuilder::default()
+            .with_output(output)
+            .build_closing_inherent();
+        failing_inherent.checker.checks = false;
+        let not_created = OutputRef {
+            tx_hash: BlakeTwo256::hash_of(&failing_inherent.encode()),
+            index: 0,
+        };
+
+        // Authoring the block does not panic.
+        let b = author_block(vec![
+            failing_inherent,
+            TestTransactionBuilder::default().build(true, false),
+        ]);
+
+        // Importing the block skips the inherent in the same way.
+        TestExternalities::default().execute_with(|| {
+            TestExecutive::execute_block(b);
+
+            assert!(TransparentUtxoSet::<TestVerifier>::peek_utxo(&not_created).is_none());
+        });
+    }
+
+    #[test]
+    fn closing_block_clears_noted_closing_inherents() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let pre_header = TestHeader {
+                parent_hash: H256::zero(),
+                number: 6,
+                state_root: H256::zero(),
+                extrinsics_root: H256::zero(),
+                digest: Default::default(),
+            };
+            TestExecutive::open_block(&pre_header);
+            TestExecutive::apply_extrinsic(
+                TestTransactionBuilder::default().build_closing_inherent(),
+            )
+            .unwrap()
+            .unwrap();
+            assert!(sp_io::storage::exists(CLOSING_INHERENTS_KEY));
+
+            TestExecutive::close_block();
+
+            assert!(!sp_io::storage::exists(CLOSING_INHERENTS_KEY));
+        });
+    }
+
+    #[test]
+    fn execute_block_closing_inherent_after_user_transaction_fails() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let b = TestBlock {
+                header: TestHeader {
+                    parent_hash: H256::zero(),
+                    number: 6,
+                    state_root: H256::zero(),
+                    extrinsics_root: H256::zero(),
+                    digest: Default::default(),
+                },
+                extrinsics: vec![
+                    TestTransactionBuilder::default().build(true, false),
+                    TestTransactionBuilder::default().build_closing_inherent(),
+                ],
+            };
+
+            assert_eq!(
+                TestExecutive::try_execute_block(b),
+                Err(BlockExecutionError::ClosingInherentOutOfPlace { extrinsic_index: 1 })
+            );
+        });
+    }
+
+    #[test]
+    fn execute_block_opening_inherent_after_closing_inherent_fails() {
+        ExternalityBuilder::default().build().execute_with(|| {
+            let b = TestBlock {
+                header: TestHeader {
+                    parent_hash: H256::zero(),
+                    number: 6,
+                    state_root: H256::zero(),
+                    extrinsics_root: H256::zero(),
+                    digest: Default::default(),
+                },
+                extrinsics: vec![
+                    TestTransactionBuilder::default().build_closing_inherent(),
+                    TestTransactionBuilder::default().build(true, true),
+                ],
+            };
+
+            assert_eq!(
+                TestExecutive::try_execute_block(b),
+                Err(BlockExecutionError::OpeningInherentOutOfPlace { extrinsic_index: 1 })
+            );
+        });
+    }
+
+    #[test]
+    fn block_inherents_are_taken_from_the_beginning() {
+        let opening = TestTransactionBuilder::default().build(true, true);
+        let closing = TestTransactionBuilder::default().build_closing_inherent();
+
+        let extrinsics = vec![
+            opening.clone(),
+            closing.clone(),
+            TestTransactionBuilder::default().build(true, false),
+        ];
+
+        assert_eq!(
+            TestExecutive::block_inherents(&extrinsics),
+            vec![opening, closing]
+        );
+    }
 }
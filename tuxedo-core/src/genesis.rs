@@ -2,7 +2,9 @@
 
 use crate::{
     ensure,
+    migration::MigrationProgress,
     types::{Output, OutputRef, Transaction},
+    utxo_set::{TransparentUtxoSet, UtxoSet, UTXO_PREFIX_MIGRATION_KEY},
     ConstraintChecker, Verifier, EXTRINSIC_KEY, LOG_TARGET,
 };
 use parity_scale_codec::{Decode, Encode};
@@ -15,7 +17,8 @@ use sp_runtime::{
     traits::{BlakeTwo256, Block as BlockT, Hash as HashT, Header as HeaderT, Zero},
     BuildStorage,
 };
-use std::sync::Arc;
+use sp_state_machine::BasicExternalities;
+use std::{marker::PhantomData, sync::Arc};
 
 pub struct TuxedoGenesisBlockBuilder<
     'a,
@@ -106,32 +109,65 @@ impl<'a, Block: BlockT, B: Backend<Block>, E: RuntimeVersionOf + CodeExecutor>
 #[derive(Serialize, Deserialize)]
 /// The `TuxedoGenesisConfig` struct is used to configure the genesis state of the runtime.
 /// It expects the wasm binary and a list of transactions to be included in the genesis block, and stored along with their outputs.
-/// They must not contain any inputs or peeks. These transactions will not be validated by the corresponding ConstraintChecker or Verifier.
+/// By default they must not contain any inputs or peeks, and they will not be validated by the corresponding ConstraintChecker or Verifier.
 /// Make sure to pass the inherents before the extrinsics.
-pub struct TuxedoGenesisConfig<V, C> {
+///
+/// With strict validation enabled (see `with_strict_validation`), each non-inherent transaction's constraint checker is run.
+/// Its inputs and peeks are resolved against the outputs of the earlier genesis transactions, and its inputs are consumed.
+/// Verifiers are still not run, because genesis transactions carry no signatures.
+/// The checkers run against a scratch copy of the genesis storage, so anything they write is discarded.
+/// Transactions that create genesis-only state, such as a mint authority that no constraint checker
+/// would let anyone create, can be trusted with `with_trusted_transactions`.
+///
+/// The outputs are stored in the UTXO set `U`, which must match the one used by the runtime's executive.
+pub struct TuxedoGenesisConfig<V, C, U = TransparentUtxoSet<V>> {
     wasm_binary: Vec<u8>,
     genesis_transactions: Vec<Transaction<V, C>>,
+    #[serde(default)]
+    strict_validation: bool,
+    #[serde(default)]
+    trusted_transactions: u32,
+    #[serde(skip)]
+    _utxo_set: PhantomData<U>,
 }
 
-impl<V, C> TuxedoGenesisConfig<V, C> {
+impl<V, C, U> TuxedoGenesisConfig<V, C, U> {
     /// Create a new `TuxedoGenesisConfig` from a WASM binary and a list of transactions.
     /// Make sure to pass the transactions in order: the inherents should be first, then the extrinsics.
     pub fn new(wasm_binary: Vec<u8>, genesis_transactions: Vec<Transaction<V, C>>) -> Self {
         Self {
             wasm_binary,
             genesis_transactions,
+            strict_validation: false,
+            trusted_transactions: 0,
+            _utxo_set: PhantomData,
         }
     }
 
+    /// Run each non-inherent genesis transaction's constraint checker when building the genesis storage.
+    /// Building the storage fails with a description of the first offending transaction.
+    pub fn with_strict_validation(mut self) -> Self {
+        self.strict_validation = true;
+        self
+    }
+
+    /// Trust the first `count` non-inherent genesis transactions even with strict validation.
+    /// They are meant to create genesis-only state, so they are placed right after the inherents.
+    pub fn with_trusted_transactions(mut self, count: u32) -> Self {
+        self.trusted_transactions = count;
+        self
+    }
+
     pub fn get_transaction(&self, i: usize) -> Option<&Transaction<V, C>> {
         self.genesis_transactions.get(i)
     }
 }
 
-impl<V, C> BuildStorage for TuxedoGenesisConfig<V, C>
+impl<V, C, U> BuildStorage for TuxedoGenesisConfig<V, C, U>
 where
     V: Verifier,
     C: ConstraintChecker<V>,
+    U: UtxoSet<V>,
     Transaction<V, C>: Encode,
     Output<V>: Encode,
 {
@@ -149,9 +185,25 @@ where
             .top
             .insert(EXTRINSIC_KEY.to_vec(), self.genesis_transactions.encode());
 
+        // A new chain stores its UTXOs under the prefix from the start, so there is nothing for
+        // the `MigrateUnprefixedUtxos` migration to do.
+        storage.top.insert(
+            UTXO_PREFIX_MIGRATION_KEY.to_vec(),
+            MigrationProgress::Complete.encode(),
+        );
+
         let mut finished_with_opening_inherents = false;
+        let mut genesis_utxos: Vec<(OutputRef, Output<V>)> = Vec::new();
+
+        // Strict validation runs the constraint checkers against a copy of the storage so that
+        // any storage they write does not leak into the genesis state.
+        let mut scratch = self
+            .strict_validation
+            .then(|| BasicExternalities::new(storage.clone()));
+
+        let mut trusted_remaining = self.trusted_transactions;
 
-        for tx in self.genesis_transactions.iter() {
+        for (index, tx) in self.genesis_transactions.iter().enumerate() {
             // Enforce that inherents are in the right place
             let current_tx_is_inherent = tx.checker.is_inherent();
             if current_tx_is_inherent && finished_with_opening_inherents {
@@ -163,22 +215,83 @@ where
                 // This is the first non-inherent, so we update our flag and continue.
                 finished_with_opening_inherents = true;
             }
-            // Enforce that transactions do not have any inputs or peeks.
-            ensure!(
-                tx.inputs.is_empty() && tx.peeks.is_empty(),
-                "Genesis transactions must not have any inputs or peeks."
-            );
-            // Insert the outputs into the storage.
+
+            // The inherents' genesis transactions come from the pieces themselves rather than from
+            // the chain spec, so they are trusted even in strict mode. So are the transactions
+            // that create genesis-only state.
+            let trusted = current_tx_is_inherent || trusted_remaining > 0;
+            if !current_tx_is_inherent {
+                trusted_remaining = trusted_remaining.saturating_sub(1);
+            }
+            if let Some(scratch) = scratch.as_mut().filter(|_| !trusted) {
+                let invalid = |reason: String| {
+                    format!(
+                        "Genesis transaction {} is invalid: {}. Transaction: {:?}",
+                        index, reason, tx
+                    )
+                };
+                ensure!(
+                    tx.evictions.is_empty(),
+                    invalid("genesis transactions must not evict anything".into())
+                );
+
+                // Resolve the inputs and peeks against the outputs created so far.
+                let mut inputs = Vec::new();
+                for input in tx.inputs.iter() {
+                    let position = genesis_utxos
+                        .iter()
+                        .position(|(output_ref, _)| *output_ref == input.output_ref)
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "input {:?} is not an output of an earlier genesis transaction",
+                                input.output_ref
+                            ))
+                        })?;
+                    inputs.push(genesis_utxos.remove(position).1);
+                }
+                let mut peeks = Vec::new();
+                for output_ref in tx.peeks.iter() {
+                    let (_, output) = genesis_utxos
+                        .iter()
+                        .find(|(created, _)| created == output_ref)
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "peek {:?} is not an output of an earlier genesis transaction",
+                                output_ref
+                            ))
+                        })?;
+                    peeks.push(output.clone());
+                }
+
+                scratch
+                    .execute_with(|| tx.checker.check(&inputs, &peeks, &tx.outputs, &[]))
+                    .map_err(|e| invalid(format!("constraint checker failed with {:?}", e)))?;
+            } else {
+                // Enforce that transactions do not have any inputs or peeks.
+                ensure!(
+                    tx.inputs.is_empty() && tx.peeks.is_empty(),
+                    "Genesis transactions must not have any inputs or peeks."
+                );
+            }
+
+            // Collect the outputs to be inserted into the storage.
             let tx_hash = BlakeTwo256::hash_of(&tx.encode());
             for (index, utxo) in tx.outputs.iter().enumerate() {
                 let output_ref = OutputRef {
                     tx_hash,
                     index: index as u32,
                 };
-                storage.top.insert(output_ref.encode(), utxo.encode());
+                genesis_utxos.push((output_ref, utxo.clone()));
             }
         }
 
+        // Insert the outputs into the storage through the configured UTXO set.
+        BasicExternalities::execute_with_storage(storage, || {
+            for (output_ref, utxo) in genesis_utxos {
+                U::store_utxo(output_ref, &utxo);
+            }
+        });
+
         Ok(())
     }
 }
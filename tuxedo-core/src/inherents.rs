@@ -29,6 +29,23 @@
 //! This module provides an inherent data provider that does just this. Any Tuxedo runtime that uses inherents (At least ones
 //! that update environmental data), needs to include this foundational previous block inherent data provider
 //! so that the Tuxedo executive can scrape it to find the output references of the previous inherent transactions.
+//!
+//! # Opening and Closing Inherents
+//!
+//! Each inherent is executed either at the beginning of the block, before any user transactions (opening inherents),
+//! or at the end of the block, after all user transactions (closing inherents). Opening inherents are typically used
+//! to update environmental information that user transactions may want to peek at, such as the timestamp.
+//! Closing inherents are useful for logic that needs to see the effects of the whole block, such as collecting fees
+//! or paying out block rewards. A constraint checker declares its placement through `ConstraintChecker::inherent_placement`.
+//!
+//! Block proposers, such as the one in `sc_basic_authorship`, apply every inherent before any transactions from the pool.
+//! So in the block body, closing inherents come right after the opening inherents. The executive sets them aside
+//! and only executes them once all user transactions have been applied, both when authoring and when importing.
+//!
+//! Because they are created before any pool transactions are applied, closing inherents cannot react to the user
+//! transactions of their own block. Logic such as paying out fees must instead handle the parent block's transactions,
+//! which it can find in the parent block inherent data. The user transactions may also consume state that a closing
+//! inherent expected. When a closing inherent fails, the executive logs it and skips it rather than halting the chain.
 
 use parity_scale_codec::Encode;
 use scale_info::TypeInfo;
@@ -38,7 +55,20 @@ use sp_inherents::{
 };
 use sp_std::{vec, vec::Vec};
 
-use crate::{types::Transaction, ConstraintChecker, Verifier};
+use crate::{
+    types::{BlockExecutionError, Transaction},
+    ConstraintChecker, Verifier,
+};
+
+/// Where in the block an inherent extrinsic is placed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InherentPlacement {
+    /// Executed at the beginning of the block, before any user transactions.
+    Opening,
+    /// Executed at the end of the block, after all user transactions.
+    /// In the block body, closing inherents are placed right after the opening inherents.
+    Closing,
+}
 
 /// An inherent identifier for the Tuxedo parent block inherent
 pub const PARENT_INHERENT_IDENTIFIER: InherentIdentifier = *b"prnt_blk";
@@ -134,10 +164,12 @@ pub trait TuxedoInherent<V, C: ConstraintChecker<V>>: Sized {
 pub trait InherentInternal<V, C: ConstraintChecker<V>>: Sized {
     /// Create the inherent extrinsic to insert into a block that is being authored locally.
     /// The inherent data is supplied by the authoring node.
+    ///
+    /// Returns an error if the previous block's inherents are not what these hooks expect.
     fn create_inherents(
         authoring_inherent_data: &InherentData,
         previous_inherents: Vec<(Transaction<V, C>, H256)>,
-    ) -> Vec<Transaction<V, C>>;
+    ) -> Result<Vec<Transaction<V, C>>, BlockExecutionError<C::Error>>;
 
     /// Perform off-chain pre-execution checks on the inherents.
     /// The inherent data is supplied by the importing node.
@@ -165,17 +197,20 @@ impl<V: Verifier, C: ConstraintChecker<V>, T: TuxedoInherent<V, C> + 'static> In
     fn create_inherents(
         authoring_inherent_data: &InherentData,
         previous_inherents: Vec<(Transaction<V, C>, H256)>,
-    ) -> Vec<Transaction<V, C>> {
-        if previous_inherents.len() > 1 {
-            panic!("Authoring a leaf inherent constraint checker, but multiple previous inherents were supplied.")
-        }
+    ) -> Result<Vec<Transaction<V, C>>, BlockExecutionError<C::Error>> {
+        // A leaf inherent appears exactly once in every block, so its parent must have exactly one too.
+        let [previous_inherent]: [(Transaction<V, C>, H256); 1] =
+            previous_inherents.try_into().map_err(|previous: Vec<_>| {
+                BlockExecutionError::UnexpectedPreviousInherents {
+                    expected: 1,
+                    found: previous.len() as u32,
+                }
+            })?;
 
-        let previous_inherent = previous_inherents.get(0).cloned();
-
-        vec![<T as TuxedoInherent<V, C>>::create_inherent(
+        Ok(vec![<T as TuxedoInherent<V, C>>::create_inherent(
             authoring_inherent_data,
-            previous_inherent.expect("Previous inherent exists."),
-        )]
+            previous_inherent,
+        )])
     }
 
     fn check_inherents(
@@ -213,8 +248,8 @@ impl<V, C: ConstraintChecker<V>> InherentInternal<V, C> for () {
     fn create_inherents(
         _: &InherentData,
         _: Vec<(Transaction<V, C>, H256)>,
-    ) -> Vec<Transaction<V, C>> {
-        Vec::new()
+    ) -> Result<Vec<Transaction<V, C>>, BlockExecutionError<C::Error>> {
+        Ok(Vec::new())
     }
 
     fn check_inherents(
@@ -9,7 +9,10 @@ pub mod dynamic_typing;
 mod executive;
 
 pub mod constraint_checker;
+pub mod dry_run;
 pub mod inherents;
+pub mod migration;
+pub mod priority;
 pub mod support_macros;
 pub mod traits;
 pub mod types;
@@ -22,7 +25,7 @@ pub mod genesis;
 pub use aggregator::{aggregate, tuxedo_constraint_checker, tuxedo_verifier};
 pub use constraint_checker::{ConstraintChecker, SimpleConstraintChecker};
 pub use executive::Executive;
-pub use verifier::Verifier;
+pub use verifier::{SimpleVerifier, Verifier};
 
 /// A Tuxedo-specific target for diagnostic node log messages
 const LOG_TARGET: &str = "tuxedo-core";
@@ -34,3 +37,12 @@ const HEADER_KEY: &[u8] = b"header"; // 686561646572
 /// A transient storage key that will hold the list of extrinsics that have been applied so far.
 /// This key is cleared before the end of the block.
 const EXTRINSIC_KEY: &[u8] = b"extrinsics";
+
+/// A transient storage key that will hold the closing inherents that have been applied so far
+/// while authoring a block. They are only executed once the block is closed.
+/// This key is cleared before the end of the block.
+const CLOSING_INHERENTS_KEY: &[u8] = b"closing_inherents";
+
+/// A storage key that holds the height of the most recent block.
+/// The pool works out the height at which its transactions would be included from it.
+const BLOCK_HEIGHT_KEY: &[u8] = b"block_height";
@@ -0,0 +1,455 @@
+//! Migrations rewrite stored UTXOs after a runtime upgrade changes how they are laid out.
+//!
+//! The executive runs the runtime's migrations at the start of every block, both when authoring
+//! and when importing, so that every node computes the same state root. A migration may only do a
+//! bounded amount of work per block. It records how far it got in storage and picks up from there
+//! in the next block, so a large UTXO set is migrated over several blocks rather than all at once.
+
+use crate::{
+    constraint_checker::ConstraintChecker,
+    dynamic_typing::VersionedUtxoData,
+    types::Output,
+    utxo_set::{PAYLOAD_MIGRATION_KEY_PREFIX, UTXO_PREFIX, UTXO_PREFIX_MIGRATION_KEY},
+    verifier::Verifier,
+    LOG_TARGET,
+};
+use parity_scale_codec::{Decode, DecodeAll, Encode};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// The most storage keys that a single migration visits in one block.
+pub const MIGRATION_KEYS_PER_BLOCK: u32 = 1000;
+
+/// A migration that the executive runs at the start of every block.
+///
+/// Runtimes plug their migrations into the `Executive`. Several migrations can be combined in a
+/// tuple, and `()` runs none.
+pub trait UtxoMigration {
+    /// Do the next bounded step of the migration, if it is not yet complete.
+    ///
+    /// This is called after the header has been stored, both from `open_block` and from
+    /// `execute_block`. It must behave identically in both so that authors and importers agree.
+    fn on_block_start();
+}
+
+impl UtxoMigration for () {
+    fn on_block_start() {}
+}
+
+impl<A: UtxoMigration, B: UtxoMigration> UtxoMigration for (A, B) {
+    fn on_block_start() {
+        A::on_block_start();
+        B::on_block_start();
+    }
+}
+
+/// How far a migration has progressed. A migration that has not started has nothing stored.
+#[derive(Encode, Decode, Debug, PartialEq, Eq)]
+pub enum MigrationProgress {
+    /// The migration has visited every key up to and including this one.
+    VisitedUpTo(Vec<u8>),
+    /// The migration has finished and will not do any more work.
+    Complete,
+}
+
+impl MigrationProgress {
+    /// Read the progress of the migration whose progress is stored at `key`.
+    pub fn get(key: &[u8]) -> Option<Self> {
+        sp_io::storage::get(key).and_then(|d| Self::decode(&mut &*d).ok())
+    }
+
+    fn set(&self, key: &[u8]) {
+        sp_io::storage::set(key, &self.encode());
+    }
+}
+
+/// Moves UTXOs that older versions of Tuxedo stored at their bare `OutputRef` encoding under the
+/// `UTXO_PREFIX`, where the `TransparentUtxoSet` expects them.
+///
+/// The entire top-level trie is walked, `MIGRATION_KEYS_PER_BLOCK` keys at a time. A key is only
+/// moved if it has the length of an encoded `OutputRef`, its value decodes as an `Output<V>`
+/// without any bytes left over, and the payload's type id belongs to one of the constraint
+/// checker's `utxo_types`. This keeps unrelated storage that happens to look like an output in
+/// place.
+///
+/// Chains launched with prefixed UTXOs have this migration marked complete in their genesis, so it
+/// does nothing for them.
+pub struct MigrateUnprefixedUtxos<V, C>(PhantomData<(V, C)>);
+
+impl<V: Verifier, C: ConstraintChecker<V>> MigrateUnprefixedUtxos<V, C> {
+    /// An `OutputRef` is a 32 byte hash followed by a 4 byte index.
+    const ENCODED_OUTPUT_REF_LEN: usize = 36;
+
+    /// The output stored at `key` if it is a UTXO from before the prefix was introduced.
+    fn unprefixed_output(key: &[u8], known_type_ids: &[[u8; 4]]) -> Option<Vec<u8>> {
+        if key.len() != Self::ENCODED_OUTPUT_REF_LEN {
+            return None;
+        }
+        let value = sp_io::storage::get(key)?;
+        let output = Output::<V>::decode_all(&mut &value[..]).ok()?;
+        known_type_ids
+            .contains(&output.payload.type_id)
+            .then(|| value.to_vec())
+    }
+}
+
+impl<V: Verifier, C: ConstraintChecker<V>> UtxoMigration for MigrateUnprefixedUtxos<V, C> {
+    fn on_block_start() {
+        let mut maybe_key = match MigrationProgress::get(UTXO_PREFIX_MIGRATION_KEY) {
+            Some(MigrationProgress::Complete) => return,
+            Some(MigrationProgress::VisitedUpTo(key)) => sp_io::storage::next_key(&key),
+            None => sp_io::storage::next_key(&[]),
+        };
+
+        let known_type_ids: Vec<[u8; 4]> = C::utxo_types().into_iter().map(|(_, id)| id).collect();
+
+        // Every key that sorts after the prefix itself but before this one is already prefixed.
+        let mut after_prefix = UTXO_PREFIX;
+        after_prefix[UTXO_PREFIX.len() - 1] += 1;
+
+        let mut moved = 0u32;
+        let mut visited = 0u32;
+        while let Some(key) = maybe_key {
+            if key.starts_with(&UTXO_PREFIX) {
+                maybe_key = sp_io::storage::next_key(&after_prefix);
+            } else {
+                if let Some(value) = Self::unprefixed_output(&key, &known_type_ids) {
+                    sp_io::storage::clear(&key);
+                    sp_io::storage::set(&[&UTXO_PREFIX[..], &key].concat(), &value);
+                    moved += 1;
+                }
+                maybe_key = sp_io::storage::next_key(&key);
+            }
+
+            visited += 1;
+            if visited == MIGRATION_KEYS_PER_BLOCK && maybe_key.is_some() {
+                MigrationProgress::VisitedUpTo(key).set(UTXO_PREFIX_MIGRATION_KEY);
+                log::info!(
+                    target: LOG_TARGET,
+                    "Migrated {} UTXOs under the UTXO prefix; continuing next block",
+                    moved
+                );
+                return;
+            }
+        }
+
+        MigrationProgress::Complete.set(UTXO_PREFIX_MIGRATION_KEY);
+        log::info!(
+            target: LOG_TARGET,
+            "Migrated {} UTXOs under the UTXO prefix; migration complete",
+            moved
+        );
+    }
+}
+
+/// Rewrites every UTXO in the `TransparentUtxoSet` whose payload is a previous version of `T` so
+/// that it holds the current version instead.
+///
+/// Previous versions are upgraded whenever they are extracted, so this is not required for
+/// correctness. It lets a runtime drop the old encodings from state, after which the previous
+/// versions can be removed from the code. Add it to the runtime's migrations in the first runtime
+/// version that introduces `T`, and remove it once it is complete. The UTXOs are walked
+/// `MIGRATION_KEYS_PER_BLOCK` at a time.
+///
+/// UTXOs whose old payload fails to decode are left untouched.
+pub struct MigratePayloads<V, T>(PhantomData<(V, T)>);
+
+impl<V: Verifier, T: VersionedUtxoData> MigratePayloads<V, T> {
+    /// The key under which the progress of this migration is stored.
+    pub fn progress_key() -> Vec<u8> {
+        [PAYLOAD_MIGRATION_KEY_PREFIX, &T::TYPE_ID[..]].concat()
+    }
+
+    /// The output stored at `key`, upgraded to `T`, if it holds a previous version of `T`.
+    fn upgraded_output(key: &[u8]) -> Option<Output<V>> {
+        let value = sp_io::storage::get(key)?;
+        let mut output = Output::<V>::decode(&mut &value[..]).ok()?;
+        if output.payload.type_id == T::TYPE_ID {
+            return None;
+        }
+        output.payload = output.payload.extract::<T>().ok()?.into();
+        Some(output)
+    }
+}
+
+impl<V: Verifier, T: VersionedUtxoData> UtxoMigration for MigratePayloads<V, T> {
+    fn on_block_start() {
+        let progress_key = Self::progress_key();
+        let mut maybe_key = match MigrationProgress::get(&progress_key) {
+            Some(MigrationProgress::Complete) => return,
+            Some(MigrationProgress::VisitedUpTo(key)) => sp_io::storage::next_key(&key),
+            None => sp_io::storage::next_key(&UTXO_PREFIX),
+        };
+
+        let mut upgraded = 0u32;
+        let mut visited = 0u32;
+        while let Some(key) = maybe_key.filter(|k| k.starts_with(&UTXO_PREFIX)) {
+            if let Some(output) = Self::upgraded_output(&key) {
+                sp_io::storage::set(&key, &output.encode());
+                upgraded += 1;
+            }
+            maybe_key = sp_io::storage::next_key(&key);
+
+            visited += 1;
+            if visited == MIGRATION_KEYS_PER_BLOCK {
+                MigrationProgress::VisitedUpTo(key).set(&progress_key);
+                log::info!(
+                    target: LOG_TARGET,
+                    "Upgraded {} UTXO payloads to type {:?}; continuing next block",
+                    upgraded,
+                    T::TYPE_ID
+                );
+                return;
+            }
+        }
+
+        MigrationProgress::Complete.set(&progress_key);
+        log::info!(
+            target: LOG_TARGET,
+            "Upgraded {} UTXO payloads to type {:?}; migration complete",
+            upgraded,
+            T::TYPE_ID
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constraint_checker::testing::TestConstraintChecker,
+        dynamic_typing::{
+            extract_previous_version, testing::Bogus, DynamicTypingError, DynamicallyTypedData,
+            UpgradesPreviousVersions, UtxoData,
+        },
+        types::OutputRef,
+        utxo_set::{TransparentUtxoSet, UtxoSet},
+        verifier::TestVerifier,
+    };
+    use sp_core::H256;
+    use sp_io::TestExternalities;
+
+    type Migration = MigrateUnprefixedUtxos<TestVerifier, TestConstraintChecker>;
+    type Set = TransparentUtxoSet<TestVerifier>;
+
+    fn output_ref(index: u32) -> OutputRef {
+        OutputRef {
+            tx_hash: H256::repeat_byte(1),
+            index,
+        }
+    }
+
+    fn output() -> Output<TestVerifier> {
+        (Bogus, TestVerifier { verifies: true }).into()
+    }
+
+    /// Run the migration until it reports that it is complete, returning the number of blocks.
+    fn run_to_completion() -> u32 {
+        let mut blocks = 0;
+        while MigrationProgress::get(UTXO_PREFIX_MIGRATION_KEY) != Some(MigrationProgress::Complete)
+        {
+            Migration::on_block_start();
+            blocks += 1;
+        }
+        blocks
+    }
+
+    #[test]
+    fn migration_moves_unprefixed_utxos() {
+        TestExternalities::default().execute_with(|| {
+            sp_io::storage::set(&output_ref(0).encode(), &output().encode());
+            sp_io::storage::set(&output_ref(1).encode(), &output().encode());
+
+            assert_eq!(run_to_completion(), 1);
+
+            assert_eq!(sp_io::storage::get(&output_ref(0).encode()), None);
+            assert_eq!(sp_io::storage::get(&output_ref(1).encode()), None);
+            assert_eq!(Set::peek_utxo(&output_ref(0)), Some(output()));
+            assert_eq!(Set::peek_utxo(&output_ref(1)), Some(output()));
+        });
+    }
+
+    #[test]
+    fn migration_ignores_other_keys() {
+        TestExternalities::default().execute_with(|| {
+            sp_io::storage::set(b"header", b"not a utxo");
+            sp_io::storage::set(&[0u8; 36], b"not a utxo either");
+
+            run_to_completion();
+
+            assert!(sp_io::storage::exists(b"header"));
+            assert!(sp_io::storage::exists(&[0u8; 36]));
+        });
+    }
+
+    #[test]
+    fn migration_ignores_outputs_of_unknown_types() {
+        TestExternalities::default().execute_with(|| {
+            let mut unknown = output();
+            unknown.payload.type_id = *b"????";
+            assert_ne!(unknown.payload.type_id, Bogus::TYPE_ID);
+            sp_io::storage::set(&output_ref(0).encode(), &unknown.encode());
+
+            run_to_completion();
+
+            assert!(sp_io::storage::exists(&output_ref(0).encode()));
+            assert_eq!(Set::peek_utxo(&output_ref(0)), None);
+        });
+    }
+
+    #[test]
+    fn migration_is_spread_over_several_blocks() {
+        TestExternalities::default().execute_with(|| {
+            for index in 0..MIGRATION_KEYS_PER_BLOCK + 1 {
+                sp_io::storage::set(&output_ref(index).encode(), &output().encode());
+            }
+
+            Migration::on_block_start();
+            assert!(matches!(
+                MigrationProgress::get(UTXO_PREFIX_MIGRATION_KEY),
+                Some(MigrationProgress::VisitedUpTo(_))
+            ));
+
+            assert_eq!(run_to_completion(), 1);
+            for index in 0..MIGRATION_KEYS_PER_BLOCK + 1 {
+                assert_eq!(sp_io::storage::get(&output_ref(index).encode()), None);
+                assert_eq!(Set::peek_utxo(&output_ref(index)), Some(output()));
+            }
+        });
+    }
+
+    #[test]
+    fn migration_does_nothing_once_complete() {
+        TestExternalities::default().execute_with(|| {
+            MigrationProgress::Complete.set(UTXO_PREFIX_MIGRATION_KEY);
+            sp_io::storage::set(&output_ref(0).encode(), &output().encode());
+
+            Migration::on_block_start();
+
+            assert!(sp_io::storage::exists(&output_ref(0).encode()));
+        });
+    }
+
+    /// The first version of a payload type, used to test payload migrations.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq)]
+    struct CounterV1(u8);
+
+    impl UtxoData for CounterV1 {
+        const TYPE_ID: [u8; 4] = *b"cnt1";
+    }
+
+    /// The second version of a payload type, used to test payload migrations.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq)]
+    struct Counter(u32);
+
+    impl UtxoData for Counter {
+        const TYPE_ID: [u8; 4] = *b"cnt2";
+
+        fn extract_previous_version(
+            data: &DynamicallyTypedData,
+        ) -> Result<Self, DynamicTypingError> {
+            extract_previous_version::<Self>(data)
+        }
+    }
+
+    impl UpgradesPreviousVersions for Counter {}
+
+    impl VersionedUtxoData for Counter {
+        type Previous = CounterV1;
+
+        fn upgrade(previous: CounterV1) -> Self {
+            Counter(previous.0 as u32)
+        }
+    }
+
+    type PayloadMigration = MigratePayloads<TestVerifier, Counter>;
+
+    fn counter_output<T: UtxoData>(counter: T) -> Output<TestVerifier> {
+        (counter, TestVerifier { verifies: true }).into()
+    }
+
+    /// Run the payload migration until it reports that it is complete, returning the number of blocks.
+    fn run_payload_migration_to_completion() -> u32 {
+        let mut blocks = 0;
+        while MigrationProgress::get(&PayloadMigration::progress_key())
+            != Some(MigrationProgress::Complete)
+        {
+            PayloadMigration::on_block_start();
+            blocks += 1;
+        }
+        blocks
+    }
+
+    #[test]
+    fn payload_migration_upgrades_previous_versions() {
+        TestExternalities::default().execute_with(|| {
+            Set::store_utxo(output_ref(0), &counter_output(CounterV1(3)));
+            Set::store_utxo(output_ref(1), &counter_output(Counter(4)));
+            Set::store_utxo(output_ref(2), &output());
+
+            assert_eq!(run_payload_migration_to_completion(), 1);
+
+            assert_eq!(
+                Set::peek_utxo(&output_ref(0)),
+                Some(counter_output(Counter(3)))
+            );
+            assert_eq!(
+                Set::peek_utxo(&output_ref(1)),
+                Some(counter_output(Counter(4)))
+            );
+            assert_eq!(Set::peek_utxo(&output_ref(2)), Some(output()));
+        });
+    }
+
+    #[test]
+    fn payload_migration_is_spread_over_several_blocks() {
+        TestExternalities::default().execute_with(|| {
+            for index in 0..MIGRATION_KEYS_PER_BLOCK + 1 {
+                Set::store_utxo(output_ref(index), &counter_output(CounterV1(3)));
+            }
+
+            PayloadMigration::on_block_start();
+            assert!(matches!(
+                MigrationProgress::get(&PayloadMigration::progress_key()),
+                Some(MigrationProgress::VisitedUpTo(_))
+            ));
+
+            assert_eq!(run_payload_migration_to_completion(), 1);
+            for index in 0..MIGRATION_KEYS_PER_BLOCK + 1 {
+                assert_eq!(
+                    Set::peek_utxo(&output_ref(index)),
+                    Some(counter_output(Counter(3)))
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn payload_migration_does_nothing_once_complete() {
+        TestExternalities::default().execute_with(|| {
+            MigrationProgress::Complete.set(&PayloadMigration::progress_key());
+            Set::store_utxo(output_ref(0), &counter_output(CounterV1(3)));
+
+            PayloadMigration::on_block_start();
+
+            assert_eq!(
+                Set::peek_utxo(&output_ref(0)),
+                Some(counter_output(CounterV1(3)))
+            );
+        });
+    }
+
+    #[test]
+    fn payload_migration_ignores_keys_outside_prefix() {
+        TestExternalities::default().execute_with(|| {
+            let unprefixed = counter_output(CounterV1(3)).encode();
+            sp_io::storage::set(&output_ref(0).encode(), &unprefixed);
+
+            run_payload_migration_to_completion();
+
+            assert_eq!(
+                sp_io::storage::get(&output_ref(0).encode()).map(|d| d.to_vec()),
+                Some(unprefixed)
+            );
+        });
+    }
+}
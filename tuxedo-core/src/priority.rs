@@ -0,0 +1,55 @@
+//! A priority policy decides the final transaction pool priority of a valid transaction.
+//!
+//! Every constraint checker returns a `TransactionPriority` when it succeeds. For example, the
+//! money piece reports the number of burned tokens. By default, that value is used directly as
+//! the pool priority, which gives fee-based ordering when blocks are congested.
+//!
+//! Some runtimes will want to adjust this. For example, a runtime may want to weight transactions
+//! that only peek at state differently from those that consume it. Such a runtime can implement
+//! this trait (typically on its `Runtime` type) and plug it into the `Executive`.
+
+use crate::types::Transaction;
+use sp_runtime::transaction_validity::TransactionPriority;
+
+/// A runtime-configurable policy for calculating the final priority of a transaction in the pool.
+pub trait PriorityPolicy<V, C> {
+    /// Calculate the pool priority of a transaction that has passed all validation.
+    /// The priority returned by the transaction's constraint checker is passed in.
+    ///
+    /// The default implementation uses the constraint checker's priority unchanged.
+    fn priority(
+        _transaction: &Transaction<V, C>,
+        checker_priority: TransactionPriority,
+    ) -> TransactionPriority {
+        checker_priority
+    }
+}
+
+/// The default priority policy. It uses the constraint checker's priority unchanged.
+impl<V, C> PriorityPolicy<V, C> for () {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constraint_checker::testing::TestConstraintChecker, verifier::TestVerifier};
+    use sp_std::vec::Vec;
+
+    #[test]
+    fn default_policy_uses_checker_priority() {
+        let tx: Transaction<TestVerifier, TestConstraintChecker> = Transaction {
+            inputs: Vec::new(),
+            peeks: Vec::new(),
+            outputs: Vec::new(),
+            checker: TestConstraintChecker {
+                checks: true,
+                inherent: false,
+                closing: false,
+                evicts: false,
+            },
+            evictions: Vec::new(),
+            valid_until: None,
+        };
+
+        assert_eq!(<() as PriorityPolicy<_, _>>::priority(&tx, 42), 42);
+    }
+}
@@ -1,12 +1,16 @@
 //! General-purpose runtime traits for describing common types of on-chain logic.
 //! Tuxedo piece implementations may loosely couple through these traits.
 
+use sp_core::H256;
+
 /// A trait for UTXOs that can act like coins, or bank notes.
 pub trait Cash {
     /// Get the value of this token.
     fn value(&self) -> u128;
 
-    /// A 1-byte unique identifier for this coin.
-    /// Might need more than 1 byte eventually...
-    const ID: u8;
+    /// A unique identifier for the kind of asset this token belongs to.
+    ///
+    /// Kinds of coins that are fixed when the runtime is compiled use small identifiers, while
+    /// assets that are registered on chain use the hash of their metadata.
+    fn asset_id(&self) -> H256;
 }
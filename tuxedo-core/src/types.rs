@@ -5,7 +5,7 @@ use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_core::H256;
-use sp_runtime::traits::Extrinsic;
+use sp_runtime::{traits::Extrinsic, transaction_validity::InvalidTransaction};
 use sp_std::vec::Vec;
 
 /// A reference to a output that is expected to exist in the state.
@@ -29,9 +29,14 @@ pub struct OutputRef {
 ///    For example, that the total output value of a cryptocurrency transaction does not exceed its
 ///    input value. Or that a cryptokitty was created with the correct genetic material from its parents.
 ///
-/// In the future, there may be additional notions of peeks (inputs that are not consumed)
-/// and evictions (inputs that are forcefully consumed.)
-/// Existing state to be read and consumed from storage
+/// In addition to the inputs, a transaction may peek at state (read it without consuming it)
+/// and evict state (consume it without consulting its verifier). Evictions allow pieces to
+/// clean up stale or disputed state that the original owner would never agree to remove.
+/// The constraint checker is responsible for deciding whether an eviction is justified.
+///
+/// A transaction may also be mortal. A mortal transaction carries the last block height at which it
+/// may be included. It is covered by the signatures on the inputs, so it cannot be extended by a
+/// third party, and the transaction pool drops the transaction once that height has passed.
 #[derive(Serialize, Deserialize, Default, Debug, PartialEq, Eq, Clone, TypeInfo)]
 pub struct Transaction<V, C> {
     /// Existing pieces of state to be read and consumed from storage
@@ -42,6 +47,12 @@ pub struct Transaction<V, C> {
     pub outputs: Vec<Output<V>>,
     /// Which piece of constraint checking logic is used to determine whether this transaction is valid
     pub checker: C,
+    /// Existing state to be read and consumed from storage without checking its verifier
+    #[serde(default)]
+    pub evictions: Vec<OutputRef>,
+    /// The last block height at which this transaction may be included, or `None` if it is immortal
+    #[serde(default)]
+    pub valid_until: Option<u32>,
 }
 
 impl<V: Clone, C: Clone> Transaction<V, C> {
@@ -54,20 +65,43 @@ impl<V: Clone, C: Clone> Transaction<V, C> {
             peeks: self.peeks.clone(),
             outputs: self.outputs.clone(),
             checker: self.checker.clone().into(),
+            evictions: self.evictions.clone(),
+            valid_until: self.valid_until,
         }
     }
 }
 
 // Manually implement Encode and Decode for the Transaction type
 // so that its encoding is the same as an opaque Vec<u8>.
+//
+// Evictions were added after the other fields, so they are encoded last, and only when there
+// are any. A transaction without evictions therefore encodes exactly as it did before, and the
+// decoder uses the length prefix to tell whether an evictions vec follows the checker.
+//
+// The valid-until height of a mortal transaction is handled the same way. It is encoded as a bare
+// `u32` after the evictions, which are then always encoded, even when empty.
 impl<V: Encode, C: Encode> Encode for Transaction<V, C> {
     fn encode_to<T: parity_scale_codec::Output + ?Sized>(&self, dest: &mut T) {
         let inputs = self.inputs.encode();
         let peeks = self.peeks.encode();
         let outputs = self.outputs.encode();
         let checker = self.checker.encode();
+        let evictions = if self.evictions.is_empty() && self.valid_until.is_none() {
+            Vec::new()
+        } else {
+            self.evictions.encode()
+        };
+        let valid_until = self
+            .valid_until
+            .map(|height| height.encode())
+            .unwrap_or_default();
 
-        let total_len = (inputs.len() + outputs.len() + peeks.len() + checker.len()) as u32;
+        let total_len = (inputs.len()
+            + outputs.len()
+            + peeks.len()
+            + checker.len()
+            + evictions.len()
+            + valid_until.len()) as u32;
         let size = parity_scale_codec::Compact::<u32>(total_len).encode();
 
         dest.write(&size);
@@ -75,6 +109,8 @@ impl<V: Encode, C: Encode> Encode for Transaction<V, C> {
         dest.write(&peeks);
         dest.write(&outputs);
         dest.write(&checker);
+        dest.write(&evictions);
+        dest.write(&valid_until);
     }
 }
 
@@ -82,23 +118,75 @@ impl<V: Decode, C: Decode> Decode for Transaction<V, C> {
     fn decode<I: parity_scale_codec::Input>(
         input: &mut I,
     ) -> Result<Self, parity_scale_codec::Error> {
-        // Throw away the length of the vec. We just want the bytes.
-        <parity_scale_codec::Compact<u32>>::skip(input)?;
+        // Read the length of the vec so we know whether any evictions or a valid-until height follow the checker.
+        let total_len = <parity_scale_codec::Compact<u32>>::decode(input)?.0 as usize;
+        let mut input = CountedInput {
+            inner: input,
+            read: 0,
+        };
 
-        let inputs = <Vec<Input>>::decode(input)?;
-        let peeks = <Vec<OutputRef>>::decode(input)?;
-        let outputs = <Vec<Output<V>>>::decode(input)?;
-        let checker = C::decode(input)?;
+        let inputs = <Vec<Input>>::decode(&mut input)?;
+        let peeks = <Vec<OutputRef>>::decode(&mut input)?;
+        let outputs = <Vec<Output<V>>>::decode(&mut input)?;
+        let checker = C::decode(&mut input)?;
+        let evictions = if input.read < total_len {
+            let evictions = <Vec<OutputRef>>::decode(&mut input)?;
+            // Empty evictions are only encoded to make room for a valid-until height.
+            // Without one, they must be left out so that each transaction has a single encoding.
+            if evictions.is_empty() && input.read >= total_len {
+                return Err(
+                    "Transaction encodes empty evictions without a valid-until height".into(),
+                );
+            }
+            evictions
+        } else {
+            Vec::new()
+        };
+        let valid_until = if input.read < total_len {
+            Some(u32::decode(&mut input)?)
+        } else {
+            None
+        };
+
+        if input.read != total_len {
+            return Err("Transaction length prefix does not match its contents".into());
+        }
 
         Ok(Transaction {
             inputs,
             peeks,
             outputs,
             checker,
+            evictions,
+            valid_until,
         })
     }
 }
 
+/// A codec input wrapper that counts how many bytes have been read through it.
+struct CountedInput<'a, I: parity_scale_codec::Input> {
+    inner: &'a mut I,
+    read: usize,
+}
+
+impl<I: parity_scale_codec::Input> parity_scale_codec::Input for CountedInput<'_, I> {
+    fn remaining_len(&mut self) -> Result<Option<usize>, parity_scale_codec::Error> {
+        self.inner.remaining_len()
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> Result<(), parity_scale_codec::Error> {
+        self.inner.read(into)?;
+        self.read += into.len();
+        Ok(())
+    }
+
+    fn read_byte(&mut self) -> Result<u8, parity_scale_codec::Error> {
+        let byte = self.inner.read_byte()?;
+        self.read += 1;
+        Ok(byte)
+    }
+}
+
 // We must implement this Extrinsic trait to use our Transaction type as the Block's Transaction type
 // See https://paritytech.github.io/substrate/master/sp_runtime/traits/trait.Block.html#associatedtype.Extrinsic
 //
@@ -147,7 +235,7 @@ pub struct Input {
     pub redeemer: Vec<u8>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Encode, Decode, Debug, PartialEq, Eq)]
 pub enum UtxoError<ConstraintCheckerError> {
     /// This transaction defines the same input multiple times
     DuplicateInput,
@@ -161,6 +249,149 @@ pub enum UtxoError<ConstraintCheckerError> {
     VerifierError,
     /// One or more of the inputs required by this transaction is not present in the UTXO set
     MissingInput,
+    /// This transaction is mortal and the block height it was valid until has passed
+    Expired,
+    /// This transaction evicts state, but its constraint checker does not allow evictions
+    EvictionsNotAllowed,
+}
+
+/// The custom validity code reported when a transaction defines the same input multiple times.
+pub const DUPLICATE_INPUT_CODE: u8 = 0xFF;
+
+/// The custom validity code reported when a transaction evicts state, but its constraint checker
+/// does not allow evictions.
+pub const EVICTIONS_NOT_ALLOWED_CODE: u8 = 0xFE;
+
+/// The custom validity code reported when a constraint checker fails, but the index of the
+/// failing checker is too large to be packed into the code. See `checker_error_code`.
+pub const UNPACKABLE_CHECKER_ERROR_CODE: u8 = 0xF0;
+
+/// The low nibble used when a constraint checker fails, but the index of its inner error is
+/// too large to be packed into the code. See `checker_error_code`.
+pub const UNPACKABLE_INNER_ERROR: u8 = 0x0F;
+
+/// Pack an encoded constraint checker error into a single custom validity code.
+///
+/// For an aggregated constraint checker, the first byte of the encoded error is the index of
+/// the checker that failed, and the second byte is the index of that checker's error variant.
+/// The high nibble of the code is the checker index and the low nibble is the inner index.
+///
+/// Codes with a high nibble of `0xF` are reserved. Checker indices that do not fit are reported
+/// as `UNPACKABLE_CHECKER_ERROR_CODE`, and inner indices that do not fit are reported with a low
+/// nibble of `UNPACKABLE_INNER_ERROR`.
+pub fn checker_error_code<E: Encode>(error: &E) -> u8 {
+    let encoded = error.encode();
+    let outer = encoded.first().copied().unwrap_or_default();
+    let inner = encoded.get(1).copied().unwrap_or_default();
+
+    if outer >= 0x0F {
+        UNPACKABLE_CHECKER_ERROR_CODE
+    } else if inner >= UNPACKABLE_INNER_ERROR {
+        (outer << 4) | UNPACKABLE_INNER_ERROR
+    } else {
+        (outer << 4) | inner
+    }
+}
+
+/// Map a UTXO error into the error reported to the transaction pool and block authoring.
+///
+/// Wherever there is a standard variant with the same meaning, it is used. Constraint checker
+/// errors are packed into a custom code so users can tell which checker failed and why.
+impl<E: Encode> From<UtxoError<E>> for InvalidTransaction {
+    fn from(error: UtxoError<E>) -> Self {
+        match error {
+            UtxoError::DuplicateInput => InvalidTransaction::Custom(DUPLICATE_INPUT_CODE),
+            UtxoError::PreExistingOutput => InvalidTransaction::Stale,
+            UtxoError::ConstraintCheckerError(e) => {
+                InvalidTransaction::Custom(checker_error_code(&e))
+            }
+            UtxoError::VerifierError => InvalidTransaction::BadProof,
+            UtxoError::MissingInput => InvalidTransaction::Future,
+            UtxoError::Expired => InvalidTransaction::AncientBirthBlock,
+            UtxoError::EvictionsNotAllowed => {
+                InvalidTransaction::Custom(EVICTIONS_NOT_ALLOWED_CODE)
+            }
+        }
+    }
+}
+
+impl<E> UtxoError<E> {
+    /// Convert the contained constraint checker error, if any, into another type.
+    pub fn map_checker_error<E2>(self, f: impl FnOnce(E) -> E2) -> UtxoError<E2> {
+        match self {
+            Self::DuplicateInput => UtxoError::DuplicateInput,
+            Self::PreExistingOutput => UtxoError::PreExistingOutput,
+            Self::ConstraintCheckerError(e) => UtxoError::ConstraintCheckerError(f(e)),
+            Self::VerifierError => UtxoError::VerifierError,
+            Self::MissingInput => UtxoError::MissingInput,
+            Self::Expired => UtxoError::Expired,
+            Self::EvictionsNotAllowed => UtxoError::EvictionsNotAllowed,
+        }
+    }
+}
+
+/// The reason a block could not be executed or its inherents could not be created.
+///
+/// Block execution happens behind the `Core` runtime API, which cannot return an error, so the
+/// executive logs this error once and then panics with it. That way the node's import queue
+/// reports exactly what went wrong instead of an opaque trap.
+#[derive(Encode, Decode, Debug, PartialEq, Eq)]
+pub enum BlockExecutionError<ConstraintCheckerError, Hash = H256> {
+    /// An opening inherent appeared after a closing inherent or user transaction.
+    OpeningInherentOutOfPlace {
+        /// The index of the misplaced inherent among the block's extrinsics.
+        extrinsic_index: u32,
+    },
+    /// A closing inherent appeared after a user transaction. Closing inherents are placed
+    /// right after the opening inherents, even though they are executed at the end of the block.
+    ClosingInherentOutOfPlace {
+        /// The index of the misplaced inherent among the block's extrinsics.
+        extrinsic_index: u32,
+    },
+    /// An extrinsic in the block failed to apply.
+    ExtrinsicFailed {
+        /// The index of the failing extrinsic among the block's extrinsics.
+        extrinsic_index: u32,
+        /// The reason the extrinsic failed.
+        error: UtxoError<ConstraintCheckerError>,
+    },
+    /// The state root computed after execution does not match the one in the header.
+    StateRootMismatch { expected: Hash, actual: Hash },
+    /// The extrinsics root computed from the block body does not match the one in the header.
+    ExtrinsicsRootMismatch { expected: Hash, actual: Hash },
+    /// An inherent that expects exactly one inherent from the parent block found a different number.
+    UnexpectedPreviousInherents { expected: u32, found: u32 },
+}
+
+impl<E, Hash> BlockExecutionError<E, Hash> {
+    /// Convert the contained constraint checker error, if any, into another type.
+    /// This is used when aggregating inherent hooks into an outer constraint checker.
+    pub fn map_checker_error<E2>(self, f: impl FnOnce(E) -> E2) -> BlockExecutionError<E2, Hash> {
+        match self {
+            Self::OpeningInherentOutOfPlace { extrinsic_index } => {
+                BlockExecutionError::OpeningInherentOutOfPlace { extrinsic_index }
+            }
+            Self::ClosingInherentOutOfPlace { extrinsic_index } => {
+                BlockExecutionError::ClosingInherentOutOfPlace { extrinsic_index }
+            }
+            Self::ExtrinsicFailed {
+                extrinsic_index,
+                error,
+            } => BlockExecutionError::ExtrinsicFailed {
+                extrinsic_index,
+                error: error.map_checker_error(f),
+            },
+            Self::StateRootMismatch { expected, actual } => {
+                BlockExecutionError::StateRootMismatch { expected, actual }
+            }
+            Self::ExtrinsicsRootMismatch { expected, actual } => {
+                BlockExecutionError::ExtrinsicsRootMismatch { expected, actual }
+            }
+            Self::UnexpectedPreviousInherents { expected, found } => {
+                BlockExecutionError::UnexpectedPreviousInherents { expected, found }
+            }
+        }
+    }
 }
 
 /// The Result of dispatching a UTXO transaction.
@@ -206,12 +437,16 @@ pub mod tests {
         let checker = TestConstraintChecker {
             checks: true,
             inherent: false,
+            closing: false,
+            evicts: false,
         };
         let tx: Transaction<TestVerifier, TestConstraintChecker> = Transaction {
             inputs: Vec::new(),
             peeks: Vec::new(),
             outputs: Vec::new(),
             checker,
+            evictions: Vec::new(),
+            valid_until: None,
         };
         let e = Transaction::new(tx.clone(), None).unwrap();
 
@@ -224,12 +459,16 @@ pub mod tests {
         let checker = TestConstraintChecker {
             checks: true,
             inherent: false,
+            closing: false,
+            evicts: false,
         };
         let tx: Transaction<TestVerifier, TestConstraintChecker> = Transaction {
             inputs: Vec::new(),
             peeks: Vec::new(),
             outputs: Vec::new(),
             checker,
+            evictions: Vec::new(),
+            valid_until: None,
         };
         let e = Transaction::new(tx.clone(), Some(())).unwrap();
 
@@ -242,16 +481,186 @@ pub mod tests {
         let checker = TestConstraintChecker {
             checks: true,
             inherent: true,
+            closing: false,
+            evicts: false,
         };
         let tx: Transaction<TestVerifier, TestConstraintChecker> = Transaction {
             inputs: Vec::new(),
             peeks: Vec::new(),
             outputs: Vec::new(),
             checker,
+            evictions: Vec::new(),
+            valid_until: None,
         };
         let e = Transaction::new(tx.clone(), Some(())).unwrap();
 
         assert_eq!(e, tx);
         assert_eq!(e.is_signed(), Some(false));
     }
+
+    fn tx_with_evictions(
+        evictions: Vec<OutputRef>,
+    ) -> Transaction<TestVerifier, TestConstraintChecker> {
+        Transaction {
+            inputs: vec![Input {
+                output_ref: OutputRef {
+                    tx_hash: H256::repeat_byte(1),
+                    index: 0,
+                },
+                redeemer: vec![1, 2, 3],
+            }],
+            peeks: Vec::new(),
+            outputs: Vec::new(),
+            checker: TestConstraintChecker {
+                checks: true,
+                inherent: false,
+                closing: false,
+                evicts: false,
+            },
+            evictions,
+            valid_until: None,
+        }
+    }
+
+    #[test]
+    fn encoding_without_evictions_is_unchanged() {
+        let tx = tx_with_evictions(Vec::new());
+
+        let mut legacy = Vec::new();
+        legacy.extend(tx.inputs.encode());
+        legacy.extend(tx.peeks.encode());
+        legacy.extend(tx.outputs.encode());
+        legacy.extend(tx.checker.encode());
+
+        assert_eq!(tx.encode(), legacy.encode());
+        assert_eq!(Transaction::decode(&mut &legacy.encode()[..]), Ok(tx));
+    }
+
+    #[test]
+    fn encoding_with_evictions_round_trips() {
+        let tx = tx_with_evictions(vec![OutputRef {
+            tx_hash: H256::repeat_byte(2),
+            index: 3,
+        }]);
+
+        let encoded = tx.encode();
+        // The transaction is still a valid opaque Vec<u8>.
+        assert!(<Vec<u8>>::decode(&mut &encoded[..]).is_ok());
+        assert_eq!(Transaction::decode(&mut &encoded[..]), Ok(tx));
+    }
+
+    #[test]
+    fn encoding_with_valid_until_round_trips() {
+        let mut tx = tx_with_evictions(Vec::new());
+        tx.valid_until = Some(42);
+
+        let encoded = tx.encode();
+        assert!(<Vec<u8>>::decode(&mut &encoded[..]).is_ok());
+        assert_eq!(Transaction::decode(&mut &encoded[..]), Ok(tx.clone()));
+
+        // Evictions and the valid-until height may appear together.
+        tx.evictions.push(OutputRef {
+            tx_hash: H256::repeat_byte(2),
+            index: 3,
+        });
+        let encoded = tx.encode();
+        assert_eq!(Transaction::decode(&mut &encoded[..]), Ok(tx));
+    }
+
+    #[test]
+    fn decoding_rejects_mismatched_length_prefix() {
+        let tx = tx_with_evictions(Vec::new());
+        let mut body = tx.encode()[1..].to_vec();
+        body.extend([0, 0]);
+
+        // Claims two extra bytes, but an empty evictions vec only accounts for one,
+        // and the remaining byte is too short to be a valid-until height.
+        let encoded = body.encode();
+        assert!(
+            Transaction::<TestVerifier, TestConstraintChecker>::decode(&mut &encoded[..]).is_err()
+        );
+    }
+
+    #[test]
+    fn decoding_rejects_empty_evictions_without_valid_until() {
+        let tx = tx_with_evictions(Vec::new());
+        let mut body = tx.encode()[1..].to_vec();
+        body.extend(Vec::<OutputRef>::new().encode());
+
+        // The length prefix accounts for the explicitly encoded empty evictions, but
+        // the same transaction is already encoded without them.
+        let encoded = body.encode();
+        assert!(
+            Transaction::<TestVerifier, TestConstraintChecker>::decode(&mut &encoded[..]).is_err()
+        );
+    }
+
+    #[test]
+    fn utxo_errors_map_to_distinct_validity_errors() {
+        let errors: Vec<InvalidTransaction> = vec![
+            UtxoError::<()>::DuplicateInput.into(),
+            UtxoError::<()>::PreExistingOutput.into(),
+            UtxoError::<()>::ConstraintCheckerError(()).into(),
+            UtxoError::<()>::VerifierError.into(),
+            UtxoError::<()>::MissingInput.into(),
+            UtxoError::<()>::Expired.into(),
+            UtxoError::<()>::EvictionsNotAllowed.into(),
+        ];
+
+        for (i, e) in errors.iter().enumerate() {
+            assert!(!errors[i + 1..].contains(e));
+        }
+        assert_eq!(errors[3], InvalidTransaction::BadProof);
+    }
+
+    #[test]
+    fn checker_error_packs_outer_and_inner_index() {
+        let e = UtxoError::ConstraintCheckerError((2u8, 3u8));
+        assert_eq!(
+            InvalidTransaction::from(e),
+            InvalidTransaction::Custom(0x23)
+        );
+    }
+
+    #[test]
+    fn checker_error_with_large_inner_index() {
+        assert_eq!(checker_error_code(&(1u8, 27u8)), 0x1F);
+    }
+
+    #[test]
+    fn checker_error_with_large_outer_index() {
+        assert_eq!(
+            checker_error_code(&(15u8, 0u8)),
+            UNPACKABLE_CHECKER_ERROR_CODE
+        );
+    }
+
+    #[test]
+    fn checker_error_codes_never_collide_with_reserved_codes() {
+        assert_ne!(checker_error_code(&(255u8, 255u8)), DUPLICATE_INPUT_CODE);
+        assert_ne!(checker_error_code(&(14u8, 255u8)), DUPLICATE_INPUT_CODE);
+        assert_ne!(
+            checker_error_code(&(255u8, 255u8)),
+            EVICTIONS_NOT_ALLOWED_CODE
+        );
+        assert_ne!(
+            checker_error_code(&(14u8, 255u8)),
+            EVICTIONS_NOT_ALLOWED_CODE
+        );
+    }
+
+    #[test]
+    fn block_execution_error_maps_checker_error() {
+        let e: BlockExecutionError<u8> = BlockExecutionError::ExtrinsicFailed {
+            extrinsic_index: 3,
+            error: UtxoError::ConstraintCheckerError(7),
+        };
+        assert_eq!(
+            e.map_checker_error(|inner| (1u8, inner)),
+            BlockExecutionError::ExtrinsicFailed {
+                extrinsic_index: 3,
+                error: UtxoError::ConstraintCheckerError((1u8, 7u8)),
+            }
+        );
+    }
 }
@@ -1,39 +1,79 @@
-//! For now this is a higher level description of the default UTXO set features which Tuxedo
-//! chooses to use. Future UTXO sets could take a different form especially
-//! if being used for Zero-Knowledge. In the future it may likely be abstracted into a trait
-//! to support various UTXO set types.
+//! The UTXO set is the collection of all unspent outputs. The executive reads and writes it
+//! through the `UtxoSet` trait so that runtimes can choose where and how the outputs are stored.
 //!
+//! Tuxedo ships the following implementations:
+//! * `TransparentUtxoSet` - The default. Stores each output in the top-level trie under the
+//!   `UTXO_PREFIX` followed by the SCALE encoding of its `OutputRef`.
+//! * `ChildTrieUtxoSet` - Stores each output in a dedicated child trie so that UTXOs do not share
+//!   the top-level key space with other storage such as the header, the extrinsics, and `:code`.
+//! * `InMemoryUtxoSet` - Stores outputs in memory. Useful for native unit tests that do not
+//!   want to set up externalities.
+//!
+//! Future UTXO sets could take a different form especially if being used for Zero-Knowledge.
 
 use crate::{
     types::{Output, OutputRef},
     verifier::Verifier,
     LOG_TARGET,
 };
-use parity_scale_codec::{Decode, Encode};
-use sp_std::marker::PhantomData;
+use parity_scale_codec::{Codec, Decode, Encode};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// A storage backend for the UTXO set.
+pub trait UtxoSet<V> {
+    /// Fetch a utxo from the set.
+    fn peek_utxo(output_ref: &OutputRef) -> Option<Output<V>>;
+
+    /// Consume a Utxo from the set.
+    fn consume_utxo(output_ref: &OutputRef) -> Option<Output<V>>;
+
+    /// Add a utxo into the set.
+    /// This will overwrite any utxo that already exists at this OutputRef. It should never be the
+    /// case that there are collisions though. Right??
+    fn store_utxo(output_ref: OutputRef, output: &Output<V>);
+}
 
+/// The prefix under which the `TransparentUtxoSet` stores all UTXOs in the top-level trie.
+///
+/// It is the twox128 hash of `b"utxos"`. Hashing the prefix keeps the UTXOs apart from the other
+/// top-level keys such as `header`, `extrinsics`, and `:code`, so that no `OutputRef` can collide
+/// with them, and lets clients enumerate exactly the UTXOs with `state_getKeysPaged`.
+pub const UTXO_PREFIX: [u8; 16] = [
+    0x14, 0x07, 0xe5, 0xcb, 0x5f, 0x57, 0x9e, 0x10, 0x0c, 0x9a, 0x81, 0xe6, 0xe9, 0x80, 0x9d, 0xd7,
+];
+
+/// The key at which the `TransparentUtxoSet` stores the UTXO with the given `OutputRef`.
+pub fn utxo_storage_key(output_ref: &OutputRef) -> Vec<u8> {
+    [&UTXO_PREFIX[..], &output_ref.encode()].concat()
+}
+
+/// The key that records the progress of the `MigrateUnprefixedUtxos` migration.
+pub const UTXO_PREFIX_MIGRATION_KEY: &[u8] = b"utxo_prefix_migrated";
+
+/// The prefix of the keys that record the progress of each `MigratePayloads` migration.
+/// The type id of the migrated type follows the prefix.
+pub const PAYLOAD_MIGRATION_KEY_PREFIX: &[u8] = b"utxo_payload_migrated";
+
+/// The default UTXO set. Outputs are stored in the top-level trie under the `UTXO_PREFIX`
+/// followed by the SCALE encoding of their `OutputRef`.
 pub struct TransparentUtxoSet<Verifier>(PhantomData<Verifier>);
 
-impl<V: Verifier> TransparentUtxoSet<V> {
-    /// Fetch a utxo from the set.
-    pub fn peek_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
-        sp_io::storage::get(&output_ref.encode()).and_then(|d| Output::decode(&mut &*d).ok())
+impl<V: Verifier> UtxoSet<V> for TransparentUtxoSet<V> {
+    fn peek_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
+        sp_io::storage::get(&utxo_storage_key(output_ref))
+            .and_then(|d| Output::decode(&mut &*d).ok())
     }
 
-    /// Consume a Utxo from the set.
-    pub fn consume_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
+    fn consume_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
         // TODO do we even need to read the stored value here? The only place we call this
         // is from `update_storage` and we don't use the value there.
         let maybe_output = Self::peek_utxo(output_ref);
-        sp_io::storage::clear(&output_ref.encode());
+        sp_io::storage::clear(&utxo_storage_key(output_ref));
         maybe_output
     }
 
-    /// Add a utxo into the set.
-    /// This will overwrite any utxo that already exists at this OutputRef. It should never be the
-    /// case that there are collisions though. Right??
-    pub fn store_utxo(output_ref: OutputRef, output: &Output<V>) {
-        let key = output_ref.encode();
+    fn store_utxo(output_ref: OutputRef, output: &Output<V>) {
+        let key = utxo_storage_key(&output_ref);
         log::debug!(
             target: LOG_TARGET,
             "Storing UTXO at key: {:?}",
@@ -42,3 +82,191 @@ impl<V: Verifier> TransparentUtxoSet<V> {
         sp_io::storage::set(&key, &output.encode());
     }
 }
+
+/// The name of the default child trie in which `ChildTrieUtxoSet` stores outputs.
+pub const UTXO_CHILD_TRIE: &[u8] = b"tuxedo_utxos";
+
+/// A UTXO set that lives in its own default child trie. Within the child trie, outputs are
+/// stored at the SCALE encoding of their `OutputRef`.
+///
+/// Because the child trie is separate from the top-level trie, no `OutputRef` can collide with
+/// well known keys, and clients can enumerate all UTXOs with the `childstate_getKeysPaged` RPC.
+pub struct ChildTrieUtxoSet<Verifier>(PhantomData<Verifier>);
+
+impl<V: Verifier> UtxoSet<V> for ChildTrieUtxoSet<V> {
+    fn peek_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
+        sp_io::default_child_storage::get(UTXO_CHILD_TRIE, &output_ref.encode())
+            .and_then(|d| Output::decode(&mut &*d).ok())
+    }
+
+    fn consume_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
+        let maybe_output = Self::peek_utxo(output_ref);
+        sp_io::default_child_storage::clear(UTXO_CHILD_TRIE, &output_ref.encode());
+        maybe_output
+    }
+
+    fn store_utxo(output_ref: OutputRef, output: &Output<V>) {
+        let key = output_ref.encode();
+        log::debug!(
+            target: LOG_TARGET,
+            "Storing UTXO in child trie at key: {:?}",
+            sp_core::hexdisplay::HexDisplay::from(&key)
+        );
+        sp_io::default_child_storage::set(UTXO_CHILD_TRIE, &key, &output.encode());
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// The storage behind `InMemoryUtxoSet`. Outputs are kept encoded so that a single
+    /// map can back sets of any verifier type.
+    static IN_MEMORY_UTXOS: core::cell::RefCell<std::collections::BTreeMap<Vec<u8>, Vec<u8>>> =
+        const { core::cell::RefCell::new(std::collections::BTreeMap::new()) };
+}
+
+/// A UTXO set that is kept in memory rather than in the externalities' storage.
+///
+/// This is only intended for native unit tests. Each thread has its own set, which matches
+/// how the test harness runs each test on its own thread.
+#[cfg(feature = "std")]
+pub struct InMemoryUtxoSet<Verifier>(PhantomData<Verifier>);
+
+#[cfg(feature = "std")]
+impl<V> InMemoryUtxoSet<V> {
+    /// Remove every utxo from this thread's set.
+    pub fn clear() {
+        IN_MEMORY_UTXOS.with(|utxos| utxos.borrow_mut().clear());
+    }
+}
+
+#[cfg(feature = "std")]
+impl<V: Verifier> UtxoSet<V> for InMemoryUtxoSet<V> {
+    fn peek_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
+        IN_MEMORY_UTXOS.with(|utxos| {
+            utxos
+                .borrow()
+                .get(&output_ref.encode())
+                .and_then(|d| Output::decode(&mut &d[..]).ok())
+        })
+    }
+
+    fn consume_utxo(output_ref: &OutputRef) -> Option<Output<V>> {
+        IN_MEMORY_UTXOS.with(|utxos| {
+            utxos
+                .borrow_mut()
+                .remove(&output_ref.encode())
+                .and_then(|d| Output::decode(&mut &d[..]).ok())
+        })
+    }
+
+    fn store_utxo(output_ref: OutputRef, output: &Output<V>) {
+        IN_MEMORY_UTXOS.with(|utxos| {
+            utxos
+                .borrow_mut()
+                .insert(output_ref.encode(), output.encode())
+        });
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    /// Read-only access to the UTXO set for clients such as the node's RPC.
+    pub trait TuxedoUtxoApi<V>
+    where
+        V: Codec,
+    {
+        /// Fetch a utxo from the set. Returns `None` if it does not exist, for example
+        /// because it has already been spent.
+        fn peek_utxo(output_ref: OutputRef) -> Option<Output<V>>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dynamic_typing::testing::Bogus, verifier::TestVerifier};
+    use sp_core::H256;
+    use sp_io::TestExternalities;
+
+    fn output_ref(index: u32) -> OutputRef {
+        OutputRef {
+            tx_hash: H256::repeat_byte(1),
+            index,
+        }
+    }
+
+    fn output() -> Output<TestVerifier> {
+        (Bogus, TestVerifier { verifies: true }).into()
+    }
+
+    /// Store, peek, and consume a utxo in the given set.
+    fn store_peek_consume<U: UtxoSet<TestVerifier>>() {
+        assert_eq!(U::peek_utxo(&output_ref(0)), None);
+
+        U::store_utxo(output_ref(0), &output());
+        assert_eq!(U::peek_utxo(&output_ref(0)), Some(output()));
+        assert_eq!(U::peek_utxo(&output_ref(1)), None);
+
+        assert_eq!(U::consume_utxo(&output_ref(0)), Some(output()));
+        assert_eq!(U::peek_utxo(&output_ref(0)), None);
+        assert_eq!(U::consume_utxo(&output_ref(0)), None);
+    }
+
+    #[test]
+    fn transparent_utxo_set_works() {
+        TestExternalities::default().execute_with(|| {
+            store_peek_consume::<TransparentUtxoSet<TestVerifier>>();
+        });
+    }
+
+    #[test]
+    fn transparent_utxo_set_uses_prefixed_key() {
+        TestExternalities::default().execute_with(|| {
+            TransparentUtxoSet::<TestVerifier>::store_utxo(output_ref(0), &output());
+            assert_eq!(
+                sp_io::storage::get(&utxo_storage_key(&output_ref(0))).map(|d| d.to_vec()),
+                Some(output().encode())
+            );
+            assert_eq!(sp_io::storage::get(&output_ref(0).encode()), None);
+        });
+    }
+
+    #[test]
+    fn utxo_prefix_is_hash_of_utxos() {
+        assert_eq!(UTXO_PREFIX, sp_io::hashing::twox_128(b"utxos"));
+    }
+
+    #[test]
+    fn child_trie_utxo_set_works() {
+        TestExternalities::default().execute_with(|| {
+            store_peek_consume::<ChildTrieUtxoSet<TestVerifier>>();
+        });
+    }
+
+    #[test]
+    fn child_trie_utxo_set_does_not_use_top_level_trie() {
+        TestExternalities::default().execute_with(|| {
+            ChildTrieUtxoSet::<TestVerifier>::store_utxo(output_ref(0), &output());
+            assert_eq!(sp_io::storage::get(&output_ref(0).encode()), None);
+            assert_eq!(
+                TransparentUtxoSet::<TestVerifier>::peek_utxo(&output_ref(0)),
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn in_memory_utxo_set_works() {
+        InMemoryUtxoSet::<TestVerifier>::clear();
+        store_peek_consume::<InMemoryUtxoSet<TestVerifier>>();
+    }
+
+    #[test]
+    fn in_memory_utxo_set_clear_works() {
+        InMemoryUtxoSet::<TestVerifier>::store_utxo(output_ref(0), &output());
+        InMemoryUtxoSet::<TestVerifier>::clear();
+        assert_eq!(
+            InMemoryUtxoSet::<TestVerifier>::peek_utxo(&output_ref(0)),
+            None
+        );
+    }
+}
@@ -5,22 +5,66 @@
 //! with Tuxedo core, but downstream developers are expected to create their own as well.
 //!
 
-use parity_scale_codec::{Decode, Encode};
+use crate::types::Transaction;
+use parity_scale_codec::{Decode, DecodeAll, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_core::sr25519::{Public, Signature};
-use sp_core::H256;
-use sp_std::collections::btree_map::BTreeMap;
+use sp_core::{ecdsa, ed25519, H160, H256};
 use sp_std::collections::btree_set::BTreeSet;
 use sp_std::fmt::Debug;
 use sp_std::vec::Vec;
 
-/// A means of checking that an output can be verified (aka spent). This check is made on a
+/// A simplified means of checking that an output can be verified (aka spent). This check is made on a
 /// per-output basis and neither knows nor cares anything about the validation logic that will
 /// be applied to the transaction as a whole. Nonetheless, in order to avoid malleability, we
 /// we take the entire stripped and serialized transaction as a parameter.
-pub trait Verifier: Debug + Encode + Decode + Clone {
-    fn verify(&self, simplified_tx: &[u8], redeemer: &[u8]) -> bool;
+///
+/// The height of the block in which the transaction is being executed is also passed in so that
+/// verifiers can express time locks. In the transaction pool, this is the height of the next block.
+pub trait SimpleVerifier: Debug + Encode + Decode + Clone {
+    fn verify(&self, simplified_tx: &[u8], block_height: u32, redeemer: &[u8]) -> bool;
+}
+
+/// A means of checking that an output can be verified (aka spent) with access to the full
+/// context of the transaction that is spending it.
+///
+/// This full Verifier should only be implemented if the verification logic cannot be expressed with
+/// the SimpleVerifier. For example, covenants that restrict which verifiers the transaction's outputs
+/// may use, or verifiers that only allow spending alongside a particular peek.
+///
+/// The type parameter `V` is the verifier type used in the transaction, which is typically the
+/// runtime's aggregate verifier rather than `Self`. The type parameter `C` is the transaction's
+/// constraint checker.
+pub trait Verifier<V = Self, C = ()>: Debug + Encode + Decode + Clone {
+    /// The actual verification logic.
+    ///
+    /// The transaction is passed both decoded and encoded. In both cases the redeemers have been stripped.
+    /// The redeemer for the input being verified is passed separately along with its index in the inputs.
+    fn verify_input(
+        &self,
+        simplified_tx: &[u8],
+        transaction: &Transaction<V, C>,
+        input_index: usize,
+        block_height: u32,
+        redeemer: &[u8],
+    ) -> bool;
+}
+
+// This blanket implementation makes it so that any type that chooses to
+// implement the Simple trait also implements the more Powerful trait.
+// This way the executive can always just call the more Powerful trait.
+impl<T: SimpleVerifier, V, C> Verifier<V, C> for T {
+    fn verify_input(
+        &self,
+        simplified_tx: &[u8],
+        _transaction: &Transaction<V, C>,
+        _input_index: usize,
+        block_height: u32,
+        redeemer: &[u8],
+    ) -> bool {
+        SimpleVerifier::verify(self, simplified_tx, block_height, redeemer)
+    }
 }
 
 /// A typical verifier that checks an sr25519 signature
@@ -37,8 +81,8 @@ impl Sr25519Signature {
     }
 }
 
-impl Verifier for Sr25519Signature {
-    fn verify(&self, simplified_tx: &[u8], redeemer: &[u8]) -> bool {
+impl SimpleVerifier for Sr25519Signature {
+    fn verify(&self, simplified_tx: &[u8], _block_height: u32, redeemer: &[u8]) -> bool {
         let sig = match Signature::try_from(redeemer) {
             Ok(s) => s,
             Err(_) => return false,
@@ -48,18 +92,202 @@ impl Verifier for Sr25519Signature {
     }
 }
 
+/// A verifier that checks an ed25519 signature. This is the same as `Sr25519Signature`
+/// except for the signature scheme. It is useful for hardware wallets that only support ed25519.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub struct Ed25519Signature {
+    pub owner_pubkey: H256,
+}
+
+impl Ed25519Signature {
+    pub fn new<T: Into<H256>>(value: T) -> Self {
+        Ed25519Signature {
+            owner_pubkey: value.into(),
+        }
+    }
+}
+
+impl SimpleVerifier for Ed25519Signature {
+    fn verify(&self, simplified_tx: &[u8], _block_height: u32, redeemer: &[u8]) -> bool {
+        let sig = match ed25519::Signature::try_from(redeemer) {
+            Ok(s) => s,
+            Err(_) => return false,
+        };
+
+        sp_io::crypto::ed25519_verify(
+            &sig,
+            simplified_tx,
+            &ed25519::Public::from_raw(self.owner_pubkey.0),
+        )
+    }
+}
+
+/// The party that owns an output guarded by an `EcdsaSignature` verifier.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub enum EcdsaOwner {
+    /// A compressed secp256k1 public key. The signature must be over the
+    /// BLAKE2-256 hash of the transaction as is usual in Substrate.
+    PublicKey(ecdsa::Public),
+    /// A 20 byte Ethereum address. The signer's public key is recovered from the
+    /// signature, which must be over the Keccak-256 hash of the transaction as is usual in Ethereum.
+    EthereumAddress(H160),
+}
+
+/// A verifier that checks a secp256k1 ECDSA signature. This is useful for keys that are shared with
+/// Ethereum and other EVM chains.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub struct EcdsaSignature {
+    pub owner: EcdsaOwner,
+}
+
+impl EcdsaSignature {
+    pub fn new<T: Into<ecdsa::Public>>(value: T) -> Self {
+        EcdsaSignature {
+            owner: EcdsaOwner::PublicKey(value.into()),
+        }
+    }
+
+    pub fn new_ethereum<T: Into<H160>>(address: T) -> Self {
+        EcdsaSignature {
+            owner: EcdsaOwner::EthereumAddress(address.into()),
+        }
+    }
+}
+
+/// Calculate the Ethereum address of an uncompressed secp256k1 public key (without the leading 0x04 tag).
+/// It is the last 20 bytes of the Keccak-256 hash of the key.
+pub fn ethereum_address(uncompressed_pubkey: &[u8; 64]) -> H160 {
+    H160::from_slice(&sp_io::hashing::keccak_256(uncompressed_pubkey)[12..])
+}
+
+impl SimpleVerifier for EcdsaSignature {
+    fn verify(&self, simplified_tx: &[u8], _block_height: u32, redeemer: &[u8]) -> bool {
+        let sig = match ecdsa::Signature::try_from(redeemer) {
+            Ok(s) => s,
+            Err(_) => return false,
+        };
+
+        match &self.owner {
+            EcdsaOwner::PublicKey(public) => {
+                sp_io::crypto::ecdsa_verify(&sig, simplified_tx, public)
+            }
+            EcdsaOwner::EthereumAddress(address) => {
+                let msg_hash = sp_io::hashing::keccak_256(simplified_tx);
+                match sp_io::crypto::secp256k1_ecdsa_recover(&sig.0, &msg_hash) {
+                    Ok(recovered) => ethereum_address(&recovered) == *address,
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+}
+
+/// A verifier that guards an output with an sr25519 signature like `Sr25519Signature`, but
+/// refuses spending before a given block height. This is useful for vesting and escrow payouts.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub struct BlockHeightLock {
+    /// The owner who may spend the output once it is unlocked
+    pub owner_pubkey: H256,
+    /// The first block height at which the output may be spent
+    pub unlock_height: u32,
+}
+
+impl SimpleVerifier for BlockHeightLock {
+    fn verify(&self, simplified_tx: &[u8], block_height: u32, redeemer: &[u8]) -> bool {
+        if block_height < self.unlock_height {
+            return false;
+        }
+
+        Sr25519Signature::new(self.owner_pubkey).verify(simplified_tx, block_height, redeemer)
+    }
+}
+
+/// A verifier that guards an output with an sr25519 signature like `Sr25519Signature`, but
+/// refuses spending before a given timestamp.
+///
+/// The current time is read from the `BestTimestampStorage`, so the runtime must note a new
+/// best timestamp each block for this verifier to be useful. If no timestamp has been noted,
+/// the output cannot be spent.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub struct TimestampLock {
+    /// The owner who may spend the output once it is unlocked
+    pub owner_pubkey: H256,
+    /// The first timestamp, in milliseconds, at which the output may be spent
+    pub unlock_time: u64,
+}
+
+impl SimpleVerifier for TimestampLock {
+    fn verify(&self, simplified_tx: &[u8], block_height: u32, redeemer: &[u8]) -> bool {
+        if !BestTimestampStorage::get().is_some_and(|now| now >= self.unlock_time) {
+            return false;
+        }
+
+        Sr25519Signature::new(self.owner_pubkey).verify(simplified_tx, block_height, redeemer)
+    }
+}
+
+/// A storage key that holds the best timestamp noted on-chain.
+/// Each new timestamp overwrites the previous one.
+const BEST_TIMESTAMP_KEY: &[u8] = b"best_timestamp";
+
+/// An abstraction over setting the ambiently available best timestamp that `TimestampLock` reads.
+/// This allows it to be mocked during tests and not require actual externalities.
+pub trait SetBestTimestampStorage {
+    fn set(new_timestamp: u64);
+}
+
+/// A public interface for accessing and mutating the best timestamp. This is
+/// expected to be updated by the timestamp piece once per block.
+pub enum BestTimestampStorage {}
+
+impl BestTimestampStorage {
+    /// Read the best timestamp, if one has ever been noted.
+    pub fn get() -> Option<u64> {
+        sp_io::storage::get(BEST_TIMESTAMP_KEY).and_then(|d| u64::decode(&mut &*d).ok())
+    }
+}
+
+impl SetBestTimestampStorage for BestTimestampStorage {
+    fn set(new_timestamp: u64) {
+        sp_io::storage::set(BEST_TIMESTAMP_KEY, &new_timestamp.encode());
+    }
+}
+
+/// A mock version of the BestTimestampStorage that can be used in tests without externalities.
+pub enum MockBestTimestampStorage {}
+
+impl SetBestTimestampStorage for MockBestTimestampStorage {
+    fn set(_new_timestamp: u64) {}
+}
+
 /// A simple verifier that allows anyone to consume an output at any time
 #[derive(
     Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo, Default,
 )]
 pub struct UpForGrabs;
 
-impl Verifier for UpForGrabs {
-    fn verify(&self, _simplified_tx: &[u8], _redeemer: &[u8]) -> bool {
+impl SimpleVerifier for UpForGrabs {
+    fn verify(&self, _simplified_tx: &[u8], _block_height: u32, _redeemer: &[u8]) -> bool {
         true
     }
 }
 
+/// A simple verifier that allows no one to consume an output ever.
+///
+/// This is useful for UTXOs that are intended to be read (peeked at) but never consumed.
+/// Such UTXOs can still be removed from state by evicting them, if the constraint
+/// checker allows it.
+#[derive(
+    Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo, Default,
+)]
+pub struct Unspendable;
+
+impl SimpleVerifier for Unspendable {
+    fn verify(&self, _simplified_tx: &[u8], _block_height: u32, _redeemer: &[u8]) -> bool {
+        false
+    }
+}
+
 /// A Threshold multisignature. Some number of member signatories collectively own inputs
 /// guarded by this verifier. A valid redeemer must supply valid signatures by at least
 /// `threshold` of the signatories. If the threshold is greater than the number of signatories
@@ -98,52 +326,120 @@ pub struct SignatureAndIndex {
     pub index: u8,
 }
 
-impl Verifier for ThresholdMultiSignature {
-    fn verify(&self, simplified_tx: &[u8], redeemer: &[u8]) -> bool {
+impl SimpleVerifier for ThresholdMultiSignature {
+    fn verify(&self, simplified_tx: &[u8], _block_height: u32, redeemer: &[u8]) -> bool {
         if self.has_duplicate_signatories() {
             return false;
         }
 
-        let sigs = match Vec::<SignatureAndIndex>::decode(&mut &redeemer[..]) {
+        // The entire redeemer must decode. Trailing bytes are not allowed.
+        let sigs = match Vec::<SignatureAndIndex>::decode_all(&mut &redeemer[..]) {
             Ok(s) => s,
             Err(_) => return false,
         };
 
-        if sigs.len() < self.threshold.into() {
-            return false;
-        }
-
-        {
-            // Check range of indicies
-            let index_out_of_bounds = sigs.iter().any(|sig| sig.index as usize >= sigs.len());
-            if index_out_of_bounds {
-                return false;
-            }
-        }
-
-        {
-            let set: BTreeMap<u8, Signature> = sigs
-                .iter()
-                .map(|sig_and_index| (sig_and_index.index, sig_and_index.signature.clone()))
-                .collect();
-
-            if set.len() < sigs.len() {
+        // Every index must refer to a signatory, and each signatory may only sign once.
+        let mut seen_indices = BTreeSet::new();
+        for sig in sigs.iter() {
+            if sig.index as usize >= self.signatories.len() || !seen_indices.insert(sig.index) {
                 return false;
             }
         }
 
-        let valid_sigs: Vec<_> = sigs
+        // Only valid signatures count toward the threshold.
+        let valid_sigs = sigs
             .iter()
-            .map(|sig| {
+            .filter(|sig| {
                 sp_io::crypto::sr25519_verify(
                     &sig.signature,
                     simplified_tx,
                     &Public::from_h256(self.signatories[sig.index as usize]),
-                );
+                )
             })
-            .collect();
+            .count();
 
-        valid_sigs.len() >= self.threshold.into()
+        valid_sigs >= self.threshold.into()
+    }
+}
+
+/// The hash function that a `HashTimeLock` uses to commit to its secret.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, Copy, TypeInfo)]
+pub enum HashAlgorithm {
+    /// BLAKE2b with a 256 bit output. This is the hash used throughout Substrate.
+    Blake2_256,
+    /// SHA-256. This is the hash used by Bitcoin and many other chains' HTLCs,
+    /// which makes it the right choice for cross-chain atomic swaps.
+    Sha2_256,
+}
+
+impl HashAlgorithm {
+    /// Hash the given data with this algorithm.
+    pub fn hash(&self, data: &[u8]) -> H256 {
+        match self {
+            Self::Blake2_256 => sp_io::hashing::blake2_256(data).into(),
+            Self::Sha2_256 => sp_io::hashing::sha2_256(data).into(),
+        }
+    }
+}
+
+/// A hash-time-locked contract. This is the basic building block for atomic swaps.
+///
+/// There are two ways to spend an output guarded by this verifier. The recipient may spend it
+/// at any time by revealing a secret whose hash matches the `hash_lock` and signing the transaction.
+/// Alternatively, the refunder may reclaim it by signing the transaction once the chain has
+/// reached the `refund_height`.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+pub struct HashTimeLock {
+    /// The hash of the secret that the recipient must reveal
+    pub hash_lock: H256,
+    /// The hash function used to compute the `hash_lock`
+    pub hash_algorithm: HashAlgorithm,
+    /// The owner who may spend the output by revealing the secret
+    pub recipient_pubkey: H256,
+    /// The owner who may reclaim the output after the timeout
+    pub refunder_pubkey: H256,
+    /// The first block height at which the refunder may reclaim the output
+    pub refund_height: u32,
+}
+
+/// The redeemer for a `HashTimeLock`. It specifies which of the two spend paths is being used
+/// along with the proof required for that path.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub enum UnlockHashTimeLock {
+    /// The recipient reveals the secret and signs the transaction
+    Claim {
+        secret: Vec<u8>,
+        signature: Signature,
+    },
+    /// The refunder signs the transaction after the timeout
+    Refund { signature: Signature },
+}
+
+impl SimpleVerifier for HashTimeLock {
+    fn verify(&self, simplified_tx: &[u8], block_height: u32, redeemer: &[u8]) -> bool {
+        let unlock = match UnlockHashTimeLock::decode_all(&mut &redeemer[..]) {
+            Ok(u) => u,
+            Err(_) => return false,
+        };
+
+        match unlock {
+            UnlockHashTimeLock::Claim { secret, signature } => {
+                self.hash_algorithm.hash(&secret) == self.hash_lock
+                    && sp_io::crypto::sr25519_verify(
+                        &signature,
+                        simplified_tx,
+                        &Public::from_h256(self.recipient_pubkey),
+                    )
+            }
+            UnlockHashTimeLock::Refund { signature } => {
+                block_height >= self.refund_height
+                    && sp_io::crypto::sr25519_verify(
+                        &signature,
+                        simplified_tx,
+                        &Public::from_h256(self.refunder_pubkey),
+                    )
+            }
+        }
     }
 }
 
@@ -157,8 +453,8 @@ pub struct TestVerifier {
 }
 
 #[cfg(feature = "std")]
-impl Verifier for TestVerifier {
-    fn verify(&self, _simplified_tx: &[u8], _redeemer: &[u8]) -> bool {
+impl SimpleVerifier for TestVerifier {
+    fn verify(&self, _simplified_tx: &[u8], _block_height: u32, _redeemer: &[u8]) -> bool {
         self.verifies
     }
 }
@@ -186,7 +482,12 @@ mod test {
 
     #[test]
     fn up_for_grabs_always_verifies() {
-        assert!(UpForGrabs.verify(&[], &[]))
+        assert!(UpForGrabs.verify(&[], 0, &[]))
+    }
+
+    #[test]
+    fn unspendable_never_verifies() {
+        assert!(!Unspendable.verify(&[], 0, &[]))
     }
 
     #[test]
@@ -200,7 +501,122 @@ mod test {
             owner_pubkey: pair.public().into(),
         };
 
-        assert!(sr25519_signature.verify(simplified_tx, redeemer));
+        assert!(sr25519_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ed25519_signature_with_good_sig() {
+        let pair = ed25519::Pair::from_seed(&[0u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign(simplified_tx);
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ed25519_signature = Ed25519Signature::new(pair.public().0);
+
+        assert!(ed25519_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ed25519_signature_with_wrong_signer() {
+        let pair = ed25519::Pair::from_seed(&[0u8; 32]);
+        let other = ed25519::Pair::from_seed(&[1u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = other.sign(simplified_tx);
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ed25519_signature = Ed25519Signature::new(pair.public().0);
+
+        assert!(!ed25519_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ed25519_signature_rejects_sr25519_sig() {
+        let pair = Pair::from_seed(&[0u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign(simplified_tx);
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ed25519_signature = Ed25519Signature::new(pair.public());
+
+        assert!(!ed25519_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ecdsa_signature_with_good_sig() {
+        let pair = ecdsa::Pair::from_seed(&[0u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign(simplified_tx);
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ecdsa_signature = EcdsaSignature::new(pair.public());
+
+        assert!(ecdsa_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ecdsa_signature_with_wrong_signer() {
+        let pair = ecdsa::Pair::from_seed(&[0u8; 32]);
+        let other = ecdsa::Pair::from_seed(&[1u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = other.sign(simplified_tx);
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ecdsa_signature = EcdsaSignature::new(pair.public());
+
+        assert!(!ecdsa_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ecdsa_ethereum_address_with_good_sig() {
+        // The well known test key from EIP-155
+        let pair = ecdsa::Pair::from_seed(&[0x46u8; 32]);
+        let address: [u8; 20] =
+            array_bytes::hex2array_unchecked("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign_prehashed(&sp_io::hashing::keccak_256(simplified_tx));
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ecdsa_signature = EcdsaSignature::new_ethereum(address);
+
+        assert!(ecdsa_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ecdsa_ethereum_address_with_blake2_sig_fails() {
+        let pair = ecdsa::Pair::from_seed(&[0x46u8; 32]);
+        let address: [u8; 20] =
+            array_bytes::hex2array_unchecked("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign(simplified_tx);
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ecdsa_signature = EcdsaSignature::new_ethereum(address);
+
+        assert!(!ecdsa_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ecdsa_ethereum_address_with_wrong_signer() {
+        let other = ecdsa::Pair::from_seed(&[1u8; 32]);
+        let address: [u8; 20] =
+            array_bytes::hex2array_unchecked("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+        let simplified_tx = b"hello world".as_slice();
+        let sig = other.sign_prehashed(&sp_io::hashing::keccak_256(simplified_tx));
+        let redeemer: &[u8] = sig.as_ref();
+
+        let ecdsa_signature = EcdsaSignature::new_ethereum(address);
+
+        assert!(!ecdsa_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn ecdsa_signature_with_bad_sig() {
+        let simplified_tx = b"hello world".as_slice();
+        let redeemer = b"bogus_signature".as_slice();
+
+        let ecdsa_signature = EcdsaSignature::new_ethereum([0u8; 20]);
+
+        assert!(!ecdsa_signature.verify(simplified_tx, 0, redeemer));
     }
 
     #[test]
@@ -226,7 +642,7 @@ mod test {
             signatories,
         };
 
-        assert!(threshold_multisig.verify(simplified_tx, redeemer));
+        assert!(threshold_multisig.verify(simplified_tx, 0, redeemer));
     }
 
     #[test]
@@ -253,7 +669,7 @@ mod test {
             signatories,
         };
 
-        assert!(!threshold_multisig.verify(simplified_tx, redeemer));
+        assert!(!threshold_multisig.verify(simplified_tx, 0, redeemer));
     }
 
     #[test]
@@ -279,7 +695,7 @@ mod test {
             signatories,
         };
 
-        assert!(threshold_multisig.verify(simplified_tx, redeemer));
+        assert!(threshold_multisig.verify(simplified_tx, 0, redeemer));
     }
 
     #[test]
@@ -308,7 +724,7 @@ mod test {
             signatories,
         };
 
-        assert!(!threshold_multisig.verify(simplified_tx, redeemer));
+        assert!(!threshold_multisig.verify(simplified_tx, 0, redeemer));
     }
 
     #[test]
@@ -336,7 +752,7 @@ mod test {
             signatories,
         };
 
-        assert!(!threshold_multisig.verify(simplified_tx, redeemer));
+        assert!(!threshold_multisig.verify(simplified_tx, 0, redeemer));
     }
 
     #[test]
@@ -350,7 +766,166 @@ mod test {
             signatories: vec![],
         };
 
-        assert!(!threshold_multisig.verify(b"bogus_message".as_slice(), bogus.encode().as_slice()))
+        assert!(!threshold_multisig.verify(
+            b"bogus_message".as_slice(),
+            0,
+            bogus.encode().as_slice()
+        ))
+    }
+
+    /// Build a multisig whose signatories are `n` test keys, along with a redeemer that
+    /// contains a signature over `simplified_tx` for each of the given `(signer, index)` pairs.
+    /// The signer is the position of the key that signs, and the index is the signatory it claims to be.
+    fn multisig_and_redeemer(
+        threshold: u8,
+        n: u8,
+        simplified_tx: &[u8],
+        signers: &[(usize, u8)],
+    ) -> (ThresholdMultiSignature, Vec<u8>) {
+        // Generate one extra key so tests can sign with a non-member.
+        let pairs = generate_n_pairs(n + 1);
+        let signatories = pairs
+            .iter()
+            .take(n as usize)
+            .map(|p| H256::from(p.public()))
+            .collect();
+
+        let sigs: Vec<_> = signers
+            .iter()
+            .map(|(signer, index)| SignatureAndIndex {
+                signature: pairs[*signer].sign(simplified_tx),
+                index: *index,
+            })
+            .collect();
+
+        (
+            ThresholdMultiSignature::new(threshold, signatories),
+            sigs.encode(),
+        )
+    }
+
+    const N: u8 = 3;
+    const TX: &[u8] = b"hello_world";
+
+    /// Signatures from all N signatories at their proper indices
+    fn all_good() -> Vec<(usize, u8)> {
+        (0..N).map(|i| (i as usize, i)).collect()
+    }
+
+    #[test]
+    fn threshold_multisig_zero_threshold_no_sigs_passes() {
+        let (multisig, redeemer) = multisig_and_redeemer(0, N, TX, &[]);
+        assert!(multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_zero_threshold_bad_sig_passes() {
+        // The bad signature does not count, but none are required anyway.
+        let (multisig, redeemer) = multisig_and_redeemer(0, N, TX, &[(N as usize, 0)]);
+        assert!(multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_zero_threshold_duplicate_sigs_fails() {
+        let (multisig, redeemer) = multisig_and_redeemer(0, N, TX, &[(0, 0), (0, 0)]);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_one_threshold_good_sig_passes() {
+        let (multisig, redeemer) = multisig_and_redeemer(1, N, TX, &[(2, 2)]);
+        assert!(multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_one_threshold_bad_sig_fails() {
+        let (multisig, redeemer) = multisig_and_redeemer(1, N, TX, &[(N as usize, 0)]);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_one_threshold_sig_at_wrong_index_fails() {
+        let (multisig, redeemer) = multisig_and_redeemer(1, N, TX, &[(0, 1)]);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_one_threshold_sig_over_wrong_message_fails() {
+        let (multisig, redeemer) = multisig_and_redeemer(1, N, b"other_message", &[(0, 0)]);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_one_threshold_duplicate_sigs_fails() {
+        let (multisig, redeemer) = multisig_and_redeemer(1, N, TX, &[(1, 1), (1, 1)]);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_n_threshold_good_sigs_passes() {
+        let (multisig, redeemer) = multisig_and_redeemer(N, N, TX, &all_good());
+        assert!(multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_n_threshold_one_bad_sig_fails() {
+        let mut signers = all_good();
+        signers[1] = (N as usize, 1);
+
+        let (multisig, redeemer) = multisig_and_redeemer(N, N, TX, &signers);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_n_threshold_duplicate_sigs_fails() {
+        let mut signers = all_good();
+        signers[2] = (1, 1);
+
+        let (multisig, redeemer) = multisig_and_redeemer(N, N, TX, &signers);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_n_plus_one_threshold_good_sigs_fails() {
+        let (multisig, redeemer) = multisig_and_redeemer(N + 1, N, TX, &all_good());
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_n_plus_one_threshold_bad_extra_sig_fails() {
+        let mut signers = all_good();
+        signers.push((N as usize, N));
+
+        let (multisig, redeemer) = multisig_and_redeemer(N + 1, N, TX, &signers);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_n_plus_one_threshold_duplicate_sig_fails() {
+        let mut signers = all_good();
+        signers.push((0, 0));
+
+        let (multisig, redeemer) = multisig_and_redeemer(N + 1, N, TX, &signers);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_index_beyond_signatories_fails() {
+        // Plenty of signatures, so the index is smaller than the number of signatures.
+        // It must still be rejected (rather than panicking) because there is no such signatory.
+        let mut signers = all_good();
+        signers.push((N as usize, N));
+
+        let (multisig, redeemer) = multisig_and_redeemer(1, N, TX, &signers);
+        assert!(!multisig.verify(TX, 0, &redeemer));
+    }
+
+    #[test]
+    fn threshold_multisig_trailing_redeemer_bytes_fails() {
+        let (multisig, mut redeemer) = multisig_and_redeemer(N, N, TX, &all_good());
+        redeemer.push(0);
+
+        assert!(!multisig.verify(TX, 0, &redeemer));
     }
 
     #[test]
@@ -362,18 +937,339 @@ mod test {
             owner_pubkey: H256::zero(),
         };
 
-        assert!(!sr25519_signature.verify(simplified_tx, redeemer));
+        assert!(!sr25519_signature.verify(simplified_tx, 0, redeemer));
+    }
+
+    #[test]
+    fn block_height_lock_before_unlock_fails() {
+        let pair = Pair::from_seed(&[0u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign(simplified_tx);
+
+        let lock = BlockHeightLock {
+            owner_pubkey: pair.public().into(),
+            unlock_height: 100,
+        };
+
+        assert!(!lock.verify(simplified_tx, 99, sig.as_ref()));
+    }
+
+    #[test]
+    fn block_height_lock_at_unlock_passes() {
+        let pair = Pair::from_seed(&[0u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign(simplified_tx);
+
+        let lock = BlockHeightLock {
+            owner_pubkey: pair.public().into(),
+            unlock_height: 100,
+        };
+
+        assert!(lock.verify(simplified_tx, 100, sig.as_ref()));
+        assert!(lock.verify(simplified_tx, 101, sig.as_ref()));
+    }
+
+    #[test]
+    fn block_height_lock_after_unlock_with_bad_sig_fails() {
+        let pair = Pair::from_seed(&[0u8; 32]);
+        let simplified_tx = b"hello world".as_slice();
+        let sig = pair.sign(b"other message");
+
+        let lock = BlockHeightLock {
+            owner_pubkey: pair.public().into(),
+            unlock_height: 100,
+        };
+
+        assert!(!lock.verify(simplified_tx, 101, sig.as_ref()));
+    }
+
+    #[test]
+    fn timestamp_lock_before_unlock_fails() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            let pair = Pair::from_seed(&[0u8; 32]);
+            let simplified_tx = b"hello world".as_slice();
+            let sig = pair.sign(simplified_tx);
+
+            let lock = TimestampLock {
+                owner_pubkey: pair.public().into(),
+                unlock_time: 1_000,
+            };
+            BestTimestampStorage::set(999);
+
+            assert!(!lock.verify(simplified_tx, 0, sig.as_ref()));
+        });
+    }
+
+    #[test]
+    fn timestamp_lock_at_unlock_passes() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            let pair = Pair::from_seed(&[0u8; 32]);
+            let simplified_tx = b"hello world".as_slice();
+            let sig = pair.sign(simplified_tx);
+
+            let lock = TimestampLock {
+                owner_pubkey: pair.public().into(),
+                unlock_time: 1_000,
+            };
+            BestTimestampStorage::set(1_000);
+
+            assert!(lock.verify(simplified_tx, 0, sig.as_ref()));
+        });
+    }
+
+    #[test]
+    fn timestamp_lock_with_no_timestamp_fails() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            let pair = Pair::from_seed(&[0u8; 32]);
+            let simplified_tx = b"hello world".as_slice();
+            let sig = pair.sign(simplified_tx);
+
+            let lock = TimestampLock {
+                owner_pubkey: pair.public().into(),
+                unlock_time: 0,
+            };
+
+            assert!(!lock.verify(simplified_tx, 0, sig.as_ref()));
+        });
+    }
+
+    #[test]
+    fn timestamp_lock_after_unlock_with_bad_sig_fails() {
+        sp_io::TestExternalities::default().execute_with(|| {
+            let pair = Pair::from_seed(&[0u8; 32]);
+            let simplified_tx = b"hello world".as_slice();
+            let sig = pair.sign(b"other message");
+
+            let lock = TimestampLock {
+                owner_pubkey: pair.public().into(),
+                unlock_time: 1_000,
+            };
+            BestTimestampStorage::set(2_000);
+
+            assert!(!lock.verify(simplified_tx, 0, sig.as_ref()));
+        });
+    }
+
+    /// Build an HTLC where the recipient is the key from seed 0 and the refunder is the key from seed 1.
+    /// Returns the two pairs along with the HTLC so that tests can sign.
+    fn htlc(secret: &[u8], hash_algorithm: HashAlgorithm) -> (Pair, Pair, HashTimeLock) {
+        let pairs = generate_n_pairs(2);
+        let htlc = HashTimeLock {
+            hash_lock: hash_algorithm.hash(secret),
+            hash_algorithm,
+            recipient_pubkey: pairs[0].public().into(),
+            refunder_pubkey: pairs[1].public().into(),
+            refund_height: 100,
+        };
+
+        (pairs[0].clone(), pairs[1].clone(), htlc)
+    }
+
+    #[test]
+    fn htlc_claim_with_blake2_secret_passes() {
+        let simplified_tx = b"hello world".as_slice();
+        let (recipient, _, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let redeemer = UnlockHashTimeLock::Claim {
+            secret: b"secret".to_vec(),
+            signature: recipient.sign(simplified_tx),
+        };
+
+        assert!(htlc.verify(simplified_tx, 0, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_claim_with_sha256_secret_passes() {
+        let simplified_tx = b"hello world".as_slice();
+        let (recipient, _, htlc) = htlc(b"secret", HashAlgorithm::Sha2_256);
+
+        let redeemer = UnlockHashTimeLock::Claim {
+            secret: b"secret".to_vec(),
+            signature: recipient.sign(simplified_tx),
+        };
+
+        assert!(htlc.verify(simplified_tx, 0, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_claim_after_timeout_passes() {
+        let simplified_tx = b"hello world".as_slice();
+        let (recipient, _, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let redeemer = UnlockHashTimeLock::Claim {
+            secret: b"secret".to_vec(),
+            signature: recipient.sign(simplified_tx),
+        };
+
+        assert!(htlc.verify(simplified_tx, 200, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_claim_with_wrong_secret_fails() {
+        let simplified_tx = b"hello world".as_slice();
+        let (recipient, _, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let redeemer = UnlockHashTimeLock::Claim {
+            secret: b"wrong secret".to_vec(),
+            signature: recipient.sign(simplified_tx),
+        };
+
+        assert!(!htlc.verify(simplified_tx, 0, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_claim_with_secret_hashed_by_other_algorithm_fails() {
+        let simplified_tx = b"hello world".as_slice();
+        let (recipient, _, mut htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+        htlc.hash_algorithm = HashAlgorithm::Sha2_256;
+
+        let redeemer = UnlockHashTimeLock::Claim {
+            secret: b"secret".to_vec(),
+            signature: recipient.sign(simplified_tx),
+        };
+
+        assert!(!htlc.verify(simplified_tx, 0, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_claim_by_refunder_fails() {
+        let simplified_tx = b"hello world".as_slice();
+        let (_, refunder, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let redeemer = UnlockHashTimeLock::Claim {
+            secret: b"secret".to_vec(),
+            signature: refunder.sign(simplified_tx),
+        };
+
+        assert!(!htlc.verify(simplified_tx, 0, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_refund_before_timeout_fails() {
+        let simplified_tx = b"hello world".as_slice();
+        let (_, refunder, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let redeemer = UnlockHashTimeLock::Refund {
+            signature: refunder.sign(simplified_tx),
+        };
+
+        assert!(!htlc.verify(simplified_tx, 99, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_refund_at_timeout_passes() {
+        let simplified_tx = b"hello world".as_slice();
+        let (_, refunder, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let redeemer = UnlockHashTimeLock::Refund {
+            signature: refunder.sign(simplified_tx),
+        };
+
+        assert!(htlc.verify(simplified_tx, 100, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_refund_by_recipient_fails() {
+        let simplified_tx = b"hello world".as_slice();
+        let (recipient, _, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let redeemer = UnlockHashTimeLock::Refund {
+            signature: recipient.sign(simplified_tx),
+        };
+
+        assert!(!htlc.verify(simplified_tx, 100, &redeemer.encode()));
+    }
+
+    #[test]
+    fn htlc_trailing_redeemer_bytes_fails() {
+        let simplified_tx = b"hello world".as_slice();
+        let (_, refunder, htlc) = htlc(b"secret", HashAlgorithm::Blake2_256);
+
+        let mut redeemer = UnlockHashTimeLock::Refund {
+            signature: refunder.sign(simplified_tx),
+        }
+        .encode();
+        redeemer.push(0);
+
+        assert!(!htlc.verify(simplified_tx, 100, &redeemer));
     }
 
     #[test]
     fn test_verifier_passes() {
-        let result = TestVerifier { verifies: true }.verify(&[], &[]);
+        let result = TestVerifier { verifies: true }.verify(&[], 0, &[]);
         assert!(result);
     }
 
     #[test]
     fn test_verifier_fails() {
-        let result = TestVerifier { verifies: false }.verify(&[], &[]);
+        let result = TestVerifier { verifies: false }.verify(&[], 0, &[]);
         assert!(!result);
     }
+
+    /// A covenant that may only be spent into outputs that are guarded by the same covenant.
+    /// It is only possible to express this with the full `Verifier` trait.
+    #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+    struct TestCovenant;
+
+    impl<C> Verifier<TestCovenant, C> for TestCovenant {
+        fn verify_input(
+            &self,
+            _simplified_tx: &[u8],
+            transaction: &Transaction<TestCovenant, C>,
+            input_index: usize,
+            _block_height: u32,
+            _redeemer: &[u8],
+        ) -> bool {
+            input_index < transaction.inputs.len()
+                && transaction.outputs.iter().all(|o| o.verifier == *self)
+        }
+    }
+
+    fn covenant_tx(outputs: usize) -> Transaction<TestCovenant, ()> {
+        use crate::{
+            dynamic_typing::testing::Bogus,
+            types::{Input, OutputRef},
+        };
+
+        Transaction {
+            inputs: vec![Input {
+                output_ref: OutputRef {
+                    tx_hash: H256::zero(),
+                    index: 0,
+                },
+                redeemer: Vec::new(),
+            }],
+            peeks: Vec::new(),
+            outputs: (0..outputs).map(|_| (Bogus, TestCovenant).into()).collect(),
+            checker: (),
+            evictions: Vec::new(),
+            valid_until: None,
+        }
+    }
+
+    #[test]
+    fn full_verifier_sees_decoded_transaction() {
+        let tx = covenant_tx(2);
+
+        assert!(TestCovenant.verify_input(&tx.encode(), &tx, 0, 0, &[]));
+    }
+
+    #[test]
+    fn full_verifier_sees_input_index() {
+        let tx = covenant_tx(2);
+
+        assert!(!TestCovenant.verify_input(&tx.encode(), &tx, 1, 0, &[]));
+    }
+
+    #[test]
+    fn simple_verifier_works_through_blanket_impl() {
+        let tx = covenant_tx(0);
+        let pair = Pair::from_seed(&[0u8; 32]);
+        let simplified_tx = tx.encode();
+        let sig = pair.sign(&simplified_tx);
+
+        let sr25519_signature = Sr25519Signature::new(pair.public());
+
+        assert!(sr25519_signature.verify_input(&simplified_tx, &tx, 0, 0, sig.as_ref()));
+    }
 }
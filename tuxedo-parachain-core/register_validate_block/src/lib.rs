@@ -23,22 +23,29 @@ struct RegisterValidateBlockInput {
     pub verifier: Ident,
     _comma2: Token![,],
     pub constraint_checker: Ident,
+    pub migrations: Option<Ident>,
 }
 
 impl Parse for RegisterValidateBlockInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let parsed = Self {
+        let mut parsed = Self {
             block: input.parse()?,
             _comma1: input.parse()?,
             verifier: input.parse()?,
             _comma2: input.parse()?,
             constraint_checker: input.parse()?,
+            migrations: None,
         };
 
+        // The runtime's migrations are optional, and default to none.
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            parsed.migrations = Some(input.parse()?);
+        }
+
         if !input.is_empty() {
             return Err(Error::new(
                 input.span(),
-                "Expected exactly three parameters: Block, Verifier, ConstraintChecker.",
+                "Expected three or four parameters: Block, Verifier, ConstraintChecker, and optionally Migrations.",
             ));
         }
 
@@ -59,6 +66,10 @@ pub fn register_validate_block(input: proc_macro::TokenStream) -> proc_macro::To
     let block = input.block.clone();
     let verifier = input.verifier.clone();
     let constraint_checker = input.constraint_checker.clone();
+    let migrations = match input.migrations {
+        Some(migrations) => quote::quote! { #migrations },
+        None => quote::quote! { () },
+    };
 
     // A way to refer to the tuxedo_parachain_core crate from within the macro.
     let crate_ = match crate_() {
@@ -114,6 +125,7 @@ pub fn register_validate_block(input: proc_macro::TokenStream) -> proc_macro::To
                         #block,
                         #verifier,
                         #constraint_checker,
+                        #migrations,
                     >(params);
 
                     // Step 3: Write the return value back into the shared memory
@@ -15,7 +15,9 @@ pub trait ParachainExecutiveExtension<Header> {
     fn collect_collation_info(header: &Header) -> cumulus_primitives_core::CollationInfo;
 }
 
-impl<B: BlockT, V, C> ParachainExecutiveExtension<B::Header> for Executive<B, V, C> {
+impl<B: BlockT, V, C, P, U, M> ParachainExecutiveExtension<B::Header>
+    for Executive<B, V, C, P, U, M>
+{
     fn collect_collation_info(header: &B::Header) -> cumulus_primitives_core::CollationInfo {
         // The implementation here is simple. Most of the fields are related to xcm and parachain runtime upgrades,
         // neither or which are supported in the PoC, so they are left blank.
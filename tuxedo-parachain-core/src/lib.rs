@@ -135,17 +135,14 @@ pub use tuxedo_register_validate_block::register_validate_block;
 // and go for a more strongly typed aggregate type approach.
 // Tracking issue: https://github.com/Off-Narrative-Labs/Tuxedo/issues/153
 /// A wrapper type around Cumulus's ParachainInherentData type that can be stored.
-#[derive(Encode, Decode, DebugNoBound, CloneNoBound, scale_info::TypeInfo)]
+#[derive(Encode, Decode, DebugNoBound, CloneNoBound, scale_info::TypeInfo, UtxoData)]
+#[utxo_data(type_id = *b"para")]
 
 /// A wrapper type around Cumulus's ParachainInherentData type.
 /// This type is convertable Into and From the inner type.
 /// This is necessary so that we can implement the `UtxoData` trait.
 pub struct ParachainInherentDataUtxo(ParachainInherentData);
 
-impl UtxoData for ParachainInherentDataUtxo {
-    const TYPE_ID: [u8; 4] = *b"para";
-}
-
 impl From<ParachainInherentDataUtxo> for ParachainInherentData {
     fn from(val: ParachainInherentDataUtxo) -> Self {
         val.0
@@ -11,7 +11,10 @@ use cumulus_primitives_parachain_inherent::ParachainInherentData;
 use polkadot_parachain_primitives::primitives::{
     HeadData, RelayChainBlockNumber, ValidationResult,
 };
-use tuxedo_core::{types::Transaction, ConstraintChecker, Executive, Verifier};
+use tuxedo_core::{
+    migration::UtxoMigration, types::Transaction, utxo_set::TransparentUtxoSet, ConstraintChecker,
+    Executive, Verifier,
+};
 
 use parity_scale_codec::Encode;
 use scale_info::TypeInfo;
@@ -61,7 +64,7 @@ fn with_externalities<F: FnOnce(&mut dyn Externalities) -> R, R>(f: F) -> R {
 /// ensuring that the final storage root matches the storage root in the header of the block. In the
 /// end we return back the [`ValidationResult`] with all the required information for the validator.
 #[doc(hidden)]
-pub fn validate_block<B, V, C>(
+pub fn validate_block<B, V, C, M>(
     MemoryOptimizedValidationParams {
         block_data,
         parent_head,
@@ -72,8 +75,9 @@ pub fn validate_block<B, V, C>(
 where
     B: BlockT<Extrinsic = Transaction<V, C>>,
     Transaction<V, C>: Extrinsic,
-    V: TypeInfo + Verifier + 'static,
+    V: TypeInfo + Verifier<V, C> + 'static,
     C: TypeInfo + ConstraintChecker<V> + 'static, // + Into<SetParachainInfo<V>>,
+    M: UtxoMigration,
 {
     sp_runtime::runtime_logger::RuntimeLogger::init();
     log::info!(target: "tuxvb", "🕵️🕵️🕵️🕵️Entering validate_block implementation");
@@ -195,7 +199,8 @@ where
         log::info!(target: "tuxvb", "🕵️🕵️🕵️🕵️ In the run_with_externalities closure");
         let head_data = HeadData(block.header().encode());
 
-        Executive::<B, V, C>::execute_block(block);
+        // The migrations must match the runtime's executive, or the state root will not match.
+        Executive::<B, V, C, (), TransparentUtxoSet<V>, M>::execute_block(block);
 
         log::info!(target: "tuxvb", "🕵️🕵️🕵️🕵️ returned from execute block");
 
@@ -222,7 +227,8 @@ where
 /// The data has to be extracted from the extrinsics themselves.
 /// I want the runtime to expose a method to do this, and I also want it to
 /// be nice and flexible by searching for the right transactions.
-/// For now I have a hacky implementation that assumes the parachain inherent is last
+/// For now I have a hacky implementation that looks for the first inherent whose first output
+/// is the parachain inherent data.
 fn extract_parachain_inherent_data<B, V, C>(block: &B) -> ParachainInherentData
 where
     B: BlockT<Extrinsic = Transaction<V, C>>,
@@ -257,19 +263,20 @@ where
     // 	})
     // 	.expect("Could not find `set_validation_data` inherent")
 
+    // The parachain inherent is no longer necessarily the last inherent, because closing
+    // inherents come after it. So we find it by the type of its output instead.
     block
         .extrinsics()
         .iter()
         .take_while(|&e| !e.is_signed().unwrap_or(true))
-        .collect::<Vec<_>>()
-        .last()
-        .expect("There should be at least one inherent extrinsic which is the parachain inherent.")
-        .outputs
-        .get(0)
-        .expect("Parachain inherent should be first and should have exactly one output.")
-        .payload
-        .extract::<ParachainInherentDataUtxo>()
-        .expect("Should decode to proper type based on the position in the block.")
+        .find_map(|e| {
+            e.outputs
+                .first()?
+                .payload
+                .extract::<ParachainInherentDataUtxo>()
+                .ok()
+        })
+        .expect("There should be an inherent extrinsic whose first output is the parachain inherent data.")
         .into()
 }
 
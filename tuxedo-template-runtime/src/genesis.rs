@@ -1,49 +1,199 @@
 //! Helper module to build a genesis configuration for the template runtime.
+//!
+//! The genesis outputs are described with typed, human-readable entries so that they can be
+//! listed in chain-spec JSON, for example `{ "coin": { "amount": 100, "owner": "0x..." } }`.
 
 use super::{
     kitties::{KittyData, Parent},
-    money::Coin,
-    OuterConstraintChecker, OuterConstraintCheckerInherentHooks, OuterVerifier, WASM_BINARY,
+    money::{Coin, MintAuthority, Supply, SupplyInfo, SupplyStorage},
+    OuterConstraintChecker, OuterConstraintCheckerInherentHooks, OuterVerifier, Transaction,
+    WASM_BINARY,
 };
-use hex_literal::hex;
+use serde::{Deserialize, Serialize};
+use sp_core::{storage::Storage, H256};
+use sp_runtime::BuildStorage;
 use tuxedo_core::{
+    genesis::TuxedoGenesisConfig,
     inherents::InherentInternal,
     verifier::{Sr25519Signature, ThresholdMultiSignature, UpForGrabs},
 };
 
-/// Helper type for the ChainSpec.
-pub type RuntimeGenesisConfig =
-    tuxedo_core::genesis::TuxedoGenesisConfig<OuterVerifier, OuterConstraintChecker>;
-
-const SHAWN_PUB_KEY_BYTES: [u8; 32] =
-    hex!("d2bf4b844dfefd6772a8843e669f943408966a977e3ae2af1dd78e0f55f4df67");
-const ANDREW_PUB_KEY_BYTES: [u8; 32] =
-    hex!("baa81e58b1b4d053c2e86d93045765036f9d265c7dfe8b9693bbc2c0f048d93a");
-
-pub fn development_genesis_config() -> RuntimeGenesisConfig {
-    let signatories = vec![SHAWN_PUB_KEY_BYTES.into(), ANDREW_PUB_KEY_BYTES.into()];
-
-    // The inherents are computed using the appropriate method, and placed before the extrinsics.
-    let mut genesis_transactions = OuterConstraintCheckerInherentHooks::genesis_transactions();
-
-    genesis_transactions.extend([
-        // Money Transactions
-        Coin::<0>::mint(100, Sr25519Signature::new(SHAWN_PUB_KEY_BYTES)),
-        Coin::<0>::mint(100, ThresholdMultiSignature::new(1, signatories)),
-        // Kitty Transactions
-        // KittyData::mint(Parent::mom(), b"mother",  UpForGrabs),
-        //  KittyData::mint(Parent::dad(), b"father",  UpForGrabs),
-        KittyData::mint(Parent::mom(), b"mother", UpForGrabs),
-        KittyData::mint(Parent::dad(), b"father", UpForGrabs),
-        // TODO: Initial Transactions for Existence
-    ]);
-
-    RuntimeGenesisConfig::new(
-        WASM_BINARY
-            .expect("Runtime WASM binary must exist.")
-            .to_vec(),
-        genesis_transactions,
-    )
+/// The ID of the kind of coin whose supply the runtime tracks.
+const TRACKED_COIN_ID: u8 = 1;
+
+/// The genesis outputs of the development and local testnet chains, as chain-spec JSON.
+///
+/// Both the standalone and the parachain node use these outputs. The file is compiled into the
+/// node, so editing it requires a rebuild. To change the outputs of a built node, export a chain
+/// spec with `build-spec` and edit its `outputs` instead.
+pub const DEV_GENESIS_OUTPUTS: &str = include_str!("../res/dev_genesis_outputs.json");
+
+/// An output to create in the genesis block.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub enum GenesisOutput {
+    /// A coin owned by a single sr25519 key.
+    Coin { amount: u128, owner: H256 },
+    /// A coin owned by a threshold multisignature of sr25519 keys.
+    Multisig {
+        amount: u128,
+        threshold: u8,
+        signatories: Vec<H256>,
+    },
+    /// The authority to mint coins, held by a threshold multisignature of sr25519 keys.
+    /// It is only needed when the runtime's mint policy does not allow free minting.
+    MintAuthority {
+        threshold: u8,
+        signatories: Vec<H256>,
+    },
+    /// A kitty that anyone may take. Its DNA is the hash of the preimage.
+    #[serde(rename_all = "camelCase")]
+    Kitty {
+        parent: GenesisKittyParent,
+        dna_preimage: String,
+    },
+}
+
+/// Whether a genesis kitty is a mom or a dad.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum GenesisKittyParent {
+    Mom,
+    Dad,
+}
+
+impl GenesisOutput {
+    /// The genesis transaction that creates this output.
+    pub fn transaction(&self) -> Transaction {
+        match self {
+            Self::Coin { amount, owner } => Coin::<0>::mint(*amount, Sr25519Signature::new(*owner)),
+            Self::Multisig {
+                amount,
+                threshold,
+                signatories,
+            } => Coin::<0>::mint(
+                *amount,
+                ThresholdMultiSignature::new(*threshold, signatories.clone()),
+            ),
+            Self::MintAuthority {
+                threshold,
+                signatories,
+            } => MintAuthority::<0>::genesis(ThresholdMultiSignature::new(
+                *threshold,
+                signatories.clone(),
+            )),
+            Self::Kitty {
+                parent,
+                dna_preimage,
+            } => {
+                let parent = match parent {
+                    GenesisKittyParent::Mom => Parent::mom(),
+                    GenesisKittyParent::Dad => Parent::dad(),
+                };
+                KittyData::mint(parent, dna_preimage.as_bytes(), UpForGrabs)
+            }
+        }
+    }
+
+    /// Whether this output is state that only exists from genesis, such as a mint authority.
+    /// No constraint checker accepts creating it, so strict validation trusts it instead.
+    pub fn is_genesis_only(&self) -> bool {
+        matches!(self, Self::MintAuthority { .. })
+    }
+}
+
+/// The genesis configuration of the template runtime. This is what appears in the chain spec.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RuntimeGenesisConfig {
+    /// The runtime's wasm code.
+    #[serde(with = "sp_core::bytes")]
+    pub code: Vec<u8>,
+    /// The outputs to create in the genesis block, in order.
+    pub outputs: Vec<GenesisOutput>,
+    /// Whether to run each output's constraint checker when building the genesis storage.
+    /// This catches mistakes such as zero-value coins.
+    #[serde(default)]
+    pub strict_validation: bool,
+}
+
+impl RuntimeGenesisConfig {
+    /// Create a genesis config with the given outputs and the runtime's own wasm code.
+    pub fn new(outputs: Vec<GenesisOutput>) -> Self {
+        Self {
+            code: WASM_BINARY
+                .expect("Runtime WASM binary must exist.")
+                .to_vec(),
+            outputs,
+            strict_validation: false,
+        }
+    }
+
+    /// Validate the genesis transactions when building the genesis storage.
+    pub fn with_strict_validation(mut self) -> Self {
+        self.strict_validation = true;
+        self
+    }
+
+    /// All the genesis transactions. The inherents' genesis transactions are computed using the
+    /// appropriate method, and placed before the transactions that create the configured outputs.
+    /// The genesis-only state is created first, so that strict validation can trust it.
+    pub fn genesis_transactions(&self) -> Vec<Transaction> {
+        let mut genesis_transactions = OuterConstraintCheckerInherentHooks::genesis_transactions();
+        genesis_transactions.extend(self.genesis_state_transactions());
+        genesis_transactions.extend(
+            self.outputs
+                .iter()
+                .filter(|o| !o.is_genesis_only())
+                .map(GenesisOutput::transaction),
+        );
+        genesis_transactions
+    }
+
+    /// The transactions that create genesis-only state: the supply of the tracked coin,
+    /// followed by the genesis-only outputs.
+    fn genesis_state_transactions(&self) -> Vec<Transaction> {
+        let mut transactions = vec![Supply::<TRACKED_COIN_ID>::genesis(
+            self.tracked_coin_supply().circulating,
+        )];
+        transactions.extend(
+            self.outputs
+                .iter()
+                .filter(|o| o.is_genesis_only())
+                .map(GenesisOutput::transaction),
+        );
+        transactions
+    }
+
+    /// The initial supply of the tracked coin. None of the genesis outputs are coins of that
+    /// kind, so nothing is in circulation.
+    fn tracked_coin_supply(&self) -> SupplyInfo {
+        SupplyInfo::default()
+    }
+}
+
+impl BuildStorage for RuntimeGenesisConfig {
+    fn assimilate_storage(&self, storage: &mut Storage) -> Result<(), String> {
+        let config = TuxedoGenesisConfig::<OuterVerifier, OuterConstraintChecker>::new(
+            self.code.clone(),
+            self.genesis_transactions(),
+        )
+        .with_trusted_transactions(self.genesis_state_transactions().len() as u32);
+        if self.strict_validation {
+            config
+                .with_strict_validation()
+                .assimilate_storage(storage)?;
+        } else {
+            config.assimilate_storage(storage)?;
+        }
+
+        // Note the supply created above so that the `MoneySupplyApi` serves it from the start.
+        let (key, value) =
+            SupplyStorage::genesis_entry(TRACKED_COIN_ID, &self.tracked_coin_supply());
+        storage.top.insert(key, value);
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -55,12 +205,12 @@ mod tests {
     use sp_api::HashT;
     use sp_core::testing::SR25519;
     use sp_keystore::{testing::MemoryKeystore, Keystore, KeystoreExt};
-    use sp_runtime::{traits::BlakeTwo256, BuildStorage};
+    use sp_runtime::traits::BlakeTwo256;
     use std::sync::Arc;
     use tuxedo_core::{
         dynamic_typing::{DynamicallyTypedData, UtxoData},
-        inherents::InherentInternal,
         types::{Output, OutputRef},
+        utxo_set::utxo_storage_key,
     };
 
     // other random account generated with subkey
@@ -82,21 +232,23 @@ mod tests {
             .unwrap()
             .0;
 
-        let signatories = vec![shawn_pub_key_bytes.into(), andrew_pub_key_bytes.into()];
-
-        let mut genesis_transactions = OuterConstraintCheckerInherentHooks::genesis_transactions();
-        genesis_transactions.extend([
-            // Money Transactions
-            Coin::<0>::mint(100, Sr25519Signature::new(shawn_pub_key_bytes)),
-            Coin::<0>::mint(100, ThresholdMultiSignature::new(1, signatories)),
-        ]);
+        RuntimeGenesisConfig::new(vec![
+            GenesisOutput::Coin {
+                amount: 100,
+                owner: shawn_pub_key_bytes.into(),
+            },
+            GenesisOutput::Multisig {
+                amount: 100,
+                threshold: 1,
+                signatories: vec![shawn_pub_key_bytes.into(), andrew_pub_key_bytes.into()],
+            },
+        ])
+    }
 
-        RuntimeGenesisConfig::new(
-            WASM_BINARY
-                .expect("Runtime WASM binary must exist.")
-                .to_vec(),
-            genesis_transactions,
-        )
+    /// The index of the genesis transaction that creates the first configured output.
+    /// The configured outputs, genesis-only ones first, are created by the last genesis transactions.
+    fn first_output_index(config: &RuntimeGenesisConfig) -> usize {
+        config.genesis_transactions().len() - config.outputs.len()
     }
 
     fn new_test_ext() -> sp_io::TestExternalities {
@@ -129,12 +281,8 @@ mod tests {
                 },
             };
 
-            let inherents_len = OuterConstraintCheckerInherentHooks::genesis_transactions().len();
-
-            let tx = default_runtime_genesis_config()
-                .get_transaction(inherents_len)
-                .unwrap()
-                .clone();
+            let config = default_runtime_genesis_config();
+            let tx = config.genesis_transactions()[first_output_index(&config)].clone();
 
             assert_eq!(tx.outputs.get(0), Some(&genesis_utxo));
 
@@ -145,7 +293,7 @@ mod tests {
             };
 
             let encoded_utxo =
-                sp_io::storage::get(&output_ref.encode()).expect("Retrieve Genesis UTXO");
+                sp_io::storage::get(&utxo_storage_key(&output_ref)).expect("Retrieve Genesis UTXO");
             let utxo = Output::decode(&mut &encoded_utxo[..]).expect("Can Decode UTXO correctly");
             assert_eq!(utxo, genesis_utxo);
         })
@@ -173,12 +321,8 @@ mod tests {
                 },
             };
 
-            let inherents_len = OuterConstraintCheckerInherentHooks::genesis_transactions().len();
-
-            let tx = default_runtime_genesis_config()
-                .get_transaction(1 + inherents_len)
-                .unwrap()
-                .clone();
+            let config = default_runtime_genesis_config();
+            let tx = config.genesis_transactions()[1 + first_output_index(&config)].clone();
 
             assert_eq!(tx.outputs.get(0), Some(&genesis_multi_sig_utxo));
 
@@ -188,10 +332,138 @@ mod tests {
                 index: 0_u32,
             };
 
-            let encoded_utxo =
-                sp_io::storage::get(&output_ref.encode()).expect("Retrieve Genesis MultiSig UTXO");
+            let encoded_utxo = sp_io::storage::get(&utxo_storage_key(&output_ref))
+                .expect("Retrieve Genesis MultiSig UTXO");
             let utxo = Output::decode(&mut &encoded_utxo[..]).expect("Can Decode UTXO correctly");
             assert_eq!(utxo, genesis_multi_sig_utxo);
         })
     }
+
+    #[test]
+    fn genesis_config_json_round_trip() {
+        let config = default_runtime_genesis_config();
+
+        let json = serde_json::to_string(&config).unwrap();
+        let decoded: RuntimeGenesisConfig = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded, config);
+        assert_eq!(
+            decoded.build_storage().unwrap(),
+            config.build_storage().unwrap()
+        );
+    }
+
+    #[test]
+    fn genesis_outputs_from_typed_json() {
+        let json = r#"[
+            { "coin": { "amount": 100, "owner": "0xd2bf4b844dfefd6772a8843e669f943408966a977e3ae2af1dd78e0f55f4df67" } },
+            { "multisig": { "amount": 50, "threshold": 1, "signatories": [
+                "0xd2bf4b844dfefd6772a8843e669f943408966a977e3ae2af1dd78e0f55f4df67",
+                "0xbaa81e58b1b4d053c2e86d93045765036f9d265c7dfe8b9693bbc2c0f048d93a"
+            ] } },
+            { "kitty": { "parent": "mom", "dnaPreimage": "mother" } }
+        ]"#;
+        let shawn = H256(hex_literal::hex!(
+            "d2bf4b844dfefd6772a8843e669f943408966a977e3ae2af1dd78e0f55f4df67"
+        ));
+        let andrew = H256(hex_literal::hex!(
+            "baa81e58b1b4d053c2e86d93045765036f9d265c7dfe8b9693bbc2c0f048d93a"
+        ));
+
+        let outputs: Vec<GenesisOutput> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            outputs,
+            vec![
+                GenesisOutput::Coin {
+                    amount: 100,
+                    owner: shawn,
+                },
+                GenesisOutput::Multisig {
+                    amount: 50,
+                    threshold: 1,
+                    signatories: vec![shawn, andrew],
+                },
+                GenesisOutput::Kitty {
+                    parent: GenesisKittyParent::Mom,
+                    dna_preimage: "mother".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            outputs[2].transaction(),
+            KittyData::mint(Parent::mom(), b"mother", UpForGrabs)
+        );
+    }
+
+    #[test]
+    fn dev_genesis_outputs_are_valid() {
+        let outputs: Vec<GenesisOutput> = serde_json::from_str(DEV_GENESIS_OUTPUTS).unwrap();
+        let config = RuntimeGenesisConfig::new(outputs).with_strict_validation();
+
+        assert!(config.build_storage().is_ok());
+    }
+
+    #[test]
+    fn genesis_output_rejects_unknown_kind() {
+        let json = r#"{ "pony": { "amount": 100 } }"#;
+
+        assert!(serde_json::from_str::<GenesisOutput>(json).is_err());
+    }
+
+    #[test]
+    fn strict_validation_accepts_valid_outputs() {
+        let config = default_runtime_genesis_config().with_strict_validation();
+
+        assert!(config.build_storage().is_ok());
+    }
+
+    #[test]
+    fn genesis_notes_tracked_supply() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                SupplyStorage::get(TRACKED_COIN_ID),
+                Some(SupplyInfo::default())
+            );
+        })
+    }
+
+    #[test]
+    fn strict_validation_trusts_mint_authority() {
+        let signatories = vec![H256::repeat_byte(1), H256::repeat_byte(2)];
+        let config = RuntimeGenesisConfig::new(vec![
+            GenesisOutput::Coin {
+                amount: 100,
+                owner: H256::repeat_byte(1),
+            },
+            GenesisOutput::MintAuthority {
+                threshold: 2,
+                signatories: signatories.clone(),
+            },
+        ]);
+
+        // The mint authority is created before the coin, along with the rest of the genesis-only state.
+        assert_eq!(
+            config.genesis_transactions()[first_output_index(&config)],
+            MintAuthority::<0>::genesis(ThresholdMultiSignature::new(2, signatories))
+        );
+
+        assert!(config.with_strict_validation().build_storage().is_ok());
+    }
+
+    #[test]
+    fn strict_validation_rejects_zero_value_coin() {
+        let config = RuntimeGenesisConfig::new(vec![GenesisOutput::Coin {
+            amount: 0,
+            owner: H256::zero(),
+        }]);
+
+        // Without strict validation the coin is written blindly.
+        assert!(config.build_storage().is_ok());
+
+        let index = first_output_index(&config);
+        let error = config.with_strict_validation().build_storage().unwrap_err();
+        assert!(error.starts_with(&format!("Genesis transaction {} is invalid", index)));
+        assert!(error.contains("ZeroValueCoin"));
+    }
 }
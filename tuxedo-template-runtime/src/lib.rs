@@ -19,7 +19,7 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 
 use sp_api::impl_runtime_apis;
-use sp_core::OpaqueMetadata;
+use sp_core::{OpaqueMetadata, H256};
 use sp_inherents::InherentData;
 use sp_runtime::{
     create_runtime_str, impl_opaque_keys,
@@ -34,12 +34,21 @@ use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
 use tuxedo_core::{
+    dry_run::DryRunReport,
+    migration::MigrateUnprefixedUtxos,
     tuxedo_constraint_checker, tuxedo_verifier,
-    types::Transaction as TuxedoTransaction,
-    verifier::{Sr25519Signature, ThresholdMultiSignature, UpForGrabs},
+    types::{OutputRef, Transaction as TuxedoTransaction, UtxoError},
+    utxo_set::TransparentUtxoSet,
+    verifier::{
+        BestTimestampStorage, BlockHeightLock, EcdsaSignature, Ed25519Signature, HashTimeLock,
+        Sr25519Signature, ThresholdMultiSignature, TimestampLock, Unspendable, UpForGrabs,
+    },
 };
 
 pub use amoeba;
+pub use assets;
+pub use exchange;
+pub use fees;
 pub use kitties;
 pub use money;
 pub use poe;
@@ -107,7 +116,18 @@ pub type Transaction = TuxedoTransaction<OuterVerifier, OuterConstraintChecker>;
 pub type BlockNumber = u32;
 pub type Header = sp_runtime::generic::Header<BlockNumber, BlakeTwo256>;
 pub type Block = sp_runtime::generic::Block<Header, Transaction>;
-pub type Executive = tuxedo_core::Executive<Block, OuterVerifier, OuterConstraintChecker>;
+pub type Executive = tuxedo_core::Executive<
+    Block,
+    OuterVerifier,
+    OuterConstraintChecker,
+    (),
+    TransparentUtxoSet<OuterVerifier>,
+    UtxoMigrations,
+>;
+
+/// The migrations that run at the start of every block.
+/// Chains launched before UTXOs were stored under a prefix need their UTXOs moved.
+pub type UtxoMigrations = MigrateUnprefixedUtxos<OuterVerifier, OuterConstraintChecker>;
 pub type Output = tuxedo_core::types::Output<OuterVerifier>;
 
 impl sp_runtime::traits::GetNodeBlockType for Runtime {
@@ -129,6 +149,12 @@ pub enum OuterVerifier {
     Sr25519Signature(Sr25519Signature),
     UpForGrabs(UpForGrabs),
     ThresholdMultiSignature(ThresholdMultiSignature),
+    BlockHeightLock(BlockHeightLock),
+    TimestampLock(TimestampLock),
+    HashTimeLock(HashTimeLock),
+    Ed25519Signature(Ed25519Signature),
+    EcdsaSignature(EcdsaSignature),
+    Unspendable(Unspendable),
 }
 
 impl poe::PoeConfig for Runtime {
@@ -137,10 +163,62 @@ impl poe::PoeConfig for Runtime {
     }
 }
 
+// This is a development runtime, so anyone may mint coins. A production runtime should
+// disallow free minting and place a `MintAuthority` in its genesis state instead, for example
+// with a `mintAuthority` genesis output.
+// Spends may deposit the value they burn as fees, which the fees piece pays to block authors.
+// Its supply is not tracked, because tracked mints must evict the previous `Supply`, and the
+// wallets mint this coin without any evictions. So the `MoneySupplyApi` serves `None` for it.
+impl money::MintPolicy<0> for Runtime {
+    const FREE_MINTING: bool = true;
+    const COLLECT_FEES: bool = true;
+}
+
+// A second kind of coin, so that the two can be exchanged. It is minted freely like the first,
+// but spending it does not pay fees. Its supply is tracked and served by the `MoneySupplyApi`,
+// so each mint or burn must evict the previous `Supply` and create an updated one.
+// The genesis config places the first `Supply` in state.
+impl money::MintPolicy<1> for Runtime {
+    const FREE_MINTING: bool = true;
+    const TRACK_SUPPLY: bool = true;
+}
+
+impl fees::FeesConfig for Runtime {
+    fn block_author() -> Option<H256> {
+        use sp_application_crypto::ByteArray;
+
+        // Aura authors take turns, one per slot, and note the slot in a pre-runtime digest.
+        let slot = Executive::block_header().digest.convert_first(|item| {
+            item.pre_runtime_try_to::<sp_consensus_aura::Slot>(&sp_consensus_aura::AURA_ENGINE_ID)
+        })?;
+        let authorities = Self::aura_authorities();
+        let index = (*slot).checked_rem(authorities.len() as u64)?;
+        authorities
+            .get(index as usize)
+            .map(|author| H256::from_slice(author.as_slice()))
+    }
+
+    fn parent_block_fees<const ID: u8>(
+        authoring_inherent_data: &InherentData,
+    ) -> Vec<(OutputRef, u128)> {
+        let parent: Block = authoring_inherent_data
+            .get_data(&tuxedo_core::inherents::PARENT_INHERENT_IDENTIFIER)
+            .expect("Parent block inherent data should be able to decode.")
+            .expect("Parent block should be present among authoring inherent data.");
+        fees::fee_deposits::<ID, _, _>(parent.extrinsics())
+    }
+
+    fn deposit_exists(output_ref: &OutputRef) -> bool {
+        Executive::peek_utxo(output_ref).is_some()
+    }
+}
+
 impl timestamp::TimestampConfig for Runtime {
     fn block_height() -> u32 {
         Executive::block_height()
     }
+
+    type SetBestTimestampStorage = BestTimestampStorage;
 }
 
 #[cfg(feature = "parachain")]
@@ -168,7 +246,7 @@ impl parachain_piece::ParachainPieceConfig for Runtime {
 #[cfg(feature = "parachain")]
 pub enum OuterConstraintChecker {
     /// Checks monetary transactions in a basic fungible cryptocurrency
-    Money(money::MoneyConstraintChecker<0>),
+    Money(money::MoneyWithPolicy<0, Runtime>),
     /// Checks Free Kitty transactions
     FreeKittyConstraintChecker(kitties::FreeKittyConstraintChecker),
     /// Checks Paid Kitty transactions
@@ -190,11 +268,18 @@ pub enum OuterConstraintChecker {
     SetTimestamp(timestamp::SetTimestamp<Runtime>),
     /// Upgrade the Wasm Runtime
     RuntimeUpgrade(runtime_upgrade::RuntimeUpgrade),
-
-    // TODO This one is last for now so that I can write a hacky algorithm to scrape
-    // the inherent data and assume it is last.
     /// Set some parachain related information via an inherent extrinsic.
     ParachainInfo(parachain_piece::SetParachainInfo<Runtime>),
+    /// Pay the fees deposited in the previous block to the current author via a closing inherent extrinsic.
+    PayFees(fees::PayFees<Runtime, 0>),
+    /// Checks monetary transactions in a second fungible cryptocurrency
+    SecondMoney(money::MoneyWithPolicy<1, Runtime>),
+    /// Exchanges the first cryptocurrency for the second through atomic swaps or swap offers
+    Exchange(exchange::ExchangeConstraintChecker<0, 1>),
+    /// Exchanges the second cryptocurrency for the first through atomic swaps or swap offers
+    ReverseExchange(exchange::ExchangeConstraintChecker<1, 0>),
+    /// Register, mint, and spend assets that are created on chain
+    Assets(assets::AssetConstraintChecker<0>),
 }
 
 /// A constraint checker is a piece of logic that can be used to check a transaction.
@@ -205,7 +290,7 @@ pub enum OuterConstraintChecker {
 #[cfg(not(feature = "parachain"))]
 pub enum OuterConstraintChecker {
     /// Checks monetary transactions in a basic fungible cryptocurrency
-    Money(money::MoneyConstraintChecker<0>),
+    Money(money::MoneyWithPolicy<0, Runtime>),
     /// Checks Free Kitty transactions
     FreeKitty(kitties::FreeKittyConstraintChecker),
     /// Checks Paid Kitty transactions
@@ -227,10 +312,19 @@ pub enum OuterConstraintChecker {
     SetTimestamp(timestamp::SetTimestamp<Runtime>),
     /// Upgrade the Wasm Runtime
     RuntimeUpgrade(runtime_upgrade::RuntimeUpgrade),
-
     /// A Dummy Constraint Checker to make the encoding compatible with the parachain.
     /// This does nothing.
     ParachainInfo(DummyParachainInfo),
+    /// Pay the fees deposited in the previous block to the current author via a closing inherent extrinsic.
+    PayFees(fees::PayFees<Runtime, 0>),
+    /// Checks monetary transactions in a second fungible cryptocurrency
+    SecondMoney(money::MoneyWithPolicy<1, Runtime>),
+    /// Exchanges the first cryptocurrency for the second through atomic swaps or swap offers
+    Exchange(exchange::ExchangeConstraintChecker<0, 1>),
+    /// Exchanges the second cryptocurrency for the first through atomic swaps or swap offers
+    ReverseExchange(exchange::ExchangeConstraintChecker<1, 0>),
+    /// Register, mint, and spend assets that are created on chain
+    Assets(assets::AssetConstraintChecker<0>),
 }
 
 #[derive(
@@ -248,9 +342,15 @@ impl tuxedo_core::SimpleConstraintChecker for DummyParachainInfo {
         _input_data: &[tuxedo_core::dynamic_typing::DynamicallyTypedData],
         _peeks: &[tuxedo_core::dynamic_typing::DynamicallyTypedData],
         _output_data: &[tuxedo_core::dynamic_typing::DynamicallyTypedData],
+        _evicted_data: &[tuxedo_core::dynamic_typing::DynamicallyTypedData],
     ) -> Result<TransactionPriority, ()> {
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<tuxedo_core::dynamic_typing::UtxoTypeInfo> {
+        // It has no payload types of its own.
+        Vec::new()
+    }
 }
 
 /// The main struct in this module.
@@ -443,6 +543,37 @@ impl_runtime_apis! {
         }
     }
 
+    impl tuxedo_core::utxo_set::TuxedoUtxoApi<Block, OuterVerifier> for Runtime {
+        fn peek_utxo(output_ref: OutputRef) -> Option<Output> {
+            Executive::peek_utxo(&output_ref)
+        }
+    }
+
+    impl money::MoneySupplyApi<Block> for Runtime {
+        fn supply(id: u8) -> Option<money::SupplyInfo> {
+            money::SupplyStorage::get(id)
+        }
+    }
+
+    impl assets::AssetRegistryApi<Block> for Runtime {
+        fn asset_metadata(asset_id: H256) -> Option<assets::AssetMetadata> {
+            assets::AssetRegistryStorage::get(&asset_id)
+        }
+    }
+
+    impl tuxedo_core::dry_run::TuxedoDryRunApi<
+        Block,
+        OuterVerifier,
+        OuterConstraintChecker,
+        OuterConstraintCheckerError,
+    > for Runtime {
+        fn dry_run_transaction(
+            transaction: Transaction,
+        ) -> Result<DryRunReport<OuterVerifier>, UtxoError<OuterConstraintCheckerError>> {
+            Executive::dry_run_transaction(&transaction)
+        }
+    }
+
     #[cfg(feature = "parachain")]
     impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
         fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
@@ -454,4 +585,9 @@ impl_runtime_apis! {
 
 // Register the `validate_block` function that Polkadot validators will call to verify this parachain block.
 #[cfg(feature = "parachain")]
-tuxedo_parachain_core::register_validate_block!(Block, OuterVerifier, OuterConstraintChecker);
+tuxedo_parachain_core::register_validate_block!(
+    Block,
+    OuterVerifier,
+    OuterConstraintChecker,
+    UtxoMigrations
+);
@@ -8,7 +8,11 @@ use clap::{ArgAction::Append, Args, Parser, Subcommand};
 use sp_core::H256;
 use tuxedo_core::types::OutputRef;
 
-use crate::{h256_from_string, keystore::SHAWN_PUB_KEY, output_ref_from_string, DEFAULT_ENDPOINT};
+use crate::{
+    h256_from_string,
+    keystore::{KeyScheme, SHAWN_PUB_KEY},
+    output_ref_from_string, DEFAULT_ENDPOINT,
+};
 use crate::parse_recipient_coins;
 /// The default number of coins to be minted.
 pub const DEFAULT_MINT_VALUE: &str = "100";
@@ -91,6 +95,10 @@ pub enum Command {
     InsertKey {
         /// Seed phrase of the key to insert.
         seed: String,
+
+        /// The signature scheme of the key.
+        #[arg(long, value_enum, default_value_t)]
+        scheme: KeyScheme,
         // /// Height from which the blockchain should be scanned to sync outputs
         // /// belonging to this address. If non is provided, no re-syncing will
         // /// happen and this key will be treated like a new key.
@@ -101,6 +109,10 @@ pub enum Command {
     GenerateKey {
         /// Initialize a public/private key pair with a password
         password: Option<String>,
+
+        /// The signature scheme of the key.
+        #[arg(long, value_enum, default_value_t)]
+        scheme: KeyScheme,
     },
 
     /// Show public information about all the keys in the keystore.
@@ -225,6 +237,10 @@ pub struct SpendArgs {
     #[arg(long, short, verbatim_doc_comment, value_parser = parse_recipient_coins, action = Append, 
     default_value = DEFAULT_RECIPIENT)]
     pub recipients: Vec<(H256, Vec<u128>)>,
+    /// The last block height at which this transaction may be included.
+    /// When not specified, the transaction never expires.
+    #[arg(long, verbatim_doc_comment)]
+    pub valid_until: Option<u32>,
 }
 
 #[derive(Debug, Args)]
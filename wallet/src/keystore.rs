@@ -6,18 +6,32 @@ use anyhow::anyhow;
 use parity_scale_codec::Encode;
 use sc_keystore::LocalKeystore;
 use sp_core::{
-    crypto::Pair as PairT,
+    crypto::{ByteArray, Pair as PairT},
+    ecdsa, ed25519,
     sr25519::{Pair, Public},
     H256,
 };
 use sp_keystore::Keystore;
 use sp_runtime::KeyTypeId;
 use std::path::Path;
+use tuxedo_core::verifier::{EcdsaOwner, EcdsaSignature, SimpleVerifier};
 
 /// A KeyTypeId to use in the keystore for Tuxedo transactions. We'll use this everywhere
 /// until it becomes clear that there is a reason to use multiple of them
 const KEY_TYPE: KeyTypeId = KeyTypeId(*b"_tux");
 
+/// The signature schemes that the wallet can manage keys for.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyScheme {
+    /// Keys for the `Sr25519Signature` verifier and its relatives
+    #[default]
+    Sr25519,
+    /// Keys for the `Ed25519Signature` verifier
+    Ed25519,
+    /// Keys for the `EcdsaSignature` verifier
+    Ecdsa,
+}
+
 /// A default seed phrase for signing inputs when none is provided
 /// Corresponds to the default pubkey.
 pub const SHAWN_PHRASE: &str =
@@ -47,13 +61,78 @@ pub fn sign_with(
     Ok(sig.encode())
 }
 
+/// Sign a given message with the ed25519 private key that corresponds to the given public key.
+///
+/// Returns an error if the keystore itself errors, or does not contain the requested key.
+pub fn sign_with_ed25519(
+    keystore: &LocalKeystore,
+    public: &ed25519::Public,
+    message: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    let sig = keystore
+        .ed25519_sign(KEY_TYPE, public, message)?
+        .ok_or(anyhow!("Key doesn't exist in keystore"))?;
+
+    Ok(sig.encode())
+}
+
+/// Sign a given message with the ecdsa private key that belongs to the given owner.
+///
+/// When the owner is an Ethereum address, the keystore's ecdsa keys are searched for one whose
+/// address matches, and the message is signed Ethereum-style over its Keccak-256 hash.
+///
+/// Returns an error if the keystore itself errors, or does not contain the requested key.
+pub fn sign_with_ecdsa(
+    keystore: &LocalKeystore,
+    owner: &EcdsaOwner,
+    message: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    match owner {
+        EcdsaOwner::PublicKey(public) => {
+            let sig = keystore
+                .ecdsa_sign(KEY_TYPE, public, message)?
+                .ok_or(anyhow!("Key doesn't exist in keystore"))?;
+
+            Ok(sig.encode())
+        }
+        EcdsaOwner::EthereumAddress(address) => {
+            let msg_hash = sp_core::hashing::keccak_256(message);
+            let verifier = EcdsaSignature::new_ethereum(*address);
+
+            for public in keystore.ecdsa_public_keys(KEY_TYPE) {
+                let Some(sig) = keystore.ecdsa_sign_prehashed(KEY_TYPE, &public, &msg_hash)? else {
+                    continue;
+                };
+                if verifier.verify(message, 0, sig.as_ref()) {
+                    return Ok(sig.encode());
+                }
+            }
+
+            Err(anyhow!("No key for address {:?} in keystore", address))
+        }
+    }
+}
+
+/// Calculate the public key that corresponds to the given seed in the given signature scheme.
+fn public_from_phrase(seed: &str, scheme: KeyScheme) -> anyhow::Result<Vec<u8>> {
+    fn public<P: PairT>(seed: &str) -> anyhow::Result<Vec<u8>> {
+        Ok(P::from_phrase(seed, None)?.0.public().to_raw_vec())
+    }
+
+    match scheme {
+        KeyScheme::Sr25519 => public::<Pair>(seed),
+        KeyScheme::Ed25519 => public::<ed25519::Pair>(seed),
+        KeyScheme::Ecdsa => public::<ecdsa::Pair>(seed),
+    }
+}
+
 /// Insert the private key associated with the given seed into the keystore for later use.
-pub fn insert_key(keystore: &LocalKeystore, seed: &str) -> anyhow::Result<()> {
+pub fn insert_key(keystore: &LocalKeystore, seed: &str, scheme: KeyScheme) -> anyhow::Result<()> {
     // We need to provide a public key to the keystore manually, so let's calculate it.
-    let public_key = Pair::from_phrase(seed, None)?.0.public();
-    println!("The generated public key is {:?}", public_key);
+    let public_key = public_from_phrase(seed, scheme)?;
+    println!("The generated public key is 0x{}", hex::encode(&public_key));
     keystore
-        .insert(KEY_TYPE, seed, public_key.as_ref())
+        .insert(KEY_TYPE, seed, &public_key)
         .map_err(|()| anyhow!("Error inserting key"))?;
     Ok(())
 }
@@ -62,12 +141,21 @@ pub fn insert_key(keystore: &LocalKeystore, seed: &str) -> anyhow::Result<()> {
 /// protected by a password.
 ///
 /// TODO there is no password support when using keys later when signing.
-pub fn generate_key(keystore: &LocalKeystore, password: Option<String>) -> anyhow::Result<()> {
-    let (pair, phrase, _) = Pair::generate_with_phrase(password.as_deref());
-    println!("Generated public key is {:?}", pair.public());
+pub fn generate_key(
+    keystore: &LocalKeystore,
+    password: Option<String>,
+    scheme: KeyScheme,
+) -> anyhow::Result<()> {
+    let phrase = match scheme {
+        KeyScheme::Sr25519 => Pair::generate_with_phrase(password.as_deref()).1,
+        KeyScheme::Ed25519 => ed25519::Pair::generate_with_phrase(password.as_deref()).1,
+        KeyScheme::Ecdsa => ecdsa::Pair::generate_with_phrase(password.as_deref()).1,
+    };
+    let public_key = public_from_phrase(&phrase, scheme)?;
+    println!("Generated public key is 0x{}", hex::encode(&public_key));
     println!("Generated Phrase is {}", phrase);
     keystore
-        .insert(KEY_TYPE, phrase.as_ref(), pair.public().as_ref())
+        .insert(KEY_TYPE, phrase.as_ref(), &public_key)
         .map_err(|()| anyhow!("Error inserting key"))?;
     Ok(())
 }
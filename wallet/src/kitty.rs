@@ -6,7 +6,9 @@ use crate::sync;
 use tuxedo_core::{
     dynamic_typing::UtxoData,
     types::{Input, Output, OutputRef},
-    verifier::Sr25519Signature,
+    verifier::{
+        BlockHeightLock, EcdsaSignature, Ed25519Signature, Sr25519Signature, TimestampLock,
+    },
 };
 
 use anyhow::anyhow;
@@ -16,7 +18,7 @@ use rand::distributions::Alphanumeric;
 use rand::Rng;
 use sc_keystore::LocalKeystore;
 use sled::Db;
-use sp_core::sr25519::Public;
+use sp_core::{ed25519, sr25519::Public};
 use sp_runtime::traits::{BlakeTwo256, Hash};
 
 use runtime::{
@@ -157,12 +159,27 @@ async fn send_tx(
 
                 // Construct the proof that it can be consumed
                 let redeemer = match utxo.verifier {
-                    OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+                    OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+                    | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+                    | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
                         let public = Public::from_h256(owner_pubkey);
                         crate::keystore::sign_with(ks, &public, &stripped_encoded_transaction)?
                     }
+                    OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                        let public = ed25519::Public::from_raw(owner_pubkey.0);
+                        crate::keystore::sign_with_ed25519(
+                            ks,
+                            &public,
+                            &stripped_encoded_transaction,
+                        )?
+                    }
+                    OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                        crate::keystore::sign_with_ecdsa(ks, &owner, &stripped_encoded_transaction)?
+                    }
                     OuterVerifier::UpForGrabs(_) => Vec::new(),
                     OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+                    OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+                    OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
                 };
                 // insert the proof
                 input.redeemer = redeemer;
@@ -282,6 +299,8 @@ pub async fn create_kitty(client: &HttpClient, args: CreateKittyArgs) -> anyhow:
         peeks: Vec::new(),
         outputs: vec![output],
         checker: FreeKittyConstraintChecker::Create.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     send_tx(&mut transaction, &client, None).await?;
@@ -322,6 +341,8 @@ pub async fn list_kitty_for_sale(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::ListKittiesForSale.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
     send_tx(&mut transaction, &client, Some(&keystore)).await?;
     print_new_output(&transaction)?;
@@ -357,6 +378,8 @@ pub async fn delist_kitty_for_sale(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::DelistKittiesFromSale.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     send_tx(&mut transaction, &client, Some(&keystore)).await?;
@@ -433,6 +456,8 @@ pub async fn breed_kitty(
         ])
             .to_vec(),
         checker: FreeKittyConstraintChecker::Breed.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     send_tx(&mut transaction, &client, Some(&keystore)).await?;
@@ -469,6 +494,8 @@ pub async fn buy_kitty(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::Buy.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Construct each output and then push to the transactions for Money
@@ -554,6 +581,8 @@ pub async fn update_kitty_name(
                 peeks: Vec::new(),
                 outputs: vec![output],
                 checker: FreeKittyConstraintChecker::UpdateKittiesName.into(),
+                evictions: Vec::new(),
+                valid_until: None,
             };
             transaction
         }
@@ -586,6 +615,8 @@ pub async fn update_kitty_name(
                 peeks: Vec::new(),
                 outputs: vec![output],
                 checker: TradableKittyConstraintChecker::UpdateKittiesName.into(),
+                evictions: Vec::new(),
+                valid_until: None,
             };
             transaction
         }
@@ -624,6 +655,8 @@ pub async fn update_kitty_price(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::UpdateKittiesPrice.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     send_tx(&mut transaction, &client, Some(&keystore)).await?;
@@ -80,8 +80,10 @@ async fn main() -> anyhow::Result<()> {
     let keystore_filter = |v: &OuterVerifier| -> bool {
         matches![v,
             OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+            | OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey })
                 if crate::keystore::has_key(&keystore, owner_pubkey)
-        ] || matches![v, OuterVerifier::UpForGrabs(UpForGrabs)] // used for timestamp
+        ] || matches![v, OuterVerifier::UpForGrabs(UpForGrabs)]
+            || matches![v, OuterVerifier::Unspendable(Unspendable)] // used for timestamp
     };
 
     if !sled::Db::was_recovered(&db) {
@@ -166,9 +168,11 @@ async fn main() -> anyhow::Result<()> {
             Ok(())
         }
         Some(Command::SpendCoins(args)) => money::spend_coins(&db, &client, &keystore, args).await,
-        Some(Command::InsertKey { seed }) => crate::keystore::insert_key(&keystore, &seed),
-        Some(Command::GenerateKey { password }) => {
-            crate::keystore::generate_key(&keystore, password)?;
+        Some(Command::InsertKey { seed, scheme }) => {
+            crate::keystore::insert_key(&keystore, &seed, scheme)
+        }
+        Some(Command::GenerateKey { password, scheme }) => {
+            crate::keystore::generate_key(&keystore, password, scheme)?;
             Ok(())
         }
         Some(Command::ShowKeys) => {
@@ -422,5 +426,27 @@ pub fn pretty_print_verifier(v: &OuterVerifier) {
                 string_sigs, multi_sig.threshold
             );
         }
+        OuterVerifier::BlockHeightLock(lock) => println!(
+            "owned by {}, locked until block {}",
+            lock.owner_pubkey, lock.unlock_height
+        ),
+        OuterVerifier::TimestampLock(lock) => println!(
+            "owned by {}, locked until timestamp {}",
+            lock.owner_pubkey, lock.unlock_time
+        ),
+        OuterVerifier::HashTimeLock(htlc) => println!(
+            "claimable by {} with the preimage of {:?}, refundable to {} from block {}",
+            htlc.recipient_pubkey, htlc.hash_lock, htlc.refunder_pubkey, htlc.refund_height
+        ),
+        OuterVerifier::Unspendable(_) => println!("that can never be spent"),
+        OuterVerifier::Ed25519Signature(ed25519_signature) => {
+            println! {"owned by ed25519 key {}", ed25519_signature.owner_pubkey}
+        }
+        OuterVerifier::EcdsaSignature(ecdsa_signature) => match &ecdsa_signature.owner {
+            EcdsaOwner::PublicKey(public) => println!("owned by ecdsa key {}", public),
+            EcdsaOwner::EthereumAddress(address) => {
+                println!("owned by ethereum address {:?}", address)
+            }
+        },
     }
 }
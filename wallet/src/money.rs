@@ -6,16 +6,19 @@ use anyhow::anyhow;
 use jsonrpsee::{core::client::ClientT, http_client::HttpClient, rpc_params};
 use parity_scale_codec::Encode;
 use runtime::{
-    money::{Coin, MoneyConstraintChecker},
+    money::{Coin, Fee, MoneyConstraintChecker},
     OuterConstraintChecker, OuterVerifier, Transaction,
 };
 use sc_keystore::LocalKeystore;
 use sled::Db;
-use sp_core::sr25519::Public;
+use sp_core::{ed25519, sr25519::Public};
 use sp_runtime::traits::{BlakeTwo256, Hash};
 use tuxedo_core::{
     types::{Input, Output, OutputRef},
-    verifier::Sr25519Signature,
+    verifier::{
+        BlockHeightLock, EcdsaSignature, Ed25519Signature, Sr25519Signature, TimestampLock,
+        Unspendable,
+    },
 };
 
 /// Create and send a transaction that mints the coins on the network
@@ -32,7 +35,9 @@ pub async fn mint_coins(client: &HttpClient, args: MintCoinArgs) -> anyhow::Resu
             }),
         )
             .into()],
-        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Mint),
+        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Mint.into()),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     let spawn_hex = hex::encode(transaction.encode());
@@ -89,7 +94,9 @@ pub async fn spend_coins(
         inputs: Vec::new(),
         peeks: Vec::new(),
         outputs: Vec::new(),
-        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Spend),
+        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Spend.into()),
+        evictions: Vec::new(),
+        valid_until: args.valid_until,
     };
 
     let recipient_list: Vec<RecipientOutput> = extract_recipient_list_from_args(args.clone());
@@ -135,14 +142,23 @@ pub async fn spend_coins(
 
     // Make sure each input decodes and is still present in the node's storage,
     // and then push to transaction.
+    let mut total_spent_amount = 0;
     for output_ref in &all_input_refs {
-        get_coin_from_storage(output_ref, client).await?;
+        let (coin, _) = get_coin_from_storage(output_ref, client).await?;
+        total_spent_amount += coin.0;
         transaction.inputs.push(Input {
             output_ref: output_ref.clone(),
             redeemer: vec![], // We will sign the total transaction so this should be empty
         });
     }
 
+    // Whatever the inputs are worth beyond the outputs would be burned,
+    // so deposit it as a fee for the block author instead.
+    if total_spent_amount > total_output_amount {
+        let fee = Fee::<0>(total_spent_amount - total_output_amount);
+        transaction.outputs.push((fee, Unspendable).into());
+    }
+
     // Keep a copy of the stripped encoded transaction for signing purposes
     let stripped_encoded_transaction = transaction.clone().encode();
     // Iterate back through the inputs, signing, and putting the signatures in place.
@@ -152,18 +168,38 @@ pub async fn spend_coins(
 
         // Construct the proof that it can be consumed
         let redeemer = match utxo.verifier {
-            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+            | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+            | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
                 let public = Public::from_h256(owner_pubkey);
                 crate::keystore::sign_with(keystore, &public, &stripped_encoded_transaction)?
             }
+            OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                let public = ed25519::Public::from_raw(owner_pubkey.0);
+                crate::keystore::sign_with_ed25519(
+                    keystore,
+                    &public,
+                    &stripped_encoded_transaction,
+                )?
+            }
+            OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                crate::keystore::sign_with_ecdsa(keystore, &owner, &stripped_encoded_transaction)?
+            }
             OuterVerifier::UpForGrabs(_) => Vec::new(),
             OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+            OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+            OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
         };
 
         // insert the proof
         input.redeemer = redeemer;
     }
 
+    // Make sure the transaction is valid before sending it
+    if let Err(e) = crate::rpc::dry_run_transaction(&transaction, client).await? {
+        return Err(anyhow!("Spend transaction would be rejected: {:?}", e));
+    }
+
     // Send the transaction
     let genesis_spend_hex = hex::encode(transaction.encode());
     let params = rpc_params![genesis_spend_hex];
@@ -181,6 +217,10 @@ pub async fn spend_coins(
             tx_hash,
             index: i as u32,
         };
+        if let Ok(fee) = output.payload.extract::<Fee<0>>() {
+            println!("Deposited {} as a fee.", fee.0);
+            continue;
+        }
         let amount = output.payload.extract::<Coin<0>>()?.0;
 
         print!(
@@ -207,7 +247,9 @@ pub async fn spend_coins1(
         inputs: Vec::new(),
         peeks: Vec::new(),
         outputs: Vec::new(),
-        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Spend),
+        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Spend.into()),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Construct each output and then push to the transactions
@@ -268,12 +310,23 @@ pub async fn spend_coins1(
 
         // Construct the proof that it can be consumed
         let redeemer = match utxo.verifier {
-            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+            | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+            | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
                 let public = Public::from_h256(owner_pubkey);
                 crate::keystore::sign_with(keystore, &public, &stripped_encoded_transaction)?
             }
+            OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                let public = ed25519::Public::from_raw(owner_pubkey.0);
+                crate::keystore::sign_with_ed25519(keystore, &public, &stripped_encoded_transaction)?
+            }
+            OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                crate::keystore::sign_with_ecdsa(keystore, &owner, &stripped_encoded_transaction)?
+            }
             OuterVerifier::UpForGrabs(_) => Vec::new(),
             OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+            OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+            OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
         };
 
         // insert the proof
@@ -336,6 +389,9 @@ pub(crate) fn apply_transaction(
             // Add it to the global unspent_outputs table
             crate::sync::add_unspent_output(db, &output_ref, &owner_pubkey, &amount)
         }
+        OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+            crate::sync::add_unspent_output(db, &output_ref, &owner_pubkey, &amount)
+        }
         _ => Err(anyhow!("{:?}", ())),
     }
 }
@@ -5,10 +5,14 @@ use crate::strip_0x_prefix;
 use anyhow::anyhow;
 use jsonrpsee::{core::client::ClientT, http_client::HttpClient, rpc_params};
 use parity_scale_codec::{Decode, Encode};
-use runtime::{opaque::Block as OpaqueBlock, Block};
+use runtime::{
+    opaque::Block as OpaqueBlock, Block, OuterConstraintCheckerError, OuterVerifier, Transaction,
+};
 use sp_core::H256;
 use tuxedo_core::{
-    types::{Output, OutputRef},
+    dry_run::DryRunReport,
+    types::{Output, OutputRef, UtxoError},
+    utxo_set::utxo_storage_key,
     Verifier,
 };
 
@@ -48,7 +52,7 @@ pub async fn fetch_storage<V: Verifier>(
     output_ref: &OutputRef,
     client: &HttpClient,
 ) -> anyhow::Result<Output<V>> {
-    let ref_hex = hex::encode(output_ref.encode());
+    let ref_hex = hex::encode(utxo_storage_key(output_ref));
     let params = rpc_params![ref_hex];
     let rpc_response: Result<Option<String>, _> = client.request("state_getStorage", params).await;
 
@@ -59,3 +63,21 @@ pub async fn fetch_storage<V: Verifier>(
 
     Ok(utxo)
 }
+
+/// Dry-run a transaction against the node's best block without broadcasting it.
+/// This reports exactly why the transaction would be rejected.
+pub async fn dry_run_transaction(
+    transaction: &Transaction,
+    client: &HttpClient,
+) -> anyhow::Result<Result<DryRunReport<OuterVerifier>, UtxoError<OuterConstraintCheckerError>>> {
+    let params = rpc_params![
+        "TuxedoDryRunApi_dry_run_transaction",
+        hex::encode(transaction.encode())
+    ];
+    let response_hex: String = client.request("state_call", params).await?;
+    let response_hex = strip_0x_prefix(&response_hex);
+    let response_bytes = hex::decode(response_hex)?;
+    let result = Decode::decode(&mut &response_bytes[..])?;
+
+    Ok(result)
+}
@@ -318,6 +318,13 @@ async fn apply_transaction<F: Fn(&OuterVerifier) -> bool>(
         mark_as_used_tradable_kitties(db, &output_ref)?;
     }
 
+    // Evicted outputs are removed from state just like spent inputs
+    for output_ref in tx.evictions {
+        spend_output(db, &output_ref)?;
+        mark_as_used_kitties(db, &output_ref)?;
+        mark_as_used_tradable_kitties(db, &output_ref)?;
+    }
+
     Ok(())
 }
 
@@ -373,13 +380,18 @@ fn unspend_output(db: &Db, output_ref: &OutputRef) -> anyhow::Result<()> {
 }
 
 /// Run a transaction backwards against a database. Mark all of the Inputs
-/// as unspent, and drop all of the outputs.
+/// and evictions as unspent, and drop all of the outputs.
 fn unapply_transaction(db: &Db, tx: &Transaction) -> anyhow::Result<()> {
     // Loop through the inputs moving each from spent to unspent
     for Input { output_ref, .. } in &tx.inputs {
         unspend_output(db, output_ref)?;
     }
 
+    // Evicted outputs are restored the same way
+    for output_ref in &tx.evictions {
+        unspend_output(db, output_ref)?;
+    }
+
     // Loop through the outputs pruning them from unspent and dropping all record
     let tx_hash = BlakeTwo256::hash_of(&tx.encode());
 
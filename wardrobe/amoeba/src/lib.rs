@@ -14,8 +14,9 @@ use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_runtime::transaction_validity::TransactionPriority;
+use sp_std::vec::Vec;
 use tuxedo_core::{
-    dynamic_typing::{DynamicallyTypedData, UtxoData},
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
     ensure, SimpleConstraintChecker,
 };
 
@@ -23,7 +24,8 @@ use tuxedo_core::{
 mod tests;
 
 /// An amoeba tracked by our simple Amoeba APP
-#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, UtxoData)]
+#[utxo_data(type_id = *b"amoe")]
 pub struct AmoebaDetails {
     /// How many generations after the original Eve Amoeba this one is.
     /// When going through mitosis, this number must increase by 1 each time.
@@ -33,12 +35,8 @@ pub struct AmoebaDetails {
     pub four_bytes: [u8; 4],
 }
 
-impl UtxoData for AmoebaDetails {
-    const TYPE_ID: [u8; 4] = *b"amoe";
-}
-
 /// Reasons that the amoeba constraint checkers may fail
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Encode, Decode, Eq, PartialEq)]
 pub enum ConstraintCheckerError {
     /// An input data has the wrong type.
     BadlyTypedInput,
@@ -89,6 +87,7 @@ impl SimpleConstraintChecker for AmoebaMitosis {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, ConstraintCheckerError> {
         // Make sure there is exactly one mother.
         ensure!(
@@ -130,6 +129,10 @@ impl SimpleConstraintChecker for AmoebaMitosis {
         // Such a wrapper should live with the money piece, and thus returning 0 here is fine.
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<AmoebaDetails>()
+    }
 }
 
 /// A constraint checker for simple death of an amoeba.
@@ -147,6 +150,7 @@ impl SimpleConstraintChecker for AmoebaDeath {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
         // Make sure there is a single victim
         ensure!(!input_data.is_empty(), ConstraintCheckerError::NoVictim);
@@ -169,6 +173,10 @@ impl SimpleConstraintChecker for AmoebaDeath {
 
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<AmoebaDetails>()
+    }
 }
 
 /// A constraint checker for simple creation of an amoeba.
@@ -186,6 +194,7 @@ impl SimpleConstraintChecker for AmoebaCreation {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
         // Make sure there is a single created amoeba
         ensure!(
@@ -211,4 +220,8 @@ impl SimpleConstraintChecker for AmoebaCreation {
 
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<AmoebaDetails>()
+    }
 }
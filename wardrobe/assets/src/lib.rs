@@ -0,0 +1,391 @@
+//! A registry of fungible assets that can be created on chain.
+//!
+//! The money piece's `Coin<ID>` identifies each kind of coin by a single byte that is fixed when the
+//! runtime is compiled, so adding a new kind of coin requires a runtime upgrade. This piece lets anyone
+//! register a new asset by creating an `AssetMetadata` UTXO that describes it. The asset is identified
+//! by the hash of its metadata, and its tokens are `Asset` UTXOs that carry that identifier.
+//! The issuer signs the registration by consuming one of their own coins or assets and re-creating it
+//! unchanged. Only coins and assets may be used, so that registering cannot move other pieces' UTXOs.
+//!
+//! The metadata UTXO is protected by the issuer's signature and acts as the right to mint the asset,
+//! much like the money piece's `MintAuthority`. A mint transaction consumes the metadata and usually
+//! re-creates it so that it can be used again. If the issuer chooses not to re-create it, the asset's
+//! supply is fixed forever.
+//!
+//! Each registered asset is also noted in the `AssetRegistryStorage` so that the same asset cannot be
+//! registered twice, and so that clients can look up its metadata through the `AssetRegistryApi`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use money::Coin;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash},
+    transaction_validity::TransactionPriority,
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+use tuxedo_core::{
+    dynamic_typing::{utxo_type_info, UtxoData, UtxoTypeInfo},
+    ensure,
+    traits::Cash,
+    types::Output,
+    verifier::Sr25519Signature,
+    ConstraintChecker, Verifier,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The longest name an asset may have, in bytes.
+pub const MAX_NAME_LENGTH: usize = 32;
+
+/// The longest symbol an asset may have, in bytes.
+pub const MAX_SYMBOL_LENGTH: usize = 8;
+
+/// The most decimals an asset may have. A `u128` amount has 38 full decimal digits.
+pub const MAX_DECIMALS: u8 = 38;
+
+/// The description of a registered asset.
+///
+/// The asset's identifier is the hash of its metadata, so the metadata can never change once the
+/// asset is registered. It must be protected by the issuer's sr25519 signature.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+    UtxoData,
+)]
+pub struct AssetMetadata {
+    /// The human-readable name of the asset.
+    pub name: Vec<u8>,
+    /// The short ticker symbol of the asset.
+    pub symbol: Vec<u8>,
+    /// The number of decimal places that wallets should use to display amounts of the asset.
+    pub decimals: u8,
+    /// The sr25519 public key of the account that registered the asset and may mint it.
+    pub issuer: H256,
+}
+
+impl AssetMetadata {
+    /// The identifier of the asset that this metadata describes.
+    pub fn asset_id(&self) -> H256 {
+        BlakeTwo256::hash_of(self)
+    }
+
+    /// Whether the metadata is within the limits that every asset must respect.
+    fn is_well_formed(&self) -> bool {
+        (1..=MAX_NAME_LENGTH).contains(&self.name.len())
+            && (1..=MAX_SYMBOL_LENGTH).contains(&self.symbol.len())
+            && self.decimals <= MAX_DECIMALS
+    }
+}
+
+/// Some amount of a registered asset.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+    UtxoData,
+)]
+pub struct Asset {
+    /// The identifier of the asset, which is the hash of its metadata.
+    pub asset_id: H256,
+    /// The amount of the asset, in its smallest unit.
+    pub amount: u128,
+}
+
+impl Asset {
+    pub fn new(asset_id: H256, amount: u128) -> Self {
+        Self { asset_id, amount }
+    }
+}
+
+impl Cash for Asset {
+    fn value(&self) -> u128 {
+        self.amount
+    }
+
+    fn asset_id(&self) -> H256 {
+        self.asset_id
+    }
+}
+
+/// The prefix of the storage keys under which the metadata of each registered asset is noted.
+/// The asset's identifier follows the prefix.
+const REGISTRY_KEY_PREFIX: &[u8] = b"asset_registry";
+
+/// A public interface for reading the metadata of registered assets.
+///
+/// The metadata is noted here as a side effect of checking each registration.
+pub enum AssetRegistryStorage {}
+
+impl AssetRegistryStorage {
+    fn key(asset_id: &H256) -> Vec<u8> {
+        [REGISTRY_KEY_PREFIX, asset_id.as_bytes()].concat()
+    }
+
+    /// Read the metadata of the asset with the given identifier, if it has been registered.
+    pub fn get(asset_id: &H256) -> Option<AssetMetadata> {
+        sp_io::storage::get(&Self::key(asset_id)).and_then(|d| AssetMetadata::decode(&mut &*d).ok())
+    }
+
+    fn set(metadata: &AssetMetadata) {
+        sp_io::storage::set(&Self::key(&metadata.asset_id()), &metadata.encode());
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    /// Read access to the asset registry, for clients such as wallets and explorers.
+    pub trait AssetRegistryApi {
+        /// The metadata of the asset with the given identifier, or `None` if it is not registered.
+        fn asset_metadata(asset_id: H256) -> Option<AssetMetadata>;
+    }
+}
+
+/// The constraint checker for the assets piece. Allows registering, minting, and spending assets.
+///
+/// The `ID` is the kind of coin from the money piece that issuers may use to sign registrations.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+)]
+pub enum AssetConstraintChecker<const ID: u8> {
+    /// Register a new asset by creating its metadata, protected by the issuer's signature.
+    /// To prove that the issuer signed the transaction, it consumes a single `Coin<ID>` or `Asset`
+    /// protected by the issuer's signature and re-creates it unchanged after the metadata.
+    Register,
+    /// Consume an asset's metadata and create some amount of the asset out of the void.
+    /// The metadata may be passed on by re-creating it among the outputs.
+    Mint,
+    /// A typical spend transaction where some assets are consumed and others are created.
+    /// The transaction may move several assets at once, and the amount of each is conserved separately.
+    Spend,
+}
+
+/// Errors that can occur when checking asset transactions.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+)]
+pub enum AssetError {
+    /// Dynamic typing issue.
+    /// This error doesn't discriminate between badly typed inputs and outputs.
+    BadlyTyped,
+    /// The transaction registers an asset without consuming exactly one coin or asset protected by
+    /// the issuer's sr25519 signature.
+    MissingIssuerInput,
+    /// The transaction does not create exactly one asset's metadata followed by the issuer's UTXO.
+    WrongRegistrationOutputs,
+    /// The UTXO consumed to prove the issuer's signature is not re-created unchanged.
+    IssuerInputNotPassedOn,
+    /// The metadata's name, symbol, or decimals are outside the allowed limits.
+    MalformedMetadata,
+    /// The metadata is not protected by the issuer's sr25519 signature.
+    WrongIssuer,
+    /// An asset with identical metadata has already been noted in the `AssetRegistryStorage`.
+    AlreadyRegistered,
+    /// The transaction attempts to mint without consuming exactly one asset's metadata.
+    MissingMetadata,
+    /// The transaction re-creates metadata other than what it consumed, or re-creates it more than once.
+    WrongMetadata,
+    /// The transaction attempts to mint an asset other than the one whose metadata it consumed.
+    WrongAsset,
+    /// The transaction attempts to mint nothing. This is not allowed.
+    MintingNothing,
+    /// The transaction attempts to spend without consuming any inputs.
+    SpendingNothing,
+    /// The amount of some asset created by the transaction exceeds the amount consumed.
+    OutputsExceedInputs,
+    /// The amount consumed or created by this transaction overflows the amount type.
+    ValueOverflow,
+    /// The transaction attempted to create an asset with zero amount. This is not allowed
+    /// because it wastes state space.
+    ZeroValueAsset,
+}
+
+/// Add up the amounts of each asset.
+fn asset_totals<V>(data: &[Output<V>]) -> Result<BTreeMap<H256, u128>, AssetError> {
+    let mut totals = BTreeMap::new();
+    for utxo in data {
+        let asset = utxo
+            .payload
+            .extract::<Asset>()
+            .map_err(|_| AssetError::BadlyTyped)?;
+        ensure!(asset.amount > 0, AssetError::ZeroValueAsset);
+        let total: &mut u128 = totals.entry(asset.asset_id).or_default();
+        *total = total
+            .checked_add(asset.amount)
+            .ok_or(AssetError::ValueOverflow)?;
+    }
+    Ok(totals)
+}
+
+impl<const ID: u8, V: Verifier + From<Sr25519Signature> + PartialEq> ConstraintChecker<V>
+    for AssetConstraintChecker<ID>
+{
+    type Error = AssetError;
+    type InherentHooks = ();
+
+    fn check(
+        &self,
+        inputs: &[Output<V>],
+        _peeks: &[Output<V>],
+        outputs: &[Output<V>],
+        _evictions: &[Output<V>],
+    ) -> Result<TransactionPriority, Self::Error> {
+        match self {
+            Self::Register => {
+                let [signed] = inputs else {
+                    return Err(AssetError::MissingIssuerInput);
+                };
+                let [output, passed_on] = outputs else {
+                    return Err(AssetError::WrongRegistrationOutputs);
+                };
+                let metadata = output
+                    .payload
+                    .extract::<AssetMetadata>()
+                    .map_err(|_| AssetError::BadlyTyped)?;
+
+                ensure!(metadata.is_well_formed(), AssetError::MalformedMetadata);
+                let issuer = V::from(Sr25519Signature::new(metadata.issuer));
+                ensure!(output.verifier == issuer, AssetError::WrongIssuer);
+
+                // The consumed coin or asset proves that the issuer signed the transaction.
+                // It is passed on unchanged so that registering does not destroy anything.
+                let is_cash = signed.payload.extract::<Coin<ID>>().is_ok()
+                    || signed.payload.extract::<Asset>().is_ok();
+                ensure!(
+                    is_cash && signed.verifier == issuer,
+                    AssetError::MissingIssuerInput
+                );
+                ensure!(passed_on == signed, AssetError::IssuerInputNotPassedOn);
+
+                // The registry is only written below, so this relies on every registration being
+                // checked exactly once before it is applied. The executive does that, and block
+                // builders discard the storage changes of transactions that fail afterwards.
+                ensure!(
+                    AssetRegistryStorage::get(&metadata.asset_id()).is_none(),
+                    AssetError::AlreadyRegistered
+                );
+
+                // SIDE EFFECT: Note the new asset so it cannot be registered again
+                AssetRegistryStorage::set(&metadata);
+
+                log::info!("Assets:Register registered asset {:?}", metadata.asset_id());
+
+                // No priority for registering
+                Ok(0)
+            }
+            Self::Mint => {
+                // The consumed metadata proves that the issuer signed the transaction
+                let [input] = inputs else {
+                    return Err(AssetError::MissingMetadata);
+                };
+                let metadata = input
+                    .payload
+                    .extract::<AssetMetadata>()
+                    .map_err(|_| AssetError::MissingMetadata)?;
+                let asset_id = metadata.asset_id();
+
+                // Separate the passed-on metadata from the newly minted assets
+                let mut passed_on = 0;
+                let mut minted = Vec::new();
+                for output in outputs {
+                    if let Ok(recreated) = output.payload.extract::<AssetMetadata>() {
+                        ensure!(
+                            recreated == metadata && output.verifier == input.verifier,
+                            AssetError::WrongMetadata
+                        );
+                        passed_on += 1;
+                    } else {
+                        minted.push(output.clone());
+                    }
+                }
+                ensure!(passed_on <= 1, AssetError::WrongMetadata);
+
+                // Make sure only this asset is minted, and at least some of it
+                let totals = asset_totals(&minted)?;
+                ensure!(!totals.is_empty(), AssetError::MintingNothing);
+                ensure!(
+                    totals.keys().all(|id| *id == asset_id),
+                    AssetError::WrongAsset
+                );
+
+                // No priority for minting
+                Ok(0)
+            }
+            Self::Spend => {
+                ensure!(!inputs.is_empty(), AssetError::SpendingNothing);
+
+                let consumed = asset_totals(inputs)?;
+                let created = asset_totals(outputs)?;
+
+                // Conserve the amount of each asset separately
+                for (asset_id, amount) in created {
+                    ensure!(
+                        amount <= consumed.get(&asset_id).copied().unwrap_or_default(),
+                        AssetError::OutputsExceedInputs
+                    );
+                }
+
+                // Amounts of different assets cannot be compared, so the burned value cannot be
+                // turned into a priority like it is for coins.
+                Ok(0)
+            }
+        }
+    }
+
+    fn is_inherent(&self) -> bool {
+        false
+    }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        [
+            utxo_type_info::<AssetMetadata>(),
+            utxo_type_info::<Asset>(),
+            utxo_type_info::<Coin<ID>>(),
+        ]
+        .concat()
+    }
+}
@@ -0,0 +1,516 @@
+//! Unit tests for the Assets piece
+
+use super::*;
+use tuxedo_core::dynamic_typing::testing::Bogus;
+use AssetError::*;
+
+const ISSUER: H256 = H256::repeat_byte(1);
+const HOLDER: H256 = H256::repeat_byte(2);
+
+fn tuxedo_dollars() -> AssetMetadata {
+    AssetMetadata {
+        name: b"Tuxedo Dollar".to_vec(),
+        symbol: b"TUXD".to_vec(),
+        decimals: 12,
+        issuer: ISSUER,
+    }
+}
+
+fn tuxedo_euros() -> AssetMetadata {
+    AssetMetadata {
+        name: b"Tuxedo Euro".to_vec(),
+        symbol: b"TUXE".to_vec(),
+        ..tuxedo_dollars()
+    }
+}
+
+fn issued(metadata: AssetMetadata) -> Output<Sr25519Signature> {
+    let issuer = metadata.issuer;
+    (metadata, Sr25519Signature::new(issuer)).into()
+}
+
+fn asset(metadata: &AssetMetadata, amount: u128) -> Output<Sr25519Signature> {
+    (
+        Asset::new(metadata.asset_id(), amount),
+        Sr25519Signature::new(HOLDER),
+    )
+        .into()
+}
+
+#[test]
+fn asset_id_depends_on_metadata() {
+    assert_eq!(tuxedo_dollars().asset_id(), tuxedo_dollars().asset_id());
+    assert_ne!(tuxedo_dollars().asset_id(), tuxedo_euros().asset_id());
+    assert_eq!(
+        Asset::new(tuxedo_dollars().asset_id(), 5).asset_id(),
+        tuxedo_dollars().asset_id()
+    );
+}
+
+/// A coin that the issuer consumes and re-creates to sign a registration.
+fn issuer_utxo() -> Output<Sr25519Signature> {
+    (Coin::<0>(5), Sr25519Signature::new(ISSUER)).into()
+}
+
+/// Register the given metadata, signed with the issuer's UTXO.
+fn register(metadata: AssetMetadata) -> Result<TransactionPriority, AssetError> {
+    let inputs = vec![issuer_utxo()];
+    let outputs = vec![issued(metadata), issuer_utxo()];
+    AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[])
+}
+
+#[test]
+fn register_works() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        assert_eq!(register(tuxedo_dollars()), Ok(0));
+        assert_eq!(
+            AssetRegistryStorage::get(&tuxedo_dollars().asset_id()),
+            Some(tuxedo_dollars())
+        );
+        assert_eq!(AssetRegistryStorage::get(&tuxedo_euros().asset_id()), None);
+    });
+}
+
+#[test]
+fn register_twice_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        assert_eq!(register(tuxedo_dollars()), Ok(0));
+        assert_eq!(register(tuxedo_dollars()), Err(AlreadyRegistered));
+    });
+}
+
+#[test]
+fn register_without_issuer_input_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let outputs = vec![issued(tuxedo_dollars()), issuer_utxo()];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&[], &[], &outputs, &[]),
+            Err(MissingIssuerInput)
+        );
+        assert_eq!(
+            AssetRegistryStorage::get(&tuxedo_dollars().asset_id()),
+            None
+        );
+    });
+}
+
+#[test]
+fn register_with_someone_elses_input_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let inputs = vec![asset(&tuxedo_euros(), 5)];
+        let outputs = vec![issued(tuxedo_dollars()), asset(&tuxedo_euros(), 5)];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(MissingIssuerInput)
+        );
+    });
+}
+
+#[test]
+fn register_signed_with_asset_works() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let signing_asset: Output<Sr25519Signature> = (
+            Asset::new(tuxedo_euros().asset_id(), 5),
+            Sr25519Signature::new(ISSUER),
+        )
+            .into();
+        let inputs = vec![signing_asset.clone()];
+        let outputs = vec![issued(tuxedo_dollars()), signing_asset];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Ok(0)
+        );
+    });
+}
+
+#[test]
+fn register_signed_with_other_payload_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let signing_utxo: Output<Sr25519Signature> = (Bogus, Sr25519Signature::new(ISSUER)).into();
+        let inputs = vec![signing_utxo.clone()];
+        let outputs = vec![issued(tuxedo_dollars()), signing_utxo];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(MissingIssuerInput)
+        );
+    });
+}
+
+#[test]
+fn register_signed_with_other_coin_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let signing_coin: Output<Sr25519Signature> =
+            (Coin::<1>(5), Sr25519Signature::new(ISSUER)).into();
+        let inputs = vec![signing_coin.clone()];
+        let outputs = vec![issued(tuxedo_dollars()), signing_coin];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(MissingIssuerInput)
+        );
+    });
+}
+
+#[test]
+fn register_with_several_inputs_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let inputs = vec![issuer_utxo(), issuer_utxo()];
+        let outputs = vec![issued(tuxedo_dollars()), issuer_utxo()];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(MissingIssuerInput)
+        );
+    });
+}
+
+#[test]
+fn register_without_passing_on_input_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let inputs = vec![issuer_utxo()];
+        let outputs = vec![issued(tuxedo_dollars())];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(WrongRegistrationOutputs)
+        );
+    });
+}
+
+#[test]
+fn register_changing_input_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let inputs = vec![issuer_utxo()];
+        let outputs = vec![
+            issued(tuxedo_dollars()),
+            (Bogus, Sr25519Signature::new(HOLDER)).into(),
+        ];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(IssuerInputNotPassedOn)
+        );
+    });
+}
+
+#[test]
+fn register_several_assets_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let inputs = vec![issuer_utxo()];
+        let outputs = vec![
+            issued(tuxedo_dollars()),
+            issued(tuxedo_euros()),
+            issuer_utxo(),
+        ];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(WrongRegistrationOutputs)
+        );
+    });
+}
+
+#[test]
+fn register_badly_typed_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let inputs = vec![issuer_utxo()];
+        let outputs = vec![issuer_utxo(), issuer_utxo()];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(BadlyTyped)
+        );
+    });
+}
+
+#[test]
+fn register_malformed_metadata_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let malformed = [
+            AssetMetadata {
+                name: Vec::new(),
+                ..tuxedo_dollars()
+            },
+            AssetMetadata {
+                name: vec![b'a'; MAX_NAME_LENGTH + 1],
+                ..tuxedo_dollars()
+            },
+            AssetMetadata {
+                symbol: Vec::new(),
+                ..tuxedo_dollars()
+            },
+            AssetMetadata {
+                symbol: vec![b'A'; MAX_SYMBOL_LENGTH + 1],
+                ..tuxedo_dollars()
+            },
+            AssetMetadata {
+                decimals: MAX_DECIMALS + 1,
+                ..tuxedo_dollars()
+            },
+        ];
+
+        for metadata in malformed {
+            assert_eq!(register(metadata), Err(MalformedMetadata));
+        }
+    });
+}
+
+#[test]
+fn register_for_someone_else_fails() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let inputs = vec![issuer_utxo()];
+        let outputs = vec![
+            (tuxedo_dollars(), Sr25519Signature::new(HOLDER)).into(),
+            issuer_utxo(),
+        ];
+
+        assert_eq!(
+            AssetConstraintChecker::<0>::Register.check(&inputs, &[], &outputs, &[]),
+            Err(WrongIssuer)
+        );
+    });
+}
+
+#[test]
+fn mint_and_pass_on_metadata_works() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![
+        issued(tuxedo_dollars()),
+        asset(&tuxedo_dollars(), 100),
+        asset(&tuxedo_dollars(), 50),
+    ];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Ok(0)
+    );
+}
+
+#[test]
+fn mint_and_finalize_supply_works() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![asset(&tuxedo_dollars(), 100)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Ok(0)
+    );
+}
+
+#[test]
+fn mint_without_metadata_fails() {
+    let outputs = vec![asset(&tuxedo_dollars(), 100)];
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&[], &[], &outputs, &[]),
+        Err(MissingMetadata)
+    );
+
+    let inputs = vec![asset(&tuxedo_dollars(), 100)];
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(MissingMetadata)
+    );
+}
+
+#[test]
+fn mint_with_several_metadata_fails() {
+    let inputs = vec![issued(tuxedo_dollars()), issued(tuxedo_euros())];
+    let outputs = vec![asset(&tuxedo_dollars(), 100)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(MissingMetadata)
+    );
+}
+
+#[test]
+fn mint_other_asset_fails() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![asset(&tuxedo_dollars(), 100), asset(&tuxedo_euros(), 100)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(WrongAsset)
+    );
+}
+
+#[test]
+fn mint_nothing_fails() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![issued(tuxedo_dollars())];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(MintingNothing)
+    );
+}
+
+#[test]
+fn mint_zero_value_fails() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![asset(&tuxedo_dollars(), 0)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(ZeroValueAsset)
+    );
+}
+
+#[test]
+fn mint_changing_metadata_fails() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![issued(tuxedo_euros()), asset(&tuxedo_dollars(), 100)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(WrongMetadata)
+    );
+}
+
+#[test]
+fn mint_handing_over_metadata_fails() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![
+        (tuxedo_dollars(), Sr25519Signature::new(HOLDER)).into(),
+        asset(&tuxedo_dollars(), 100),
+    ];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(WrongMetadata)
+    );
+}
+
+#[test]
+fn mint_duplicating_metadata_fails() {
+    let inputs = vec![issued(tuxedo_dollars())];
+    let outputs = vec![
+        issued(tuxedo_dollars()),
+        issued(tuxedo_dollars()),
+        asset(&tuxedo_dollars(), 100),
+    ];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Mint.check(&inputs, &[], &outputs, &[]),
+        Err(WrongMetadata)
+    );
+}
+
+#[test]
+fn spend_several_assets_works() {
+    let inputs = vec![
+        asset(&tuxedo_dollars(), 10),
+        asset(&tuxedo_euros(), 20),
+        asset(&tuxedo_dollars(), 5),
+    ];
+    let outputs = vec![
+        asset(&tuxedo_euros(), 20),
+        asset(&tuxedo_dollars(), 12),
+        asset(&tuxedo_dollars(), 3),
+    ];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&inputs, &[], &outputs, &[]),
+        Ok(0)
+    );
+}
+
+#[test]
+fn spend_burning_assets_works() {
+    let inputs = vec![asset(&tuxedo_dollars(), 10), asset(&tuxedo_euros(), 20)];
+    let outputs = vec![asset(&tuxedo_dollars(), 4)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&inputs, &[], &outputs, &[]),
+        Ok(0)
+    );
+}
+
+#[test]
+fn spend_more_than_consumed_fails() {
+    let inputs = vec![asset(&tuxedo_dollars(), 10)];
+    let outputs = vec![asset(&tuxedo_dollars(), 11)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&inputs, &[], &outputs, &[]),
+        Err(OutputsExceedInputs)
+    );
+}
+
+#[test]
+fn spend_converting_assets_fails() {
+    let inputs = vec![asset(&tuxedo_dollars(), 10)];
+    let outputs = vec![asset(&tuxedo_euros(), 10)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&inputs, &[], &outputs, &[]),
+        Err(OutputsExceedInputs)
+    );
+}
+
+#[test]
+fn spend_nothing_fails() {
+    let outputs = vec![asset(&tuxedo_dollars(), 10)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&[], &[], &outputs, &[]),
+        Err(SpendingNothing)
+    );
+}
+
+#[test]
+fn spend_zero_value_output_fails() {
+    let inputs = vec![asset(&tuxedo_dollars(), 10)];
+    let outputs = vec![asset(&tuxedo_dollars(), 10), asset(&tuxedo_dollars(), 0)];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&inputs, &[], &outputs, &[]),
+        Err(ZeroValueAsset)
+    );
+}
+
+#[test]
+fn spend_badly_typed_fails() {
+    let inputs = vec![asset(&tuxedo_dollars(), 10)];
+    let outputs = vec![issued(tuxedo_dollars())];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&inputs, &[], &outputs, &[]),
+        Err(BadlyTyped)
+    );
+}
+
+#[test]
+fn spend_overflow_fails() {
+    let inputs = vec![
+        asset(&tuxedo_dollars(), u128::MAX),
+        asset(&tuxedo_dollars(), 1),
+    ];
+
+    assert_eq!(
+        AssetConstraintChecker::<0>::Spend.check(&inputs, &[], &[], &[]),
+        Err(ValueOverflow)
+    );
+}
+
+#[test]
+fn evictions_not_allowed() {
+    assert!(!ConstraintChecker::<Sr25519Signature>::allows_evictions(
+        &AssetConstraintChecker::<0>::Spend
+    ));
+}
+
+#[test]
+fn asset_types_use_derived_type_ids() {
+    use tuxedo_core::dynamic_typing::type_id_from_name;
+
+    assert_eq!(
+        AssetMetadata::TYPE_ID,
+        type_id_from_name("assets::AssetMetadata")
+    );
+    assert_eq!(Asset::TYPE_ID, type_id_from_name("assets::Asset"));
+}
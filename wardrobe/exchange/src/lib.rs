@@ -0,0 +1,384 @@
+//! An exchange between two kinds of coins from the money piece.
+//!
+//! The money piece only moves a single kind of coin in each transaction. This piece lets `Coin<GIVE>`
+//! and `Coin<WANT>` change hands in the same transaction, and checks that the value of each kind of
+//! coin is conserved separately. There are two ways to trade.
+//!
+//! Exchange transactions never burn coins. Only the money piece knows whether burned value is deposited
+//! as fees or deducted from a tracked supply, so any burning should be done through it.
+//!
+//! ## Atomic Swaps
+//!
+//! Two parties who have agreed on a trade off-chain build a single `Swap` transaction that consumes
+//! the coins each of them gives and creates the coins each of them receives. Both parties sign it, so
+//! neither can end up with the other's coins without giving up their own.
+//!
+//! ## Swap Offers
+//!
+//! A maker who will not be around to sign a swap can leave a `SwapOffer` in state instead. The offer
+//! escrows some `Coin<GIVE>` and asks for some `Coin<WANT>` in return, which fixes the exchange rate.
+//! Any taker may fill the offer, in full or in part, by evicting it, paying the maker at the offer's
+//! rate, and re-creating the offer with whatever is left after a partial fill. Payments are coins
+//! protected by the same verifier as the offer, so they belong to whoever was able to make it.
+//! The maker may cancel the offer at any time by consuming it.
+//!
+//! Each instance of this piece only makes offers in one direction. A runtime that wants an order book in
+//! both directions should include the piece once as `<A, B>` and once as `<B, A>`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use money::Coin;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_runtime::transaction_validity::TransactionPriority;
+use sp_std::prelude::*;
+use tuxedo_core::{
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
+    ensure,
+    types::Output,
+    ConstraintChecker, Verifier,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// An offer to exchange `Coin<GIVE>` for `Coin<WANT>`.
+///
+/// The offered coins are escrowed in the offer itself. The exchange rate is `want / give`. Takers
+/// always pay at least this rate, rounded up in the maker's favour, so the maker receives at least
+/// `want` in total once the whole offer has been filled.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+    UtxoData,
+)]
+#[utxo_data(type_id = [b's', b'w', GIVE, WANT])]
+pub struct SwapOffer<const GIVE: u8, const WANT: u8> {
+    /// The value of `Coin<GIVE>` that is still on offer.
+    pub give: u128,
+    /// The value of `Coin<WANT>` that the maker asks for in return for everything still on offer.
+    pub want: u128,
+}
+
+impl<const GIVE: u8, const WANT: u8> SwapOffer<GIVE, WANT> {
+    pub fn new(give: u128, want: u128) -> Self {
+        Self { give, want }
+    }
+
+    /// The value of `Coin<WANT>` that a taker must pay to take `take` of the offered coins.
+    pub fn price(&self, take: u128) -> Result<u128, ExchangeError> {
+        let product = take
+            .checked_mul(self.want)
+            .ok_or(ExchangeError::ValueOverflow)?;
+        // Round up so that partial fills never leave the maker worse off
+        Ok(product / self.give + u128::from(product % self.give != 0))
+    }
+}
+
+/// The constraint checker for the exchange piece.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+)]
+pub enum ExchangeConstraintChecker<const GIVE: u8, const WANT: u8> {
+    /// An atomic swap that consumes and creates both kinds of coins.
+    /// The value of each kind of coin is conserved separately.
+    Swap,
+    /// Escrow some `Coin<GIVE>` in a single new `SwapOffer`, possibly with some change.
+    MakeOffer,
+    /// Evict a single `SwapOffer` and take some or all of its coins in return for a payment to the maker.
+    /// After a partial fill, the rest of the offer must be re-created with the same verifier.
+    FillOffer,
+    /// Consume some `SwapOffer`s and return the escrowed coins to the maker.
+    CancelOffer,
+}
+
+/// Errors that can occur when checking exchange transactions.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+)]
+pub enum ExchangeError {
+    /// Dynamic typing issue.
+    /// This error doesn't discriminate between badly typed inputs and outputs.
+    BadlyTyped,
+    /// The transaction does not consume any inputs.
+    ConsumingNothing,
+    /// The value of some kind of coin created by the transaction exceeds the value consumed.
+    OutputsExceedInputs,
+    /// The value of some kind of coin consumed by the transaction exceeds the value created.
+    /// The exchange does not burn coins.
+    InputsExceedOutputs,
+    /// The value consumed or created by this transaction overflows the value type.
+    ValueOverflow,
+    /// The transaction attempted to create a coin with zero value. This is not allowed
+    /// because it wastes state space.
+    ZeroValueCoin,
+    /// The transaction is expected to make, fill, or re-create exactly one offer, but it has none.
+    MissingOffer,
+    /// The transaction makes, fills, or re-creates more than one offer.
+    TooManyOffers,
+    /// The new offer gives or wants nothing.
+    EmptyOffer,
+    /// The transaction fills an offer without taking any of its coins.
+    TakingNothing,
+    /// The transaction takes more coins than the offer has.
+    TakingTooMuch,
+    /// The maker is paid less than the offer's rate.
+    Underpaid,
+    /// The offer re-created after a partial fill does not hold exactly what is left of the original,
+    /// or is not protected by the same verifier.
+    WrongRemainder,
+}
+
+/// Make sure that exactly the consumed value is created, so that nothing is minted or burned.
+fn conserve(consumed: u128, created: u128) -> Result<(), ExchangeError> {
+    ensure!(created <= consumed, ExchangeError::OutputsExceedInputs);
+    ensure!(created == consumed, ExchangeError::InputsExceedOutputs);
+    Ok(())
+}
+
+/// Add a coin's value to a running total, making sure the coin is not empty.
+fn add_value(total: &mut u128, value: u128) -> Result<(), ExchangeError> {
+    ensure!(value > 0, ExchangeError::ZeroValueCoin);
+    *total = total
+        .checked_add(value)
+        .ok_or(ExchangeError::ValueOverflow)?;
+    Ok(())
+}
+
+/// Sum the value of each kind of coin. Anything else is badly typed.
+fn coin_totals<const GIVE: u8, const WANT: u8>(
+    data: &[DynamicallyTypedData],
+) -> Result<(u128, u128), ExchangeError> {
+    let mut give_total = 0;
+    let mut want_total = 0;
+    for utxo in data {
+        if let Ok(coin) = utxo.extract::<Coin<GIVE>>() {
+            add_value(&mut give_total, coin.0)?;
+        } else {
+            let coin = utxo
+                .extract::<Coin<WANT>>()
+                .map_err(|_| ExchangeError::BadlyTyped)?;
+            add_value(&mut want_total, coin.0)?;
+        }
+    }
+    Ok((give_total, want_total))
+}
+
+impl<const GIVE: u8, const WANT: u8, V: Verifier + PartialEq> ConstraintChecker<V>
+    for ExchangeConstraintChecker<GIVE, WANT>
+{
+    type Error = ExchangeError;
+    type InherentHooks = ();
+
+    fn check(
+        &self,
+        inputs: &[Output<V>],
+        _peeks: &[Output<V>],
+        outputs: &[Output<V>],
+        evictions: &[Output<V>],
+    ) -> Result<TransactionPriority, Self::Error> {
+        let input_data: Vec<DynamicallyTypedData> =
+            inputs.iter().map(|o| o.payload.clone()).collect();
+        let output_data: Vec<DynamicallyTypedData> =
+            outputs.iter().map(|o| o.payload.clone()).collect();
+
+        match self {
+            Self::Swap => {
+                ensure!(!inputs.is_empty(), ExchangeError::ConsumingNothing);
+
+                let (give_in, want_in) = coin_totals::<GIVE, WANT>(&input_data)?;
+                let (give_out, want_out) = coin_totals::<GIVE, WANT>(&output_data)?;
+
+                // Conserve the value of each kind of coin separately
+                conserve(give_in, give_out)?;
+                conserve(want_in, want_out)?;
+
+                Ok(0)
+            }
+            Self::MakeOffer => {
+                ensure!(!inputs.is_empty(), ExchangeError::ConsumingNothing);
+
+                let mut consumed = 0;
+                for input in &input_data {
+                    let coin = input
+                        .extract::<Coin<GIVE>>()
+                        .map_err(|_| ExchangeError::BadlyTyped)?;
+                    add_value(&mut consumed, coin.0)?;
+                }
+
+                // Separate the new offer from the change
+                let mut offers = Vec::new();
+                let mut created = 0;
+                for output in &output_data {
+                    if let Ok(offer) = output.extract::<SwapOffer<GIVE, WANT>>() {
+                        ensure!(offer.give > 0 && offer.want > 0, ExchangeError::EmptyOffer);
+                        add_value(&mut created, offer.give)?;
+                        offers.push(offer);
+                    } else {
+                        let coin = output
+                            .extract::<Coin<GIVE>>()
+                            .map_err(|_| ExchangeError::BadlyTyped)?;
+                        add_value(&mut created, coin.0)?;
+                    }
+                }
+                ensure!(!offers.is_empty(), ExchangeError::MissingOffer);
+                ensure!(offers.len() == 1, ExchangeError::TooManyOffers);
+
+                // The offer and the change are backed by exactly the coins that were consumed
+                conserve(consumed, created)?;
+
+                Ok(0)
+            }
+            Self::FillOffer => {
+                let (offer, maker) = match evictions {
+                    [evicted] => (
+                        evicted
+                            .payload
+                            .extract::<SwapOffer<GIVE, WANT>>()
+                            .map_err(|_| ExchangeError::BadlyTyped)?,
+                        &evicted.verifier,
+                    ),
+                    [] => return Err(ExchangeError::MissingOffer),
+                    _ => return Err(ExchangeError::TooManyOffers),
+                };
+
+                // The taker pays with the wanted coins
+                let mut paid_in = 0;
+                for input in &input_data {
+                    let coin = input
+                        .extract::<Coin<WANT>>()
+                        .map_err(|_| ExchangeError::BadlyTyped)?;
+                    add_value(&mut paid_in, coin.0)?;
+                }
+
+                // Sort the outputs into the taken coins, the payment to the maker, the taker's change,
+                // and the remainder of the offer. The payment is recognized by the maker's verifier.
+                let mut taken = 0;
+                let mut payment = 0;
+                let mut change = 0;
+                let mut remainders = Vec::new();
+                for output in outputs {
+                    if let Ok(remainder) = output.payload.extract::<SwapOffer<GIVE, WANT>>() {
+                        ensure!(output.verifier == *maker, ExchangeError::WrongRemainder);
+                        remainders.push(remainder);
+                    } else if let Ok(coin) = output.payload.extract::<Coin<GIVE>>() {
+                        add_value(&mut taken, coin.0)?;
+                    } else {
+                        let coin = output
+                            .payload
+                            .extract::<Coin<WANT>>()
+                            .map_err(|_| ExchangeError::BadlyTyped)?;
+                        if output.verifier == *maker {
+                            add_value(&mut payment, coin.0)?;
+                        } else {
+                            add_value(&mut change, coin.0)?;
+                        }
+                    }
+                }
+
+                ensure!(taken > 0, ExchangeError::TakingNothing);
+                ensure!(taken <= offer.give, ExchangeError::TakingTooMuch);
+                conserve(
+                    paid_in,
+                    payment
+                        .checked_add(change)
+                        .ok_or(ExchangeError::ValueOverflow)?,
+                )?;
+                ensure!(payment >= offer.price(taken)?, ExchangeError::Underpaid);
+
+                // After a partial fill, the rest of the offer stays available at the same rate
+                if taken < offer.give {
+                    let expected = SwapOffer::<GIVE, WANT>::new(
+                        offer.give - taken,
+                        offer.want.saturating_sub(payment),
+                    );
+                    ensure!(expected.want > 0, ExchangeError::WrongRemainder);
+                    match remainders.as_slice() {
+                        [remainder] => {
+                            ensure!(*remainder == expected, ExchangeError::WrongRemainder)
+                        }
+                        [] => return Err(ExchangeError::MissingOffer),
+                        _ => return Err(ExchangeError::TooManyOffers),
+                    }
+                } else {
+                    ensure!(remainders.is_empty(), ExchangeError::TooManyOffers);
+                }
+
+                Ok(0)
+            }
+            Self::CancelOffer => {
+                ensure!(!inputs.is_empty(), ExchangeError::ConsumingNothing);
+
+                let mut escrowed = 0;
+                for input in &input_data {
+                    let offer = input
+                        .extract::<SwapOffer<GIVE, WANT>>()
+                        .map_err(|_| ExchangeError::BadlyTyped)?;
+                    add_value(&mut escrowed, offer.give)?;
+                }
+
+                let mut returned = 0;
+                for output in &output_data {
+                    let coin = output
+                        .extract::<Coin<GIVE>>()
+                        .map_err(|_| ExchangeError::BadlyTyped)?;
+                    add_value(&mut returned, coin.0)?;
+                }
+                conserve(escrowed, returned)?;
+
+                Ok(0)
+            }
+        }
+    }
+
+    fn is_inherent(&self) -> bool {
+        false
+    }
+
+    // Only filling an offer evicts anything.
+    fn allows_evictions(&self) -> bool {
+        *self == Self::FillOffer
+    }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        [
+            utxo_type_info::<Coin<GIVE>>(),
+            utxo_type_info::<Coin<WANT>>(),
+            utxo_type_info::<SwapOffer<GIVE, WANT>>(),
+        ]
+        .concat()
+    }
+}
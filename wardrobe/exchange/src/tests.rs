@@ -0,0 +1,473 @@
+//! Unit tests for the Exchange piece
+
+use super::*;
+use tuxedo_core::{dynamic_typing::testing::Bogus, verifier::Sr25519Signature};
+use ExchangeError::*;
+
+type Checker = ExchangeConstraintChecker<0, 1>;
+type Offer = SwapOffer<0, 1>;
+
+fn maker() -> Sr25519Signature {
+    Sr25519Signature::new([1; 32])
+}
+
+fn taker() -> Sr25519Signature {
+    Sr25519Signature::new([2; 32])
+}
+
+fn output(
+    payload: impl Into<DynamicallyTypedData>,
+    v: Sr25519Signature,
+) -> Output<Sr25519Signature> {
+    (payload, v).into()
+}
+
+#[test]
+fn swap_works() {
+    let inputs = vec![
+        output(Coin::<0>(10), maker()),
+        output(Coin::<1>(30), taker()),
+    ];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(30), maker()),
+    ];
+
+    assert_eq!(Checker::Swap.check(&inputs, &[], &outputs, &[]), Ok(0));
+}
+
+#[test]
+fn swap_burning_coins_fails() {
+    let inputs = vec![
+        output(Coin::<0>(10), maker()),
+        output(Coin::<1>(30), taker()),
+    ];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(27), maker()),
+    ];
+
+    assert_eq!(
+        Checker::Swap.check(&inputs, &[], &outputs, &[]),
+        Err(InputsExceedOutputs)
+    );
+}
+
+#[test]
+fn swap_cannot_turn_one_coin_into_another() {
+    let inputs = vec![
+        output(Coin::<0>(10), maker()),
+        output(Coin::<1>(30), taker()),
+    ];
+    let outputs = vec![
+        output(Coin::<0>(20), taker()),
+        output(Coin::<1>(20), maker()),
+    ];
+
+    assert_eq!(
+        Checker::Swap.check(&inputs, &[], &outputs, &[]),
+        Err(OutputsExceedInputs)
+    );
+}
+
+#[test]
+fn swap_other_coin_fails() {
+    let inputs = vec![
+        output(Coin::<0>(10), maker()),
+        output(Coin::<2>(30), taker()),
+    ];
+    let outputs = vec![output(Coin::<0>(10), taker())];
+
+    assert_eq!(
+        Checker::Swap.check(&inputs, &[], &outputs, &[]),
+        Err(BadlyTyped)
+    );
+}
+
+#[test]
+fn swap_zero_value_coin_fails() {
+    let inputs = vec![output(Coin::<0>(10), maker())];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(0), maker()),
+    ];
+
+    assert_eq!(
+        Checker::Swap.check(&inputs, &[], &outputs, &[]),
+        Err(ZeroValueCoin)
+    );
+}
+
+#[test]
+fn swap_nothing_fails() {
+    assert_eq!(
+        ConstraintChecker::<Sr25519Signature>::check(&Checker::Swap, &[], &[], &[], &[]),
+        Err(ConsumingNothing)
+    );
+}
+
+#[test]
+fn only_filling_offers_allows_evictions() {
+    let allows_evictions = <Checker as ConstraintChecker<Sr25519Signature>>::allows_evictions;
+
+    assert!(!allows_evictions(&Checker::Swap));
+    assert!(!allows_evictions(&Checker::MakeOffer));
+    assert!(allows_evictions(&Checker::FillOffer));
+}
+
+#[test]
+fn make_offer_works() {
+    let inputs = vec![output(Coin::<0>(15), maker())];
+    let outputs = vec![
+        output(Offer::new(10, 30), maker()),
+        output(Coin::<0>(5), maker()),
+    ];
+
+    assert_eq!(Checker::MakeOffer.check(&inputs, &[], &outputs, &[]), Ok(0));
+}
+
+#[test]
+fn make_offer_without_backing_fails() {
+    let inputs = vec![output(Coin::<0>(5), maker())];
+    let outputs = vec![output(Offer::new(10, 30), maker())];
+
+    assert_eq!(
+        Checker::MakeOffer.check(&inputs, &[], &outputs, &[]),
+        Err(OutputsExceedInputs)
+    );
+}
+
+#[test]
+fn make_offer_burning_coins_fails() {
+    let inputs = vec![output(Coin::<0>(15), maker())];
+    let outputs = vec![output(Offer::new(10, 30), maker())];
+
+    assert_eq!(
+        Checker::MakeOffer.check(&inputs, &[], &outputs, &[]),
+        Err(InputsExceedOutputs)
+    );
+}
+
+#[test]
+fn make_offer_paid_with_wanted_coin_fails() {
+    let inputs = vec![output(Coin::<1>(10), maker())];
+    let outputs = vec![output(Offer::new(10, 30), maker())];
+
+    assert_eq!(
+        Checker::MakeOffer.check(&inputs, &[], &outputs, &[]),
+        Err(BadlyTyped)
+    );
+}
+
+#[test]
+fn make_empty_offer_fails() {
+    let inputs = vec![output(Coin::<0>(10), maker())];
+    let outputs = vec![output(Offer::new(10, 0), maker())];
+
+    assert_eq!(
+        Checker::MakeOffer.check(&inputs, &[], &outputs, &[]),
+        Err(EmptyOffer)
+    );
+}
+
+#[test]
+fn make_offer_without_offer_fails() {
+    let inputs = vec![output(Coin::<0>(10), maker())];
+    let outputs = vec![output(Coin::<0>(10), maker())];
+
+    assert_eq!(
+        Checker::MakeOffer.check(&inputs, &[], &outputs, &[]),
+        Err(MissingOffer)
+    );
+}
+
+#[test]
+fn make_two_offers_fails() {
+    let inputs = vec![output(Coin::<0>(20), maker())];
+    let outputs = vec![
+        output(Offer::new(10, 30), maker()),
+        output(Offer::new(10, 20), maker()),
+    ];
+
+    assert_eq!(
+        Checker::MakeOffer.check(&inputs, &[], &outputs, &[]),
+        Err(TooManyOffers)
+    );
+}
+
+#[test]
+fn fill_whole_offer_works() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(35), taker())];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(30), maker()),
+        output(Coin::<1>(5), taker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Ok(0)
+    );
+}
+
+#[test]
+fn fill_part_of_offer_works() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(12), taker())];
+    let outputs = vec![
+        output(Coin::<0>(4), taker()),
+        output(Coin::<1>(12), maker()),
+        output(Offer::new(6, 18), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Ok(0)
+    );
+}
+
+#[test]
+fn partial_fill_rounds_price_up() {
+    // Three coins for seven is not a whole price per coin, so one coin costs three.
+    let evictions = vec![output(Offer::new(3, 7), maker())];
+    let inputs = vec![output(Coin::<1>(3), taker())];
+    let underpaid = vec![
+        output(Coin::<0>(1), taker()),
+        output(Coin::<1>(2), maker()),
+        output(Coin::<1>(1), taker()),
+        output(Offer::new(2, 5), maker()),
+    ];
+    let paid = vec![
+        output(Coin::<0>(1), taker()),
+        output(Coin::<1>(3), maker()),
+        output(Offer::new(2, 4), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &underpaid, &evictions),
+        Err(Underpaid)
+    );
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &paid, &evictions),
+        Ok(0)
+    );
+}
+
+#[test]
+fn fill_underpaid_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(30), taker())];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(29), maker()),
+        output(Coin::<1>(1), taker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(Underpaid)
+    );
+}
+
+#[test]
+fn fill_paying_someone_else_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(30), taker())];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(30), taker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(Underpaid)
+    );
+}
+
+#[test]
+fn fill_payment_exceeding_inputs_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(20), taker())];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(30), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(OutputsExceedInputs)
+    );
+}
+
+#[test]
+fn fill_burning_coins_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(35), taker())];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(30), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(InputsExceedOutputs)
+    );
+}
+
+#[test]
+fn fill_taking_too_much_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(33), taker())];
+    let outputs = vec![
+        output(Coin::<0>(11), taker()),
+        output(Coin::<1>(33), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(TakingTooMuch)
+    );
+}
+
+#[test]
+fn fill_taking_nothing_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(3), taker())];
+    let outputs = vec![output(Coin::<1>(3), maker())];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(TakingNothing)
+    );
+}
+
+#[test]
+fn partial_fill_without_remainder_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(12), taker())];
+    let outputs = vec![
+        output(Coin::<0>(4), taker()),
+        output(Coin::<1>(12), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(MissingOffer)
+    );
+}
+
+#[test]
+fn partial_fill_with_wrong_remainder_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(12), taker())];
+    let outputs = vec![
+        output(Coin::<0>(4), taker()),
+        output(Coin::<1>(12), maker()),
+        output(Offer::new(6, 30), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(WrongRemainder)
+    );
+}
+
+#[test]
+fn partial_fill_with_stolen_remainder_fails() {
+    let evictions = vec![output(Offer::new(10, 30), maker())];
+    let inputs = vec![output(Coin::<1>(12), taker())];
+    let outputs = vec![
+        output(Coin::<0>(4), taker()),
+        output(Coin::<1>(12), maker()),
+        output(Offer::new(6, 18), taker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(WrongRemainder)
+    );
+}
+
+#[test]
+fn fill_without_offer_fails() {
+    let inputs = vec![output(Coin::<1>(30), taker())];
+    let outputs = vec![output(Coin::<1>(30), maker())];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &[]),
+        Err(MissingOffer)
+    );
+}
+
+#[test]
+fn fill_bogus_offer_fails() {
+    let evictions = vec![output(Bogus, maker())];
+    let inputs = vec![output(Coin::<1>(30), taker())];
+    let outputs = vec![
+        output(Coin::<0>(10), taker()),
+        output(Coin::<1>(30), maker()),
+    ];
+
+    assert_eq!(
+        Checker::FillOffer.check(&inputs, &[], &outputs, &evictions),
+        Err(BadlyTyped)
+    );
+}
+
+#[test]
+fn cancel_offer_works() {
+    let inputs = vec![output(Offer::new(10, 30), maker())];
+    let outputs = vec![output(Coin::<0>(10), maker())];
+
+    assert_eq!(
+        Checker::CancelOffer.check(&inputs, &[], &outputs, &[]),
+        Ok(0)
+    );
+}
+
+#[test]
+fn cancel_offer_returning_too_much_fails() {
+    let inputs = vec![output(Offer::new(10, 30), maker())];
+    let outputs = vec![output(Coin::<0>(11), maker())];
+
+    assert_eq!(
+        Checker::CancelOffer.check(&inputs, &[], &outputs, &[]),
+        Err(OutputsExceedInputs)
+    );
+}
+
+#[test]
+fn cancel_offer_burning_coins_fails() {
+    let inputs = vec![output(Offer::new(10, 30), maker())];
+    let outputs = vec![output(Coin::<0>(9), maker())];
+
+    assert_eq!(
+        Checker::CancelOffer.check(&inputs, &[], &outputs, &[]),
+        Err(InputsExceedOutputs)
+    );
+}
+
+#[test]
+fn cancel_offer_returning_wanted_coin_fails() {
+    let inputs = vec![output(Offer::new(10, 30), maker())];
+    let outputs = vec![output(Coin::<1>(10), maker())];
+
+    assert_eq!(
+        Checker::CancelOffer.check(&inputs, &[], &outputs, &[]),
+        Err(BadlyTyped)
+    );
+}
+
+#[test]
+fn exchange_types_do_not_collide() {
+    let mut types = <Checker as ConstraintChecker<Sr25519Signature>>::utxo_types();
+    types.extend(<ExchangeConstraintChecker<1, 0> as ConstraintChecker<
+        Sr25519Signature,
+    >>::utxo_types());
+
+    assert_eq!(
+        tuxedo_core::dynamic_typing::find_type_id_collision(&types),
+        None
+    );
+}
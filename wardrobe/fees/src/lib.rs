@@ -0,0 +1,371 @@
+//! Pay the fees deposited by money spend transactions to block authors via a closing inherent.
+//!
+//! When the money piece's mint policy collects fees, each spend transaction may deposit some or all
+//! of the value it burns in `Fee` outputs. In each block, the block author includes a single `PayFees`
+//! transaction that evicts those deposits along with the previous `FeePot`, pays the whole pot, as a
+//! single coin, to the author's sr25519 key, and creates an empty pot in its place.
+//!
+//! ## Timing
+//!
+//! Inherents are created when the block is opened, before its user transactions are known. So even
+//! though `PayFees` is a closing inherent, it cannot sweep the deposits of its own block. Instead it
+//! sweeps the deposits that were created in the parent block, which it finds through the `FeesConfig`.
+//! This means that the author of each block is paid the fees deposited in the block before it.
+//! Deposits that are no longer in the UTXO set by then are skipped, so that the payout stays valid.
+//!
+//! If the author cannot be determined, nothing is paid and the fees stay in the pot for the next author.
+//! The author is not part of the inherent data. It is looked up by the runtime, typically from the
+//! slot in the block's Aura pre-runtime digest, through the `FeesConfig`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::marker::PhantomData;
+
+use money::{Coin, Fee};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::H256;
+use sp_inherents::{CheckInherentsResult, InherentData, InherentIdentifier};
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash as HashT},
+    transaction_validity::TransactionPriority,
+};
+use sp_std::{vec, vec::Vec};
+use tuxedo_core::{
+    dynamic_typing::{utxo_type_info, UtxoData, UtxoTypeInfo},
+    ensure,
+    inherents::{InherentPlacement, TuxedoInherent, TuxedoInherentAdapter},
+    support_macros::{CloneNoBound, DebugNoBound, DefaultNoBound},
+    types::{Output, OutputRef, Transaction},
+    verifier::{Sr25519Signature, Unspendable},
+    ConstraintChecker, Verifier,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// A piece-wide target for logging
+const LOG_TARGET: &str = "fees-piece";
+
+/// An inherent identifier for the fee payout.
+/// The payout does not need any inherent data of its own, so this is only used to identify it in errors.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"pay_fees";
+
+/// Options to configure the fees piece in your runtime.
+pub trait FeesConfig {
+    /// The sr25519 public key of the current block's author, if it can be determined.
+    /// Probably this will look up the slot's authority from the Aura pre-runtime digest.
+    fn block_author() -> Option<H256>;
+
+    /// Where to find the fees deposited for coins with the given `ID` in the parent block, and their values.
+    /// Probably this will decode the parent block from the authoring inherent data and pass its
+    /// extrinsics to `fee_deposits`.
+    fn parent_block_fees<const ID: u8>(
+        authoring_inherent_data: &InherentData,
+    ) -> Vec<(OutputRef, u128)>;
+
+    /// Whether the given fee deposit is still in the UTXO set, so that it can be swept.
+    /// Probably this will look it up with the executive's `peek_utxo`.
+    fn deposit_exists(output_ref: &OutputRef) -> bool;
+}
+
+/// Find the fees deposited for coins with the given `ID` by the given transactions, and their values.
+pub fn fee_deposits<const ID: u8, V: Encode, C: Encode>(
+    transactions: &[Transaction<V, C>],
+) -> Vec<(OutputRef, u128)> {
+    transactions
+        .iter()
+        .flat_map(|transaction| {
+            let tx_hash = BlakeTwo256::hash_of(&transaction.encode());
+            transaction
+                .outputs
+                .iter()
+                .enumerate()
+                .filter_map(move |(index, output)| {
+                    let fee = output.payload.extract::<Fee<ID>>().ok()?;
+                    let output_ref = OutputRef {
+                        tx_hash,
+                        index: index as u32,
+                    };
+                    Some((output_ref, fee.0))
+                })
+        })
+        .collect()
+}
+
+/// The fees for coins with a given `ID` that have been swept from the deposits, but not yet paid out
+/// because the author could not be determined.
+///
+/// There is a single pot for each kind of coin. It is protected by the `Unspendable` verifier, and each
+/// payout evicts it and creates an updated one as its first output, so that the next payout can find it.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    Default,
+    TypeInfo,
+    UtxoData,
+)]
+#[utxo_data(type_id = [b'p', b'o', b't', ID])]
+pub struct FeePot<const ID: u8>(pub u128);
+
+/// Reasons that paying out the fees may go wrong.
+#[derive(Debug, Encode, Decode, Eq, PartialEq)]
+pub enum FeesError {
+    /// UTXO data has an unexpected type
+    BadlyTyped,
+    /// Inputs were specified while paying out the fees, but none are allowed.
+    InputsWhilePayingFees,
+    /// The transaction does not evict exactly one previous fee pot.
+    MissingPreviousPot,
+    /// The transaction does not create an updated fee pot as its first output.
+    MissingPot,
+    /// There are fees to pay out to a known author, but no payout coin was created.
+    MissingPayout,
+    /// More outputs were created than the updated pot and the payout coin, or a payout was created
+    /// although there are no fees to pay out or nobody to pay them to.
+    ExtraOutputs,
+    /// The value of the payout coin does not match the fees in the pot.
+    WrongPayoutValue,
+    /// The payout coin is not protected by the author's sr25519 signature.
+    WrongRecipient,
+    /// The updated fee pot does not hold the fees that were not paid out.
+    WrongPotValue,
+    /// The value of the fees overflows the value type.
+    ValueOverflow,
+}
+
+/// A constraint checker for paying the fees deposited in the previous block to the current block's author.
+///
+/// This is expected to be performed through a closing inherent, and to happen exactly once per block.
+///
+/// The transaction consumes nothing. It evicts the previous fee pot and any number of fee deposits, whose
+/// values together make up the fees to pay. If there are any fees and the current author is known, it
+/// creates an empty pot followed by a single coin worth exactly those fees that is protected by the author's
+/// sr25519 signature. Otherwise it creates a pot holding all of the fees, and pays nothing.
+#[derive(
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    DebugNoBound,
+    DefaultNoBound,
+    PartialEq,
+    Eq,
+    CloneNoBound,
+    TypeInfo,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct PayFees<T, const ID: u8>(PhantomData<T>);
+
+impl<T: FeesConfig, const ID: u8> PayFees<T, ID> {
+    /// The author to pay the given fees to, if there are any fees and the author is known.
+    fn recipient(fees: u128) -> Option<H256> {
+        T::block_author().filter(|_| fees > 0)
+    }
+}
+
+impl<
+        T: FeesConfig + 'static,
+        const ID: u8,
+        V: Verifier + From<Sr25519Signature> + From<Unspendable> + PartialEq,
+    > ConstraintChecker<V> for PayFees<T, ID>
+{
+    type Error = FeesError;
+    type InherentHooks = TuxedoInherentAdapter<Self>;
+
+    fn check(
+        &self,
+        input_data: &[Output<V>],
+        _peek_data: &[Output<V>],
+        output_data: &[Output<V>],
+        evicted_data: &[Output<V>],
+    ) -> Result<TransactionPriority, Self::Error> {
+        log::debug!(
+            target: LOG_TARGET,
+            "💸 Checking constraints for PayFees."
+        );
+
+        // Paying out the fees does not consume anything.
+        ensure!(input_data.is_empty(), Self::Error::InputsWhilePayingFees);
+
+        // Separate the previous pot from the deposits and add up the fees
+        let mut previous_pots = 0;
+        let mut fees: u128 = 0;
+        for evicted in evicted_data {
+            let value = match evicted.payload.extract::<FeePot<ID>>() {
+                Ok(pot) => {
+                    previous_pots += 1;
+                    pot.0
+                }
+                Err(_) => {
+                    evicted
+                        .payload
+                        .extract::<Fee<ID>>()
+                        .map_err(|_| Self::Error::BadlyTyped)?
+                        .0
+                }
+            };
+            fees = fees.checked_add(value).ok_or(Self::Error::ValueOverflow)?;
+        }
+        ensure!(previous_pots == 1, Self::Error::MissingPreviousPot);
+
+        // The updated pot always comes first
+        let (pot, payout) = match output_data {
+            [] => return Err(Self::Error::MissingPot),
+            [pot] => (pot, None),
+            [pot, payout] => (pot, Some(payout)),
+            _ => return Err(Self::Error::ExtraOutputs),
+        };
+        let pot = pot
+            .payload
+            .extract::<FeePot<ID>>()
+            .map_err(|_| Self::Error::MissingPot)?;
+
+        match Self::recipient(fees) {
+            Some(recipient) => {
+                // Make sure the whole pot is paid to the author
+                let payout = payout.ok_or(Self::Error::MissingPayout)?;
+                let payout_value = payout
+                    .payload
+                    .extract::<Coin<ID>>()
+                    .map_err(|_| Self::Error::BadlyTyped)?
+                    .0;
+                ensure!(payout_value == fees, Self::Error::WrongPayoutValue);
+                ensure!(
+                    payout.verifier == V::from(Sr25519Signature::new(recipient)),
+                    Self::Error::WrongRecipient
+                );
+                ensure!(pot.0 == 0, Self::Error::WrongPotValue);
+            }
+            None => {
+                // Keep the fees in the pot until an author is known
+                ensure!(payout.is_none(), Self::Error::ExtraOutputs);
+                ensure!(pot.0 == fees, Self::Error::WrongPotValue);
+            }
+        }
+
+        Ok(0)
+    }
+
+    fn is_inherent(&self) -> bool {
+        true
+    }
+
+    // The previous pot and the deposits are unspendable, so they are evicted.
+    fn allows_evictions(&self) -> bool {
+        true
+    }
+
+    fn inherent_placement(&self) -> InherentPlacement {
+        InherentPlacement::Closing
+    }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        [
+            utxo_type_info::<FeePot<ID>>(),
+            utxo_type_info::<Fee<ID>>(),
+            utxo_type_info::<Coin<ID>>(),
+        ]
+        .concat()
+    }
+}
+
+impl<
+        T: FeesConfig + 'static,
+        const ID: u8,
+        V: Verifier + From<Sr25519Signature> + From<Unspendable> + PartialEq,
+    > TuxedoInherent<V, Self> for PayFees<T, ID>
+{
+    type Error = sp_inherents::MakeFatalError<()>;
+    const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+    fn create_inherent(
+        authoring_inherent_data: &InherentData,
+        previous_inherent: (Transaction<V, Self>, H256),
+    ) -> Transaction<V, Self> {
+        // The previous payout created the pot as its first output, so we know right where to find it.
+        let previous_pot = previous_inherent
+            .0
+            .outputs
+            .first()
+            .and_then(|output| output.payload.extract::<FeePot<ID>>().ok())
+            .expect("Previous payout should create the fee pot as its first output.");
+        let mut evictions = vec![OutputRef {
+            tx_hash: previous_inherent.1,
+            index: 0,
+        }];
+
+        // Sweep the fees deposited in the parent block along with the pot
+        let mut fees = previous_pot.0;
+        for (output_ref, value) in T::parent_block_fees::<ID>(authoring_inherent_data) {
+            // Evicting a deposit that is already gone would make the whole payout fail
+            if !T::deposit_exists(&output_ref) {
+                log::debug!(
+                    target: LOG_TARGET,
+                    "💸 Skipping fee deposit {output_ref:?} that is no longer in the UTXO set."
+                );
+                continue;
+            }
+            evictions.push(output_ref);
+            fees = fees.saturating_add(value);
+        }
+
+        let outputs = match Self::recipient(fees) {
+            Some(recipient) => {
+                log::debug!(
+                    target: LOG_TARGET,
+                    "💸 Paying {fees} in fees to {recipient:?}."
+                );
+                vec![
+                    (FeePot::<ID>(0), Unspendable).into(),
+                    (Coin::<ID>::new(fees), Sr25519Signature::new(recipient)).into(),
+                ]
+            }
+            None => vec![(FeePot::<ID>(fees), Unspendable).into()],
+        };
+
+        Transaction {
+            inputs: Vec::new(),
+            peeks: Vec::new(),
+            outputs,
+            checker: Self::default(),
+            evictions,
+            valid_until: None,
+        }
+    }
+
+    fn check_inherent(
+        _importing_inherent_data: &InherentData,
+        _inherent: Transaction<V, Self>,
+        _result: &mut CheckInherentsResult,
+    ) {
+        log::debug!(
+            target: LOG_TARGET,
+            "In check_inherents for fee payout. No actual off-chain checks are required."
+        );
+    }
+
+    #[cfg(feature = "std")]
+    fn genesis_transactions() -> Vec<Transaction<V, Self>> {
+        // Nothing has been deposited and nobody has authored a block yet, so there is nothing to pay.
+        // This payout only creates the empty pot for the first block's payout to find.
+        vec![Transaction {
+            inputs: Vec::new(),
+            peeks: Vec::new(),
+            outputs: vec![(FeePot::<ID>::default(), Unspendable).into()],
+            checker: Self::default(),
+            evictions: Vec::new(),
+            valid_until: None,
+        }]
+    }
+}
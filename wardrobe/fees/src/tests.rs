@@ -0,0 +1,448 @@
+//! Unit tests for the Fees piece.
+//! These tests deposit fees with money spends and pay them out to block authors.
+
+use super::*;
+use money::{MintPolicy, MoneyConstraintChecker, MoneyWithPolicy};
+use tuxedo_core::{
+    dynamic_typing::testing::Bogus,
+    utxo_set::{TransparentUtxoSet, UtxoSet},
+    verifier::SimpleVerifier,
+    Executive,
+};
+use FeesError::*;
+
+/// A mint policy that lets spends deposit the value they burn as fees.
+struct FeeCollecting;
+
+impl MintPolicy<0> for FeeCollecting {
+    const FREE_MINTING: bool = true;
+    const COLLECT_FEES: bool = true;
+}
+
+/// A verifier that can be either of the verifiers that payouts create.
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
+enum TestVerifier {
+    Sr25519Signature(Sr25519Signature),
+    Unspendable(Unspendable),
+}
+
+impl From<Sr25519Signature> for TestVerifier {
+    fn from(v: Sr25519Signature) -> Self {
+        Self::Sr25519Signature(v)
+    }
+}
+
+impl From<Unspendable> for TestVerifier {
+    fn from(v: Unspendable) -> Self {
+        Self::Unspendable(v)
+    }
+}
+
+impl SimpleVerifier for TestVerifier {
+    fn verify(&self, _simplified_tx: &[u8], _block_height: u32, _redeemer: &[u8]) -> bool {
+        false
+    }
+}
+
+const ALICE: H256 = H256::repeat_byte(1);
+
+fn output_ref(index: u32) -> OutputRef {
+    OutputRef {
+        tx_hash: H256::repeat_byte(7),
+        index,
+    }
+}
+
+/// The mock config says Alice authors the block, and two fees were deposited in the parent block.
+pub struct AliceAuthors;
+
+impl FeesConfig for AliceAuthors {
+    fn block_author() -> Option<H256> {
+        Some(ALICE)
+    }
+
+    fn parent_block_fees<const ID: u8>(_: &InherentData) -> Vec<(OutputRef, u128)> {
+        vec![(output_ref(1), 3), (output_ref(2), 1)]
+    }
+
+    fn deposit_exists(_: &OutputRef) -> bool {
+        true
+    }
+}
+
+/// The mock config says the author is unknown, and two fees were deposited in the parent block.
+pub struct UnknownAuthor;
+
+impl FeesConfig for UnknownAuthor {
+    fn block_author() -> Option<H256> {
+        None
+    }
+
+    fn parent_block_fees<const ID: u8>(_: &InherentData) -> Vec<(OutputRef, u128)> {
+        AliceAuthors::parent_block_fees::<ID>(&InherentData::new())
+    }
+
+    fn deposit_exists(_: &OutputRef) -> bool {
+        true
+    }
+}
+
+/// The mock config says Alice authors the block, and looks the deposits up in the actual UTXO set.
+pub struct StoredDeposits;
+
+impl FeesConfig for StoredDeposits {
+    fn block_author() -> Option<H256> {
+        Some(ALICE)
+    }
+
+    fn parent_block_fees<const ID: u8>(_: &InherentData) -> Vec<(OutputRef, u128)> {
+        AliceAuthors::parent_block_fees::<ID>(&InherentData::new())
+    }
+
+    fn deposit_exists(output_ref: &OutputRef) -> bool {
+        TransparentUtxoSet::<TestVerifier>::peek_utxo(output_ref).is_some()
+    }
+}
+
+type AliceChecker = PayFees<AliceAuthors, 0>;
+type UnknownChecker = PayFees<UnknownAuthor, 0>;
+type StoredChecker = PayFees<StoredDeposits, 0>;
+
+type TestHeader = sp_runtime::generic::Header<u32, BlakeTwo256>;
+type TestBlock = sp_runtime::generic::Block<TestHeader, Transaction<TestVerifier, StoredChecker>>;
+type TestExecutive = Executive<TestBlock, TestVerifier, StoredChecker>;
+
+/// Check a payout of coins with ID 0 that is authored according to the config `T`.
+fn pay_fees<T: FeesConfig + 'static>(
+    input_data: &[Output<TestVerifier>],
+    output_data: &[Output<TestVerifier>],
+    evicted_data: &[Output<TestVerifier>],
+) -> Result<TransactionPriority, FeesError> {
+    PayFees::<T, 0>::default().check(input_data, &[], output_data, evicted_data)
+}
+
+fn pot(value: u128) -> Output<TestVerifier> {
+    (FeePot::<0>(value), Unspendable).into()
+}
+
+fn fee(value: u128) -> Output<TestVerifier> {
+    (Fee::<0>(value), Unspendable).into()
+}
+
+fn payout(value: u128, owner: H256) -> Output<TestVerifier> {
+    (Coin::<0>(value), Sr25519Signature::new(owner)).into()
+}
+
+/// A spend that turns a coin worth 10 into a coin worth 6 and deposits the difference as two fees.
+fn spend() -> Transaction<TestVerifier, MoneyWithPolicy<0, FeeCollecting>> {
+    Transaction {
+        inputs: Vec::new(),
+        peeks: Vec::new(),
+        outputs: vec![payout(6, ALICE), fee(3), fee(1)],
+        checker: MoneyConstraintChecker::Spend.into(),
+        evictions: Vec::new(),
+        valid_until: None,
+    }
+}
+
+#[test]
+fn fees_move_from_spenders_to_author() {
+    let spend = spend();
+    let inputs = vec![payout(10, ALICE)];
+    assert_eq!(
+        spend.checker.check(&inputs, &[], &spend.outputs, &[]),
+        Ok(4)
+    );
+
+    // The author sweeps both deposits along with the empty pot, and receives all of the fees
+    let evicted = vec![pot(0), fee(3), fee(1)];
+    let out = vec![pot(0), payout(4, ALICE)];
+    assert_eq!(pay_fees::<AliceAuthors>(&[], &out, &evicted), Ok(0));
+}
+
+#[test]
+fn fee_deposits_are_found_in_transactions() {
+    let spend = spend();
+    let tx_hash = BlakeTwo256::hash_of(&spend.encode());
+
+    assert_eq!(
+        fee_deposits::<0, _, _>(&[spend]),
+        vec![
+            (OutputRef { tx_hash, index: 1 }, 3),
+            (OutputRef { tx_hash, index: 2 }, 1),
+        ]
+    );
+}
+
+#[test]
+fn fee_deposits_of_other_coins_are_ignored() {
+    assert!(fee_deposits::<1, _, _>(&[spend()]).is_empty());
+}
+
+#[test]
+fn created_inherent_pays_pot_and_deposits_to_author() {
+    let mut previous =
+        <AliceChecker as TuxedoInherent<TestVerifier, AliceChecker>>::genesis_transactions()
+            .pop()
+            .unwrap();
+    previous.outputs = vec![pot(2)];
+    let previous_hash = H256::repeat_byte(9);
+
+    let inherent = <AliceChecker as TuxedoInherent<TestVerifier, AliceChecker>>::create_inherent(
+        &InherentData::new(),
+        (previous, previous_hash),
+    );
+
+    let previous_pot = OutputRef {
+        tx_hash: previous_hash,
+        index: 0,
+    };
+    assert_eq!(
+        inherent.evictions,
+        vec![previous_pot, output_ref(1), output_ref(2)]
+    );
+    assert_eq!(inherent.outputs, vec![pot(0), payout(6, ALICE)]);
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &inherent.outputs, &[pot(2), fee(3), fee(1)]),
+        Ok(0)
+    );
+}
+
+#[test]
+fn created_inherent_keeps_fees_without_author() {
+    let previous =
+        <UnknownChecker as TuxedoInherent<TestVerifier, UnknownChecker>>::genesis_transactions()
+            .pop()
+            .unwrap();
+
+    let inherent =
+        <UnknownChecker as TuxedoInherent<TestVerifier, UnknownChecker>>::create_inherent(
+            &InherentData::new(),
+            (previous, H256::zero()),
+        );
+
+    assert_eq!(inherent.outputs, vec![pot(4)]);
+    assert_eq!(
+        pay_fees::<UnknownAuthor>(&[], &inherent.outputs, &[pot(0), fee(3), fee(1)]),
+        Ok(0)
+    );
+}
+
+#[test]
+fn created_inherent_skips_deposits_that_are_gone() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let previous =
+            <StoredChecker as TuxedoInherent<TestVerifier, StoredChecker>>::genesis_transactions()
+                .pop()
+                .unwrap();
+        let previous_hash = BlakeTwo256::hash_of(&previous.encode());
+        let previous_pot = OutputRef {
+            tx_hash: previous_hash,
+            index: 0,
+        };
+        TransparentUtxoSet::store_utxo(previous_pot.clone(), &pot(0));
+        TransparentUtxoSet::store_utxo(output_ref(1), &fee(3));
+        TransparentUtxoSet::store_utxo(output_ref(2), &fee(1));
+
+        // Another transaction evicts one of the deposits before the payout is created
+        TransparentUtxoSet::<TestVerifier>::consume_utxo(&output_ref(2));
+
+        let inherent =
+            <StoredChecker as TuxedoInherent<TestVerifier, StoredChecker>>::create_inherent(
+                &InherentData::new(),
+                (previous, previous_hash),
+            );
+        let payout_ref = OutputRef {
+            tx_hash: BlakeTwo256::hash_of(&inherent.encode()),
+            index: 1,
+        };
+
+        assert_eq!(inherent.evictions, vec![previous_pot, output_ref(1)]);
+        assert_eq!(TestExecutive::apply_tuxedo_transaction(inherent), Ok(()));
+        assert_eq!(
+            TransparentUtxoSet::peek_utxo(&payout_ref),
+            Some(payout(3, ALICE))
+        );
+        assert!(TransparentUtxoSet::<TestVerifier>::peek_utxo(&output_ref(1)).is_none());
+    });
+}
+
+#[test]
+fn genesis_creates_empty_pot() {
+    let genesis =
+        <AliceChecker as TuxedoInherent<TestVerifier, AliceChecker>>::genesis_transactions();
+
+    assert_eq!(genesis.len(), 1);
+    assert_eq!(genesis[0].outputs, vec![pot(0)]);
+    assert!(genesis[0].evictions.is_empty());
+}
+
+#[test]
+fn pay_fees_is_closing_inherent() {
+    let checker = AliceChecker::default();
+
+    assert!(ConstraintChecker::<TestVerifier>::is_inherent(&checker));
+    assert_eq!(
+        ConstraintChecker::<TestVerifier>::inherent_placement(&checker),
+        InherentPlacement::Closing
+    );
+}
+
+#[test]
+fn nothing_to_pay_works() {
+    assert_eq!(pay_fees::<AliceAuthors>(&[], &[pot(0)], &[pot(0)]), Ok(0));
+}
+
+#[test]
+fn fees_stay_in_pot_without_author() {
+    let evicted = vec![pot(2), fee(3)];
+    assert_eq!(pay_fees::<UnknownAuthor>(&[], &[pot(5)], &evicted), Ok(0));
+}
+
+#[test]
+fn payout_without_author_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    let out = vec![pot(0), payout(3, ALICE)];
+    assert_eq!(
+        pay_fees::<UnknownAuthor>(&[], &out, &evicted),
+        Err(ExtraOutputs)
+    );
+}
+
+#[test]
+fn payout_missing_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &[pot(3)], &evicted),
+        Err(MissingPayout)
+    );
+}
+
+#[test]
+fn payout_wrong_value_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    let out = vec![pot(0), payout(4, ALICE)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &out, &evicted),
+        Err(WrongPayoutValue)
+    );
+}
+
+#[test]
+fn payout_to_wrong_recipient_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    let out = vec![pot(0), payout(3, H256::repeat_byte(2))];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &out, &evicted),
+        Err(WrongRecipient)
+    );
+}
+
+#[test]
+fn payout_badly_typed_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    let out = vec![pot(0), (Bogus, Sr25519Signature::new(ALICE)).into()];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &out, &evicted),
+        Err(BadlyTyped)
+    );
+}
+
+#[test]
+fn payout_with_extra_outputs_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    let out = vec![pot(0), payout(3, ALICE), payout(3, ALICE)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &out, &evicted),
+        Err(ExtraOutputs)
+    );
+}
+
+#[test]
+fn payout_without_fees_fails() {
+    let out = vec![pot(0), payout(3, ALICE)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &out, &[pot(0)]),
+        Err(ExtraOutputs)
+    );
+}
+
+#[test]
+fn payout_not_emptying_pot_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    let out = vec![pot(3), payout(3, ALICE)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &out, &evicted),
+        Err(WrongPotValue)
+    );
+}
+
+#[test]
+fn pot_losing_fees_fails() {
+    let evicted = vec![pot(2), fee(3)];
+    assert_eq!(
+        pay_fees::<UnknownAuthor>(&[], &[pot(3)], &evicted),
+        Err(WrongPotValue)
+    );
+}
+
+#[test]
+fn payout_with_inputs_fails() {
+    let inp = vec![payout(3, ALICE)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&inp, &[pot(0)], &[pot(0)]),
+        Err(InputsWhilePayingFees)
+    );
+}
+
+#[test]
+fn payout_without_previous_pot_fails() {
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &[pot(0)], &[]),
+        Err(MissingPreviousPot)
+    );
+}
+
+#[test]
+fn payout_evicting_two_pots_fails() {
+    assert_eq!(
+        pay_fees::<UnknownAuthor>(&[], &[pot(0)], &[pot(0), pot(0)]),
+        Err(MissingPreviousPot)
+    );
+}
+
+#[test]
+fn payout_evicting_coins_fails() {
+    let evicted = vec![pot(0), payout(3, ALICE)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &[pot(0)], &evicted),
+        Err(BadlyTyped)
+    );
+}
+
+#[test]
+fn payout_without_pot_fails() {
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &[], &[pot(0)]),
+        Err(MissingPot)
+    );
+}
+
+#[test]
+fn payout_before_pot_fails() {
+    let evicted = vec![pot(0), fee(3)];
+    let out = vec![payout(3, ALICE), pot(0)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &out, &evicted),
+        Err(MissingPot)
+    );
+}
+
+#[test]
+fn fees_overflow_fails() {
+    let evicted = vec![pot(u128::MAX), fee(1)];
+    assert_eq!(
+        pay_fees::<AliceAuthors>(&[], &[pot(0)], &evicted),
+        Err(ValueOverflow)
+    );
+}
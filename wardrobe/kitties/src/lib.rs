@@ -57,7 +57,7 @@ use sp_runtime::{
 };
 use sp_std::prelude::*;
 use tuxedo_core::{
-    dynamic_typing::{DynamicallyTypedData, UtxoData},
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
     ensure,
     types::Transaction,
     SimpleConstraintChecker, Verifier,
@@ -215,7 +215,9 @@ pub struct KittyDNA(pub H256);
     Hash,
     Debug,
     TypeInfo,
+    UtxoData,
 )]
+#[utxo_data(type_id = *b"Kitt")]
 pub struct KittyData {
     pub parent: Parent,
     pub free_breedings: u64, // Ignore in breed for money case
@@ -245,6 +247,8 @@ impl KittyData {
             )
                 .into()],
             checker: FreeKittyConstraintChecker::Create.into(),
+            evictions: Vec::new(),
+            valid_until: None,
         }
     }
 }
@@ -261,10 +265,6 @@ impl Default for KittyData {
     }
 }
 
-impl UtxoData for KittyData {
-    const TYPE_ID: [u8; 4] = *b"Kitt";
-}
-
 /// Reasons that kitty opertaion may go wrong.
 #[derive(
     Serialize,
@@ -581,6 +581,7 @@ impl SimpleConstraintChecker for FreeKittyConstraintChecker {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
         match &self {
             Self::Create => {
@@ -622,6 +623,10 @@ impl SimpleConstraintChecker for FreeKittyConstraintChecker {
             }
         }
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<KittyData>()
+    }
 }
 
 /// Checks if input and output contain a list of KittyData in the same order.
@@ -53,6 +53,7 @@ fn create_happy_path_works() {
         &[],
         &[],
         &[KittyData::default().into(), KittyData::default_dad().into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -64,13 +65,14 @@ fn create_with_input_fails() {
         &[KittyData::default().into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::CreatingWithInputs));
 }
 #[test]
 fn create_without_output_fails() {
     let result =
-        FreeKittyConstraintChecker::check(&FreeKittyConstraintChecker::Create, &[], &[], &[]);
+        FreeKittyConstraintChecker::check(&FreeKittyConstraintChecker::Create, &[], &[], &[], &[]);
     assert_eq!(result, Err(ConstraintCheckerError::CreatingNothing));
 }
 #[test]
@@ -84,6 +86,7 @@ fn create_with_wrong_output_type_fails() {
             KittyData::default().into(),
             KittyData::default_dad().into(),
         ],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::BadlyTyped));
 }
@@ -100,6 +103,7 @@ fn breed_happy_path_works() {
             new_family[1].clone().into(),
             new_family[2].clone().into(),
         ],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -111,6 +115,7 @@ fn breed_wrong_input_type_fails() {
         &[Bogus.into(), Bogus.into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::BadlyTyped));
 }
@@ -122,6 +127,7 @@ fn breed_wrong_output_type_fails() {
         &[KittyData::default().into(), KittyData::default_dad().into()],
         &[],
         &[Bogus.into(), Bogus.into(), Bogus.into()],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::BadlyTyped));
 }
@@ -133,6 +139,7 @@ fn inputs_dont_contain_two_parents_fails() {
         &[KittyData::default().into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::TwoParentsDoNotExist));
 }
@@ -144,6 +151,7 @@ fn outputs_dont_contain_all_family_members_fails() {
         &[KittyData::default().into(), KittyData::default_dad().into()],
         &[],
         &[KittyData::default().into()],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::NotEnoughFamilyMembers));
 }
@@ -158,6 +166,7 @@ fn breed_two_dads_fails() {
         ],
         &[],
         &[KittyData::default().into()],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::TwoDadsNotValid));
 }
@@ -169,6 +178,7 @@ fn breed_two_moms_fails() {
         &[KittyData::default().into(), KittyData::default().into()],
         &[],
         &[KittyData::default().into()],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::TwoMomsNotValid));
 }
@@ -180,6 +190,7 @@ fn first_input_not_mom_fails() {
         &[KittyData::default_dad().into(), KittyData::default().into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::TwoDadsNotValid))
 }
@@ -195,6 +206,7 @@ fn first_output_not_mom_fails() {
             KittyData::default().into(),
             KittyData::default_child().into(),
         ],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::TwoDadsNotValid));
 }
@@ -209,6 +221,7 @@ fn breed_mom_when_she_gave_birth_recently_fails() {
         &[new_momma.into(), KittyData::default_dad().into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::MomNotReadyYet));
 }
@@ -223,6 +236,7 @@ fn breed_dad_when_he_is_tired_fails() {
         &[KittyData::default().into(), tired_dadda.into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::DadTooTired));
 }
@@ -237,6 +251,7 @@ fn check_mom_breedings_overflow_fails() {
         &[test_mom.into(), KittyData::default_dad().into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(
         result,
@@ -254,6 +269,7 @@ fn check_dad_breedings_overflow_fails() {
         &[KittyData::default().into(), test_dad.into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(
         result,
@@ -271,6 +287,7 @@ fn check_mom_free_breedings_zero_fails() {
         &[test_mom.into(), KittyData::default_dad().into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::NotEnoughFreeBreedings));
 }
@@ -285,6 +302,7 @@ fn check_dad_free_breedings_zero_fails() {
         &[KittyData::default().into(), test_dad.into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::NotEnoughFreeBreedings));
 }
@@ -304,6 +322,7 @@ fn check_new_mom_free_breedings_incorrect_fails() {
             new_family[1].clone().into(),
             new_family[2].clone().into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -326,6 +345,7 @@ fn check_new_dad_free_breedings_incorrect_fails() {
             new_dad.into(),
             new_family[2].clone().into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -348,6 +368,7 @@ fn check_new_mom_num_breedings_incorrect_fails() {
             new_family[1].clone().into(),
             new_family[2].clone().into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -370,6 +391,7 @@ fn check_new_dad_num_breedings_incorrect_fails() {
             new_dad.into(),
             new_family[2].clone().into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -392,6 +414,7 @@ fn check_new_mom_dna_doesnt_match_old_fails() {
             new_family[1].clone().into(),
             new_family[2].clone().into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -414,6 +437,7 @@ fn check_new_dad_dna_doesnt_match_old_fails() {
             new_dad.into(),
             new_family[2].clone().into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -436,6 +460,7 @@ fn check_child_dna_incorrect_fails() {
             new_family[1].clone().into(),
             new_child.into(),
         ],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::NewChildDnaIncorrect));
 }
@@ -455,6 +480,7 @@ fn check_child_dad_parent_tired_fails() {
             new_family[1].clone().into(),
             new_child.into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -477,6 +503,7 @@ fn check_child_mom_parent_recently_gave_birth_fails() {
             new_family[1].clone().into(),
             new_child.into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -499,6 +526,7 @@ fn check_child_free_breedings_incorrect_fails() {
             new_family[1].clone().into(),
             new_child.into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -521,6 +549,7 @@ fn check_child_num_breedings_non_zero_fails() {
             new_family[1].clone().into(),
             new_child.into(),
         ],
+        &[],
     );
     assert_eq!(
         result,
@@ -539,6 +568,7 @@ fn update_name_happy_path_works() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -559,6 +589,7 @@ fn update_name_happy_path_with_multiple_input_sworks() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.into(), output2.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -578,6 +609,7 @@ fn update_name_inputs_and_outputs_number_mismatch_fails() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -594,6 +626,7 @@ fn update_name_no_inputs_fails() {
         &[],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -610,6 +643,7 @@ fn update_name_no_output_fails() {
         &[input.into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(
         result,
@@ -629,6 +663,7 @@ fn update_name_dna_update_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -652,6 +687,7 @@ fn update_name_out_of_order_input_and_output_fails() {
         &[input.clone().into(), input1.into()],
         &[],
         &[output1.clone().into(), output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -666,6 +702,7 @@ fn update_name_name_unupdated_path_fails() {
         &[KittyData::default_dad().into()],
         &[],
         &[KittyData::default_dad().into()],
+        &[],
     );
     assert_eq!(result, Err(ConstraintCheckerError::KittyNameUnAltered));
 }
@@ -681,6 +718,7 @@ fn update_name_free_breeding_updated_path_fails() {
         &[KittyData::default().into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -699,6 +737,7 @@ fn update_name_num_of_breeding_updated_path_fails() {
         &[KittyData::default().into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -717,6 +756,7 @@ fn update_name_gender_updated_path_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -2,17 +2,22 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use core::marker::PhantomData;
+
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
+use sp_core::H256;
 use sp_runtime::transaction_validity::TransactionPriority;
 use sp_std::prelude::*;
 use tuxedo_core::{
-    dynamic_typing::{DynamicallyTypedData, UtxoData},
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
     ensure,
+    support_macros::{CloneNoBound, DebugNoBound},
     traits::Cash,
-    types::Transaction,
-    SimpleConstraintChecker, Verifier,
+    types::{Output, Transaction},
+    verifier::Unspendable,
+    ConstraintChecker, SimpleConstraintChecker, Verifier,
 };
 
 #[cfg(test)]
@@ -23,12 +28,50 @@ impl<const ID: u8> Cash for Coin<ID> {
         self.0
     }
 
-    const ID: u8 = ID;
+    fn asset_id(&self) -> H256 {
+        H256::from_low_u64_be(ID.into())
+    }
 }
 
 // use log::info;
 
+/// Configuration items for issuing the coins with a given `ID` when the
+/// money piece is instantiated in a concrete runtime.
+pub trait MintPolicy<const ID: u8> {
+    /// Whether anyone may mint coins out of the void.
+    ///
+    /// When this is `false`, every mint transaction must consume a `MintAuthority<ID>`.
+    /// Development chains may like to keep minting free, but production chains should not.
+    const FREE_MINTING: bool;
+
+    /// Whether mint and burn transactions must keep a `Supply<ID>` UTXO up to date.
+    ///
+    /// When this is `true`, mints and burns evict the previous supply and create an updated one,
+    /// and the latest supply is noted in the `SupplyStorage` for the `MoneySupplyApi`.
+    /// Spends may not burn any value, except what they deposit as fees, so the supply stays exact.
+    const TRACK_SUPPLY: bool = false;
+
+    /// Whether spend transactions may deposit the value they burn as fees.
+    ///
+    /// When this is `true`, a spend may create `Fee<ID>` outputs alongside its coins, worth at most
+    /// the difference between its inputs and its coin outputs, so that the value can be paid out to
+    /// block authors by another piece such as the fees piece. Any value that is not deposited is lost.
+    const COLLECT_FEES: bool = false;
+}
+
+/// A mint policy that allows anyone to mint any coins.
+/// This is how minting worked before mint authorities existed, so it is the default policy.
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Debug)]
+pub struct FreeMinting;
+
+impl<const ID: u8> MintPolicy<ID> for FreeMinting {
+    const FREE_MINTING: bool = true;
+}
+
 /// The main constraint checker for the money piece. Allows spending and minting tokens.
+///
+/// On its own, this checker follows the `FreeMinting` policy. Runtimes that configure a
+/// `MintPolicy` wrap it in a `MoneyWithPolicy` instead.
 #[derive(
     Serialize,
     Deserialize,
@@ -46,12 +89,201 @@ impl<const ID: u8> Cash for Coin<ID> {
 pub enum MoneyConstraintChecker<const ID: u8> {
     /// A typical spend transaction where some coins are consumed and others are created.
     /// Input value must exceed output value. The difference is burned and reflected in the
-    /// transaction's priority.
+    /// transaction's priority. If the mint policy collects fees, some or all of the difference may be
+    /// deposited as `Fee<ID>` outputs instead. If the mint policy tracks supply, all of it must be.
     Spend,
-    /// A mint transaction that creates no coins out of the void. In a real-world chain,
-    /// this should be protected somehow, or not included at all. For now it is publicly
-    /// available. I'm adding it to explore multiple validation paths in a single piece.
+    /// A mint transaction that creates coins out of the void.
+    ///
+    /// Unless the mint policy allows free minting, the transaction must consume a single
+    /// `MintAuthority<ID>`. The authority may be passed on by re-creating it among the outputs.
     Mint,
+    /// A burn transaction that consumes coins and creates none.
+    /// Like in a spend transaction, the burned value is reflected in the transaction's priority.
+    Burn,
+}
+
+/// The money constraint checker of a runtime whose `MintPolicy` for coins with this `ID` is `T`.
+///
+/// This wraps a `MoneyConstraintChecker` and is encoded exactly like it.
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, Encode, Decode, DebugNoBound, CloneNoBound, TypeInfo,
+)]
+#[serde(transparent)]
+#[scale_info(skip_type_params(T))]
+pub struct MoneyWithPolicy<const ID: u8, T>(
+    pub MoneyConstraintChecker<ID>,
+    #[serde(skip)] PhantomData<T>,
+);
+
+impl<const ID: u8, T> From<MoneyConstraintChecker<ID>> for MoneyWithPolicy<ID, T> {
+    fn from(checker: MoneyConstraintChecker<ID>) -> Self {
+        Self(checker, PhantomData)
+    }
+}
+
+/// The right to mint coins with a given `ID` when the runtime's mint policy does not allow free minting.
+///
+/// A mint authority is expected to be protected by a multisignature verifier. Peeks do not consult
+/// verifiers, so merely peeking at an authority proves nothing. Instead a mint transaction consumes the
+/// authority, and usually re-creates it among its outputs so that it can be used again.
+///
+/// Mint authorities cannot be created by any transaction, so they must be placed in the genesis state.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    Default,
+    TypeInfo,
+    UtxoData,
+)]
+#[utxo_data(type_id = [b'm', b'n', b't', ID])]
+pub struct MintAuthority<const ID: u8>;
+
+impl<const ID: u8> MintAuthority<ID> {
+    /// Create the genesis transaction that places a mint authority, protected by the given verifier, in state.
+    ///
+    /// This transaction would not pass the constraint checker, because an authority cannot be
+    /// created from nothing. It is only valid at genesis, where transactions are not checked
+    /// unless strict genesis validation is enabled.
+    pub fn genesis<V, OV, OC, T>(v: V) -> Transaction<OV, OC>
+    where
+        V: Verifier,
+        OV: Verifier + From<V>,
+        OC: tuxedo_core::ConstraintChecker<OV> + From<MoneyWithPolicy<ID, T>>,
+    {
+        Transaction {
+            inputs: vec![],
+            peeks: vec![],
+            outputs: vec![(Self, v).into()],
+            checker: MoneyWithPolicy::from(MoneyConstraintChecker::Mint).into(),
+            evictions: Vec::new(),
+            valid_until: None,
+        }
+    }
+}
+
+/// The issuance figures of one kind of coin.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    Default,
+    TypeInfo,
+)]
+pub struct SupplyInfo {
+    /// The total value of all coins that have been minted and not explicitly burned.
+    ///
+    /// Value deposited as fees is still counted until it is paid out, because it is not burned.
+    pub circulating: u128,
+    /// The total value of all coins that have been burned by burn transactions.
+    pub burned: u128,
+}
+
+/// The supply of coins with a given `ID`, for runtimes whose mint policy tracks supply.
+/// A new-type wrapper around `SupplyInfo`.
+///
+/// There is a single supply UTXO for each kind of coin. It is protected by the `Unspendable`
+/// verifier, and each mint or burn transaction evicts it and creates an updated one. This means
+/// that at most one mint or burn of each kind of coin can be included per block.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    Default,
+    TypeInfo,
+    UtxoData,
+)]
+#[utxo_data(type_id = [b's', b'u', b'p', ID])]
+pub struct Supply<const ID: u8>(pub SupplyInfo);
+
+impl<const ID: u8> Supply<ID> {
+    /// Create the genesis transaction that begins tracking the supply of coins with this `ID`.
+    ///
+    /// The circulating value should match the total value of the genesis coins. Like a mint
+    /// authority, the supply cannot be created from nothing, so this is only valid at genesis.
+    /// The same supply should be noted in the genesis storage with `SupplyStorage::genesis_entry`.
+    pub fn genesis<OV, OC, T>(circulating: u128) -> Transaction<OV, OC>
+    where
+        OV: Verifier + From<Unspendable>,
+        OC: tuxedo_core::ConstraintChecker<OV> + From<MoneyWithPolicy<ID, T>>,
+    {
+        let supply = Self(SupplyInfo {
+            circulating,
+            burned: 0,
+        });
+        Transaction {
+            inputs: vec![],
+            peeks: vec![],
+            outputs: vec![(supply, Unspendable).into()],
+            checker: MoneyWithPolicy::from(MoneyConstraintChecker::Mint).into(),
+            evictions: Vec::new(),
+            valid_until: None,
+        }
+    }
+}
+
+/// The prefix of the storage keys under which the latest supply of each kind of coin is noted.
+/// The coin's `ID` follows the prefix.
+const SUPPLY_KEY_PREFIX: &[u8] = b"money_supply";
+
+/// A public interface for reading the latest tracked supply of each kind of coin.
+///
+/// Constraint checkers cannot read storage, so the supply is noted here as a side effect of
+/// checking each mint or burn transaction. The initial supply is noted at genesis.
+pub enum SupplyStorage {}
+
+impl SupplyStorage {
+    fn key(id: u8) -> Vec<u8> {
+        [SUPPLY_KEY_PREFIX, &[id]].concat()
+    }
+
+    /// Read the latest supply of coins with the given `ID`, if it is tracked.
+    pub fn get(id: u8) -> Option<SupplyInfo> {
+        sp_io::storage::get(&Self::key(id)).and_then(|d| SupplyInfo::decode(&mut &*d).ok())
+    }
+
+    fn set(id: u8, supply: &SupplyInfo) {
+        sp_io::storage::set(&Self::key(id), &supply.encode());
+    }
+
+    /// The storage key and value that note the given supply of coins with the given `ID`.
+    /// Runtimes that track supply insert this into their genesis storage along with the
+    /// `Supply::genesis` transaction, so that the supply is available before the first mint or burn.
+    pub fn genesis_entry(id: u8, supply: &SupplyInfo) -> (Vec<u8>, Vec<u8>) {
+        (Self::key(id), supply.encode())
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    /// Read access to the tracked supply of each kind of coin, for clients such as explorers.
+    pub trait MoneySupplyApi {
+        /// The latest supply of coins with the given `ID`, or `None` if it is not tracked.
+        fn supply(id: u8) -> Option<SupplyInfo>;
+    }
 }
 
 /// A single coin in the fungible money system.
@@ -69,34 +301,58 @@ pub enum MoneyConstraintChecker<const ID: u8> {
     Hash,
     Debug,
     TypeInfo,
+    UtxoData,
 )]
+#[utxo_data(type_id = [b'c', b'o', b'i', ID])]
 pub struct Coin<const ID: u8>(pub u128);
 
+/// Fees that a spend transaction deposits for block authors, when the mint policy collects fees.
+/// A new-type wrapper around a `u128` value.
+///
+/// Fees are not coins, so they cannot be spent. Instead another piece, such as the fees piece,
+/// evicts them and pays their value out to block authors. Their verifier is never consulted,
+/// so they must be protected by the `Unspendable` verifier.
+#[derive(
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Clone,
+    Encode,
+    Decode,
+    Hash,
+    Debug,
+    TypeInfo,
+    UtxoData,
+)]
+#[utxo_data(type_id = [b'f', b'e', b'e', ID])]
+pub struct Fee<const ID: u8>(pub u128);
+
 impl<const ID: u8> Coin<ID> {
     pub fn new(amt: u128) -> Self {
         Coin(amt)
     }
 
     /// Create a mint transaction for a single Coin.
-    pub fn mint<V, OV, OC>(amt: u128, v: V) -> Transaction<OV, OC>
+    pub fn mint<V, OV, OC, T>(amt: u128, v: V) -> Transaction<OV, OC>
     where
         V: Verifier,
         OV: Verifier + From<V>,
-        OC: tuxedo_core::ConstraintChecker<OV> + From<MoneyConstraintChecker<ID>>,
+        OC: tuxedo_core::ConstraintChecker<OV> + From<MoneyWithPolicy<ID, T>>,
     {
         Transaction {
             inputs: vec![],
             peeks: vec![],
             outputs: vec![(Self::new(amt), v).into()],
-            checker: MoneyConstraintChecker::Mint.into(),
+            checker: MoneyWithPolicy::from(MoneyConstraintChecker::Mint).into(),
+            evictions: Vec::new(),
+            valid_until: None,
         }
     }
 }
 
-impl<const ID: u8> UtxoData for Coin<ID> {
-    const TYPE_ID: [u8; 4] = [b'c', b'o', b'i', ID];
-}
-
 /// Errors that can occur when checking money transactions.
 #[derive(
     Serialize,
@@ -116,7 +372,8 @@ pub enum ConstraintCheckerError {
     /// Dynamic typing issue.
     /// This error doesn't discriminate between badly typed inputs and outputs.
     BadlyTyped,
-    /// The transaction attempts to consume inputs while minting. This is not allowed.
+    /// The transaction attempts to consume inputs other than a single mint authority while minting.
+    /// This is not allowed.
     MintingWithInputs,
     /// The transaction attempts to mint zero coins. This is not allowed.
     MintingNothing,
@@ -133,6 +390,74 @@ pub enum ConstraintCheckerError {
     /// The transaction attempted to create a coin with zero value. This is not allowed
     /// because it wastes state space.
     ZeroValueCoin,
+    /// The transaction evicts something other than the previous supply of a tracked mint or burn.
+    EvictionsNotAllowed,
+    /// The mint policy does not allow free minting, and the transaction does not consume a mint authority.
+    MissingMintAuthority,
+    /// The transaction creates more mint authorities than it consumes.
+    TooManyMintAuthorities,
+    /// The mint policy tracks supply, and the transaction does not evict the previous supply
+    /// or does not create an updated one.
+    MissingSupply,
+    /// The updated supply created by the transaction does not match the value minted or burned.
+    SupplyMismatch,
+    /// The transaction attempts to create outputs other than the updated supply while burning.
+    BurningWithOutputs,
+    /// The fees deposited by a spend transaction are worth more than the value it burns.
+    FeesExceedBurnedValue,
+    /// The mint policy tracks supply, and a spend transaction burns value that it does not deposit
+    /// as fees. Value may only leave circulation through burn transactions.
+    InputsExceedOutputs,
+    /// The transaction creates a supply or fee that is not protected by the `Unspendable` verifier.
+    NotUnspendable,
+}
+
+/// Calculate the priority of a transaction that burns the given value.
+fn burn_priority(burned: u128) -> TransactionPriority {
+    if burned < u64::max_value() as u128 {
+        burned as u64
+    } else {
+        u64::max_value()
+    }
+}
+
+impl<const ID: u8, T: MintPolicy<ID>> MoneyWithPolicy<ID, T> {
+    /// Extract the previous supply from the evicted data if the mint policy tracks supply.
+    /// Otherwise nothing can have been evicted.
+    fn previous_supply(
+        evicted_data: &[DynamicallyTypedData],
+    ) -> Result<Option<SupplyInfo>, ConstraintCheckerError> {
+        if !T::TRACK_SUPPLY {
+            return Ok(None);
+        }
+
+        match evicted_data {
+            [evicted] => evicted
+                .extract::<Supply<ID>>()
+                .map(|supply| Some(supply.0))
+                .map_err(|_| ConstraintCheckerError::MissingSupply),
+            [] => Err(ConstraintCheckerError::MissingSupply),
+            _ => Err(ConstraintCheckerError::EvictionsNotAllowed),
+        }
+    }
+
+    /// Make sure the transaction created exactly the expected updated supply,
+    /// and note it for the `MoneySupplyApi`.
+    fn note_updated_supply(
+        expected: SupplyInfo,
+        created: &[SupplyInfo],
+    ) -> Result<(), ConstraintCheckerError> {
+        match created {
+            [supply] => ensure!(*supply == expected, ConstraintCheckerError::SupplyMismatch),
+            [] => return Err(ConstraintCheckerError::MissingSupply),
+            _ => return Err(ConstraintCheckerError::SupplyMismatch),
+        }
+
+        // SIDE EFFECT: Note the new supply so it is available to the runtime API
+        SupplyStorage::set(ID, &expected);
+
+        Ok(())
+    }
 }
 
 impl<const ID: u8> SimpleConstraintChecker for MoneyConstraintChecker<ID> {
@@ -143,9 +468,42 @@ impl<const ID: u8> SimpleConstraintChecker for MoneyConstraintChecker<ID> {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
-        match &self {
-            Self::Spend => {
+        MoneyWithPolicy::<ID, FreeMinting>::from(self.clone()).check_payloads(
+            input_data,
+            output_data,
+            evicted_data,
+        )
+    }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        [
+            utxo_type_info::<Coin<ID>>(),
+            utxo_type_info::<MintAuthority<ID>>(),
+            utxo_type_info::<Supply<ID>>(),
+            utxo_type_info::<Fee<ID>>(),
+        ]
+        .concat()
+    }
+}
+
+impl<const ID: u8, T: MintPolicy<ID>> MoneyWithPolicy<ID, T> {
+    /// Check the payloads of a transaction against the mint policy.
+    /// The verifiers are checked separately by the `ConstraintChecker` implementation.
+    fn check_payloads(
+        &self,
+        input_data: &[DynamicallyTypedData],
+        output_data: &[DynamicallyTypedData],
+        evicted_data: &[DynamicallyTypedData],
+    ) -> Result<TransactionPriority, ConstraintCheckerError> {
+        match &self.0 {
+            MoneyConstraintChecker::Spend => {
+                ensure!(
+                    evicted_data.is_empty(),
+                    ConstraintCheckerError::EvictionsNotAllowed
+                );
+
                 // Check that we are consuming at least one input
                 log::info!("Money:Spend called ");
                 ensure!(
@@ -155,6 +513,7 @@ impl<const ID: u8> SimpleConstraintChecker for MoneyConstraintChecker<ID> {
 
                 let mut total_input_value: u128 = 0;
                 let mut total_output_value: u128 = 0;
+                let mut total_fee_value: u128 = 0;
 
                 // Check that sum of input values < output values
                 for input in input_data {
@@ -168,6 +527,16 @@ impl<const ID: u8> SimpleConstraintChecker for MoneyConstraintChecker<ID> {
                 }
 
                 for utxo in output_data {
+                    // If the mint policy collects fees, some of the burned value may be deposited as fees
+                    if T::COLLECT_FEES {
+                        if let Ok(fee) = utxo.extract::<Fee<ID>>() {
+                            ensure!(fee.0 > 0, ConstraintCheckerError::ZeroValueCoin);
+                            total_fee_value = total_fee_value
+                                .checked_add(fee.0)
+                                .ok_or(ConstraintCheckerError::ValueOverflow)?;
+                            continue;
+                        }
+                    }
                     let utxo_value = utxo
                         .extract::<Coin<ID>>()
                         .map_err(|_| ConstraintCheckerError::BadlyTyped)?
@@ -183,44 +552,193 @@ impl<const ID: u8> SimpleConstraintChecker for MoneyConstraintChecker<ID> {
                     ConstraintCheckerError::OutputsExceedInputs
                 );
 
-                // Priority is based on how many token are burned
+                // Priority is based on how many token are burned, whether or not they are deposited as fees
                 // Type stuff is kinda ugly. Maybe division would be better?
                 let burned = total_input_value - total_output_value;
                 log::info!(
                     "Money:Spend Total burned for this transaction = {:?}",
                     burned
                 );
-                Ok(if burned < u64::max_value() as u128 {
-                    burned as u64
-                } else {
-                    u64::max_value()
-                })
+                ensure!(
+                    total_fee_value <= burned,
+                    ConstraintCheckerError::FeesExceedBurnedValue
+                );
+                // When the supply is tracked, nothing may be burned without updating it
+                ensure!(
+                    !T::TRACK_SUPPLY || total_fee_value == burned,
+                    ConstraintCheckerError::InputsExceedOutputs
+                );
+
+                Ok(burn_priority(burned))
             }
-            Self::Mint => {
-                // Make sure there are no inputs being consumed
+            MoneyConstraintChecker::Mint => {
+                let previous_supply = Self::previous_supply(evicted_data)?;
+
+                // Make sure the only input, if any, is a mint authority
                 ensure!(
-                    input_data.is_empty(),
+                    input_data.len() <= 1,
                     ConstraintCheckerError::MintingWithInputs
                 );
+                let consumed_authorities = input_data.len();
+                for input in input_data {
+                    input
+                        .extract::<MintAuthority<ID>>()
+                        .map_err(|_| ConstraintCheckerError::MintingWithInputs)?;
+                }
 
-                // Make sure there is at least one output being minted
+                // Unless minting is free, a mint authority must be consumed
                 ensure!(
-                    !output_data.is_empty(),
-                    ConstraintCheckerError::MintingNothing
+                    T::FREE_MINTING || consumed_authorities == 1,
+                    ConstraintCheckerError::MissingMintAuthority
                 );
 
-                // Make sure the outputs are the right type
+                // Make sure the outputs are the right type, and separate the passed-on
+                // authority and the updated supply from the newly minted coins
+                let mut created_authorities = 0;
+                let mut created_supplies = Vec::new();
+                let mut minted_coins = 0;
+                let mut minted_value: u128 = 0;
                 for utxo in output_data {
+                    if utxo.extract::<MintAuthority<ID>>().is_ok() {
+                        created_authorities += 1;
+                        continue;
+                    }
+                    if previous_supply.is_some() {
+                        if let Ok(supply) = utxo.extract::<Supply<ID>>() {
+                            created_supplies.push(supply.0);
+                            continue;
+                        }
+                    }
                     let utxo_value = utxo
                         .extract::<Coin<ID>>()
                         .map_err(|_| ConstraintCheckerError::BadlyTyped)?
                         .0;
                     ensure!(utxo_value > 0, ConstraintCheckerError::ZeroValueCoin);
+                    minted_coins += 1;
+                    minted_value = minted_value
+                        .checked_add(utxo_value)
+                        .ok_or(ConstraintCheckerError::ValueOverflow)?;
+                }
+
+                // An authority may be passed on, but never duplicated or created from nothing
+                ensure!(
+                    created_authorities <= consumed_authorities,
+                    ConstraintCheckerError::TooManyMintAuthorities
+                );
+
+                // Make sure there is at least one coin being minted
+                ensure!(minted_coins > 0, ConstraintCheckerError::MintingNothing);
+
+                // Make sure the supply is updated by the minted value
+                if let Some(previous) = previous_supply {
+                    let expected = SupplyInfo {
+                        circulating: previous
+                            .circulating
+                            .checked_add(minted_value)
+                            .ok_or(ConstraintCheckerError::ValueOverflow)?,
+                        burned: previous.burned,
+                    };
+                    Self::note_updated_supply(expected, &created_supplies)?;
                 }
 
                 // No priority for minting
                 Ok(0)
             }
+            MoneyConstraintChecker::Burn => {
+                let previous_supply = Self::previous_supply(evicted_data)?;
+
+                // Check that we are burning at least one input
+                ensure!(
+                    !input_data.is_empty(),
+                    ConstraintCheckerError::SpendingNothing
+                );
+
+                let mut burned_value: u128 = 0;
+                for input in input_data {
+                    let utxo_value = input
+                        .extract::<Coin<ID>>()
+                        .map_err(|_| ConstraintCheckerError::BadlyTyped)?
+                        .0;
+                    burned_value = burned_value
+                        .checked_add(utxo_value)
+                        .ok_or(ConstraintCheckerError::ValueOverflow)?;
+                }
+
+                // The only output allowed is the updated supply
+                let mut created_supplies = Vec::new();
+                for utxo in output_data {
+                    ensure!(
+                        previous_supply.is_some(),
+                        ConstraintCheckerError::BurningWithOutputs
+                    );
+                    let supply = utxo
+                        .extract::<Supply<ID>>()
+                        .map_err(|_| ConstraintCheckerError::BurningWithOutputs)?;
+                    created_supplies.push(supply.0);
+                }
+
+                // Make sure the supply is updated by the burned value
+                if let Some(previous) = previous_supply {
+                    let expected = SupplyInfo {
+                        circulating: previous
+                            .circulating
+                            .checked_sub(burned_value)
+                            .ok_or(ConstraintCheckerError::SupplyMismatch)?,
+                        burned: previous
+                            .burned
+                            .checked_add(burned_value)
+                            .ok_or(ConstraintCheckerError::ValueOverflow)?,
+                    };
+                    Self::note_updated_supply(expected, &created_supplies)?;
+                }
+
+                Ok(burn_priority(burned_value))
+            }
+        }
+    }
+}
+
+impl<const ID: u8, T: MintPolicy<ID>, V: Verifier + From<Unspendable> + PartialEq>
+    ConstraintChecker<V> for MoneyWithPolicy<ID, T>
+{
+    type Error = ConstraintCheckerError;
+    type InherentHooks = ();
+
+    fn check(
+        &self,
+        inputs: &[Output<V>],
+        _peeks: &[Output<V>],
+        outputs: &[Output<V>],
+        evictions: &[Output<V>],
+    ) -> Result<TransactionPriority, Self::Error> {
+        // The supply and fees are evicted rather than consumed, so their verifiers are never consulted.
+        // They must be unspendable so that no other constraint checker can consume them instead.
+        for output in outputs {
+            let payload = &output.payload;
+            if payload.extract::<Supply<ID>>().is_ok() || payload.extract::<Fee<ID>>().is_ok() {
+                ensure!(
+                    output.verifier == V::from(Unspendable),
+                    ConstraintCheckerError::NotUnspendable
+                );
+            }
         }
+
+        let payloads = |utxos: &[Output<V>]| -> Vec<DynamicallyTypedData> {
+            utxos.iter().map(|utxo| utxo.payload.clone()).collect()
+        };
+        self.check_payloads(&payloads(inputs), &payloads(outputs), &payloads(evictions))
+    }
+
+    fn is_inherent(&self) -> bool {
+        false
+    }
+
+    // Tracked mints and burns evict the previous supply.
+    fn allows_evictions(&self) -> bool {
+        T::TRACK_SUPPLY
+    }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        <MoneyConstraintChecker<ID> as SimpleConstraintChecker>::utxo_types()
     }
 }
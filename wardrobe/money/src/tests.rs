@@ -1,7 +1,32 @@
 //! Unit tests for the Money piece
 
 use super::*;
-use tuxedo_core::dynamic_typing::testing::Bogus;
+use tuxedo_core::{dynamic_typing::testing::Bogus, verifier::UpForGrabs};
+
+/// A verifier that can be either spendable or not.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+enum TestVerifier {
+    UpForGrabs(UpForGrabs),
+    Unspendable(Unspendable),
+}
+
+impl From<UpForGrabs> for TestVerifier {
+    fn from(v: UpForGrabs) -> Self {
+        Self::UpForGrabs(v)
+    }
+}
+
+impl From<Unspendable> for TestVerifier {
+    fn from(v: Unspendable) -> Self {
+        Self::Unspendable(v)
+    }
+}
+
+impl tuxedo_core::verifier::SimpleVerifier for TestVerifier {
+    fn verify(&self, _simplified_tx: &[u8], _block_height: u32, _redeemer: &[u8]) -> bool {
+        matches!(self, Self::UpForGrabs(_))
+    }
+}
 
 #[test]
 fn spend_valid_transaction_work() {
@@ -10,7 +35,7 @@ fn spend_valid_transaction_work() {
     let expected_priority = 1u64;
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
         Ok(expected_priority),
     );
 }
@@ -25,7 +50,7 @@ fn spend_with_zero_value_output_fails() {
     ]; // total 11
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::ZeroValueCoin),
     );
 }
@@ -37,7 +62,7 @@ fn spend_no_outputs_is_a_burn() {
     let expected_priority = 12u64;
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
         Ok(expected_priority),
     );
 }
@@ -48,7 +73,7 @@ fn spend_no_inputs_fails() {
     let output_data = vec![Coin::<0>(10).into(), Coin::<0>(1).into()];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::SpendingNothing),
     );
 }
@@ -59,7 +84,7 @@ fn spend_wrong_input_type_fails() {
     let output_data = vec![Coin::<0>(10).into(), Coin::<0>(1).into()];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::BadlyTyped),
     );
 }
@@ -70,7 +95,7 @@ fn spend_wrong_output_type_fails() {
     let output_data = vec![Bogus.into()];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::BadlyTyped),
     );
 }
@@ -81,7 +106,7 @@ fn spend_output_value_exceeds_input_value_fails() {
     let output_data = vec![Coin::<0>(5).into(), Coin::<0>(7).into()]; // total 12
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::OutputsExceedInputs),
     );
 }
@@ -92,7 +117,7 @@ fn mint_valid_transaction_works() {
     let output_data = vec![Coin::<0>(10).into(), Coin::<0>(1).into()];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data, &[]),
         Ok(0),
     );
 }
@@ -103,7 +128,7 @@ fn mint_with_zero_value_output_fails() {
     let output_data = vec![Coin::<0>(0).into()];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::ZeroValueCoin),
     );
 }
@@ -114,7 +139,7 @@ fn mint_with_inputs_fails() {
     let output_data = vec![Coin::<0>(10).into(), Coin::<0>(1).into()];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::MintingWithInputs),
     );
 }
@@ -125,7 +150,7 @@ fn mint_with_no_outputs_fails() {
     let output_data = vec![];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::MintingNothing),
     );
 }
@@ -136,7 +161,406 @@ fn mint_wrong_output_type_fails() {
     let output_data = vec![Coin::<0>(10).into(), Bogus.into()];
 
     assert_eq!(
-        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data),
+        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data, &[]),
         Err(ConstraintCheckerError::BadlyTyped),
     );
 }
+
+/// A mint policy that requires a mint authority, like a production chain would.
+struct PermissionedMinting;
+
+impl MintPolicy<0> for PermissionedMinting {
+    const FREE_MINTING: bool = false;
+}
+
+type PermissionedChecker = MoneyWithPolicy<0, PermissionedMinting>;
+
+const PERMISSIONED_MINT: PermissionedChecker =
+    MoneyWithPolicy(MoneyConstraintChecker::Mint, PhantomData);
+
+#[test]
+fn money_with_policy_is_encoded_like_its_checker() {
+    assert_eq!(
+        PERMISSIONED_MINT.encode(),
+        MoneyConstraintChecker::<0>::Mint.encode()
+    );
+}
+
+#[test]
+fn permissioned_mint_with_authority_works() {
+    let input_data = vec![MintAuthority::<0>.into()];
+    let output_data = vec![MintAuthority::<0>.into(), Coin::<0>(10).into()];
+
+    assert_eq!(
+        PERMISSIONED_MINT.check_payloads(&input_data, &output_data, &[]),
+        Ok(0),
+    );
+}
+
+#[test]
+fn permissioned_mint_may_retire_authority() {
+    let input_data = vec![MintAuthority::<0>.into()];
+    let output_data = vec![Coin::<0>(10).into()];
+
+    assert_eq!(
+        PERMISSIONED_MINT.check_payloads(&input_data, &output_data, &[]),
+        Ok(0),
+    );
+}
+
+#[test]
+fn permissioned_mint_without_authority_fails() {
+    let input_data = vec![];
+    let output_data = vec![Coin::<0>(10).into()];
+
+    assert_eq!(
+        PERMISSIONED_MINT.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::MissingMintAuthority),
+    );
+}
+
+#[test]
+fn permissioned_mint_with_authority_of_other_coin_fails() {
+    let input_data = vec![MintAuthority::<1>.into()];
+    let output_data = vec![Coin::<0>(10).into()];
+
+    assert_eq!(
+        PERMISSIONED_MINT.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::MintingWithInputs),
+    );
+}
+
+#[test]
+fn mint_with_authority_and_other_inputs_fails() {
+    let input_data = vec![MintAuthority::<0>.into(), Coin::<0>(5).into()];
+    let output_data = vec![MintAuthority::<0>.into(), Coin::<0>(10).into()];
+
+    assert_eq!(
+        PERMISSIONED_MINT.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::MintingWithInputs),
+    );
+}
+
+#[test]
+fn mint_duplicating_authority_fails() {
+    let input_data = vec![MintAuthority::<0>.into()];
+    let output_data = vec![
+        MintAuthority::<0>.into(),
+        MintAuthority::<0>.into(),
+        Coin::<0>(10).into(),
+    ];
+
+    assert_eq!(
+        PERMISSIONED_MINT.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::TooManyMintAuthorities),
+    );
+}
+
+#[test]
+fn free_mint_creating_authority_fails() {
+    let input_data = vec![];
+    let output_data = vec![MintAuthority::<0>.into(), Coin::<0>(10).into()];
+
+    assert_eq!(
+        MoneyConstraintChecker::<0>::Mint.check(&input_data, &[], &output_data, &[]),
+        Err(ConstraintCheckerError::TooManyMintAuthorities),
+    );
+}
+
+#[test]
+fn mint_passing_on_authority_only_fails() {
+    let input_data = vec![MintAuthority::<0>.into()];
+    let output_data = vec![MintAuthority::<0>.into()];
+
+    assert_eq!(
+        PERMISSIONED_MINT.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::MintingNothing),
+    );
+}
+
+/// A mint policy that tracks supply, like an auditable chain would.
+struct TrackedMinting;
+
+impl MintPolicy<0> for TrackedMinting {
+    const FREE_MINTING: bool = true;
+    const TRACK_SUPPLY: bool = true;
+}
+
+type TrackedChecker = MoneyWithPolicy<0, TrackedMinting>;
+
+const TRACKED_MINT: TrackedChecker = MoneyWithPolicy(MoneyConstraintChecker::Mint, PhantomData);
+const TRACKED_BURN: TrackedChecker = MoneyWithPolicy(MoneyConstraintChecker::Burn, PhantomData);
+const TRACKED_SPEND: TrackedChecker = MoneyWithPolicy(MoneyConstraintChecker::Spend, PhantomData);
+
+fn supply(circulating: u128, burned: u128) -> Supply<0> {
+    Supply(SupplyInfo {
+        circulating,
+        burned,
+    })
+}
+
+#[test]
+fn tracked_mint_updates_supply() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let evicted_data = vec![supply(10, 2).into()];
+        let output_data = vec![Coin::<0>(5).into(), supply(15, 2).into()];
+
+        assert_eq!(
+            TRACKED_MINT.check_payloads(&[], &output_data, &evicted_data),
+            Ok(0),
+        );
+        assert_eq!(SupplyStorage::get(0), Some(supply(15, 2).0));
+    });
+}
+
+#[test]
+fn tracked_mint_without_previous_supply_fails() {
+    let output_data = vec![Coin::<0>(5).into(), supply(5, 0).into()];
+
+    assert_eq!(
+        TRACKED_MINT.check_payloads(&[], &output_data, &[]),
+        Err(ConstraintCheckerError::MissingSupply),
+    );
+}
+
+#[test]
+fn tracked_mint_without_updated_supply_fails() {
+    let evicted_data = vec![supply(10, 0).into()];
+    let output_data = vec![Coin::<0>(5).into()];
+
+    assert_eq!(
+        TRACKED_MINT.check_payloads(&[], &output_data, &evicted_data),
+        Err(ConstraintCheckerError::MissingSupply),
+    );
+}
+
+#[test]
+fn tracked_mint_with_wrong_supply_fails() {
+    let evicted_data = vec![supply(10, 0).into()];
+    let output_data = vec![Coin::<0>(5).into(), supply(10, 0).into()];
+
+    assert_eq!(
+        TRACKED_MINT.check_payloads(&[], &output_data, &evicted_data),
+        Err(ConstraintCheckerError::SupplyMismatch),
+    );
+}
+
+#[test]
+fn tracked_burn_updates_supply() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let input_data = vec![Coin::<0>(5).into(), Coin::<0>(3).into()];
+        let evicted_data = vec![supply(10, 2).into()];
+        let output_data = vec![supply(2, 10).into()];
+
+        assert_eq!(
+            TRACKED_BURN.check_payloads(&input_data, &output_data, &evicted_data),
+            Ok(8),
+        );
+        assert_eq!(SupplyStorage::get(0), Some(supply(2, 10).0));
+    });
+}
+
+#[test]
+fn tracked_burn_with_wrong_supply_fails() {
+    let input_data = vec![Coin::<0>(5).into()];
+    let evicted_data = vec![supply(10, 0).into()];
+    let output_data = vec![supply(10, 5).into()];
+
+    assert_eq!(
+        TRACKED_BURN.check_payloads(&input_data, &output_data, &evicted_data),
+        Err(ConstraintCheckerError::SupplyMismatch),
+    );
+}
+
+#[test]
+fn tracked_mint_creating_unspendable_supply_works() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let evicted: Vec<Output<TestVerifier>> = vec![(supply(10, 0), Unspendable).into()];
+        let outputs: Vec<Output<TestVerifier>> = vec![
+            (Coin::<0>(5), UpForGrabs).into(),
+            (supply(15, 0), Unspendable).into(),
+        ];
+
+        assert_eq!(
+            ConstraintChecker::<TestVerifier>::check(&TRACKED_MINT, &[], &[], &outputs, &evicted),
+            Ok(0),
+        );
+    });
+}
+
+#[test]
+fn tracked_mint_creating_spendable_supply_fails() {
+    let evicted: Vec<Output<TestVerifier>> = vec![(supply(10, 0), Unspendable).into()];
+    let outputs: Vec<Output<TestVerifier>> = vec![
+        (Coin::<0>(5), UpForGrabs).into(),
+        (supply(15, 0), UpForGrabs).into(),
+    ];
+
+    assert_eq!(
+        ConstraintChecker::<TestVerifier>::check(&TRACKED_MINT, &[], &[], &outputs, &evicted),
+        Err(ConstraintCheckerError::NotUnspendable),
+    );
+}
+
+#[test]
+fn tracked_spend_without_burning_works() {
+    let input_data = vec![Coin::<0>(5).into(), Coin::<0>(7).into()];
+    let output_data = vec![Coin::<0>(12).into()];
+
+    assert_eq!(
+        TRACKED_SPEND.check_payloads(&input_data, &output_data, &[]),
+        Ok(0),
+    );
+}
+
+#[test]
+fn tracked_spend_burning_value_fails() {
+    let input_data = vec![Coin::<0>(5).into(), Coin::<0>(7).into()];
+    let output_data = vec![Coin::<0>(11).into()];
+
+    assert_eq!(
+        TRACKED_SPEND.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::InputsExceedOutputs),
+    );
+}
+
+#[test]
+fn untracked_burn_works() {
+    let input_data = vec![Coin::<0>(5).into(), Coin::<0>(7).into()];
+
+    assert_eq!(
+        MoneyConstraintChecker::<0>::Burn.check(&input_data, &[], &[], &[]),
+        Ok(12),
+    );
+}
+
+#[test]
+fn untracked_burn_with_outputs_fails() {
+    let input_data = vec![Coin::<0>(5).into()];
+    let output_data = vec![Coin::<0>(1).into()];
+
+    assert_eq!(
+        MoneyConstraintChecker::<0>::Burn.check(&input_data, &[], &output_data, &[]),
+        Err(ConstraintCheckerError::BurningWithOutputs),
+    );
+}
+
+#[test]
+fn burn_no_inputs_fails() {
+    assert_eq!(
+        MoneyConstraintChecker::<0>::Burn.check(&[], &[], &[], &[]),
+        Err(ConstraintCheckerError::SpendingNothing),
+    );
+}
+
+#[test]
+fn only_tracked_supply_allows_evictions() {
+    assert!(!MoneyConstraintChecker::<0>::ALLOWS_EVICTIONS);
+    assert!(ConstraintChecker::<TestVerifier>::allows_evictions(
+        &TRACKED_MINT
+    ));
+}
+
+/// A mint policy that lets spends deposit the value they burn as fees.
+struct FeeCollecting;
+
+impl MintPolicy<0> for FeeCollecting {
+    const FREE_MINTING: bool = true;
+    const COLLECT_FEES: bool = true;
+}
+
+const FEE_COLLECTING_SPEND: MoneyWithPolicy<0, FeeCollecting> =
+    MoneyWithPolicy(MoneyConstraintChecker::Spend, PhantomData);
+
+#[test]
+fn spend_depositing_spendable_fee_fails() {
+    let inputs: Vec<Output<TestVerifier>> = vec![(Coin::<0>(12), UpForGrabs).into()];
+    let outputs: Vec<Output<TestVerifier>> = vec![
+        (Coin::<0>(10), UpForGrabs).into(),
+        (Fee::<0>(2), UpForGrabs).into(),
+    ];
+
+    assert_eq!(
+        ConstraintChecker::<TestVerifier>::check(
+            &FEE_COLLECTING_SPEND,
+            &inputs,
+            &[],
+            &outputs,
+            &[]
+        ),
+        Err(ConstraintCheckerError::NotUnspendable),
+    );
+}
+
+#[test]
+fn spend_depositing_burned_value_as_fee_works() {
+    let input_data = vec![Coin::<0>(5).into(), Coin::<0>(7).into()]; // total 12
+    let output_data = vec![Coin::<0>(10).into(), Fee::<0>(2).into()];
+
+    assert_eq!(
+        FEE_COLLECTING_SPEND.check_payloads(&input_data, &output_data, &[]),
+        Ok(2),
+    );
+}
+
+#[test]
+fn spend_depositing_part_of_burned_value_as_fee_works() {
+    let input_data = vec![Coin::<0>(12).into()];
+    let output_data = vec![Coin::<0>(7).into(), Fee::<0>(2).into(), Fee::<0>(1).into()];
+
+    assert_eq!(
+        FEE_COLLECTING_SPEND.check_payloads(&input_data, &output_data, &[]),
+        Ok(5),
+    );
+}
+
+#[test]
+fn spend_depositing_more_than_burned_value_fails() {
+    let input_data = vec![Coin::<0>(12).into()];
+    let output_data = vec![Coin::<0>(10).into(), Fee::<0>(3).into()];
+
+    assert_eq!(
+        FEE_COLLECTING_SPEND.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::FeesExceedBurnedValue),
+    );
+}
+
+#[test]
+fn spend_depositing_zero_fee_fails() {
+    let input_data = vec![Coin::<0>(12).into()];
+    let output_data = vec![Coin::<0>(10).into(), Fee::<0>(0).into()];
+
+    assert_eq!(
+        FEE_COLLECTING_SPEND.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::ZeroValueCoin),
+    );
+}
+
+#[test]
+fn spend_depositing_fee_without_fee_collection_fails() {
+    let input_data = vec![Coin::<0>(12).into()];
+    let output_data = vec![Coin::<0>(10).into(), Fee::<0>(2).into()];
+
+    assert_eq!(
+        MoneyConstraintChecker::<0>::Spend.check(&input_data, &[], &output_data, &[]),
+        Err(ConstraintCheckerError::BadlyTyped),
+    );
+}
+
+#[test]
+fn spend_depositing_fee_of_other_coin_fails() {
+    let input_data = vec![Coin::<0>(12).into()];
+    let output_data = vec![Coin::<0>(10).into(), Fee::<1>(2).into()];
+
+    assert_eq!(
+        FEE_COLLECTING_SPEND.check_payloads(&input_data, &output_data, &[]),
+        Err(ConstraintCheckerError::BadlyTyped),
+    );
+}
+
+#[test]
+fn coins_have_distinct_asset_ids() {
+    assert_eq!(Coin::<0>(5).asset_id(), H256::zero());
+    assert_eq!(Coin::<1>(5).asset_id(), H256::from_low_u64_be(1));
+    assert_ne!(Coin::<0>(5).asset_id(), Coin::<1>(5).asset_id());
+}
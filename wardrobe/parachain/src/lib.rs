@@ -33,6 +33,7 @@ use sp_std::{vec, vec::Vec};
 use tuxedo_parachain_core::ParachainInherentDataUtxo;
 use tuxedo_parachain_core::{
     tuxedo_core::{
+        dynamic_typing::{utxo_type_info, UtxoTypeInfo},
         ensure,
         inherents::{TuxedoInherent, TuxedoInherentAdapter},
         support_macros::{CloneNoBound, DebugNoBound, DefaultNoBound},
@@ -69,7 +70,7 @@ pub trait ParachainPieceConfig {
 }
 
 /// Reasons that setting or cleaning up the parachain info may go wrong.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Encode, Decode, Eq, PartialEq)]
 pub enum ParachainError {
     /// UTXO data has an unexpected type
     BadlyTyped,
@@ -117,6 +118,7 @@ impl<T: ParachainPieceConfig + 'static, V: Verifier + From<UpForGrabs>> Constrai
         input_data: &[Output<V>],
         _peek_data: &[Output<V>],
         output_data: &[Output<V>],
+        _evicted_data: &[Output<V>],
     ) -> Result<TransactionPriority, Self::Error> {
         log::debug!(
             target: LOG_TARGET,
@@ -167,6 +169,10 @@ impl<T: ParachainPieceConfig + 'static, V: Verifier + From<UpForGrabs>> Constrai
     fn is_inherent(&self) -> bool {
         true
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<ParachainInherentDataUtxo>()
+    }
 }
 
 impl<V: Verifier + From<UpForGrabs>, T: ParachainPieceConfig + 'static> TuxedoInherent<V, Self>
@@ -214,6 +220,8 @@ impl<V: Verifier + From<UpForGrabs>, T: ParachainPieceConfig + 'static> TuxedoIn
             peeks: Vec::new(),
             outputs: vec![new_output],
             checker: Self::default(),
+            evictions: Vec::new(),
+            valid_until: None,
         };
 
         log::debug!(
@@ -250,6 +258,8 @@ impl<V: Verifier + From<UpForGrabs>, T: ParachainPieceConfig + 'static> TuxedoIn
                 verifier: UpForGrabs.into(),
             }],
             checker: Self::default(),
+            evictions: Vec::new(),
+            valid_until: None,
         }]
     }
 }
@@ -24,9 +24,9 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_core::H256;
 use sp_runtime::transaction_validity::TransactionPriority;
-use sp_std::fmt::Debug;
+use sp_std::{fmt::Debug, vec::Vec};
 use tuxedo_core::{
-    dynamic_typing::{DynamicallyTypedData, UtxoData},
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
     ensure,
     support_macros::{CloneNoBound, DebugNoBound},
     SimpleConstraintChecker,
@@ -36,7 +36,8 @@ use tuxedo_core::{
 mod tests;
 
 // Notice this type doesn't have to be public. Cool.
-#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, UtxoData)]
+#[utxo_data(type_id = *b"poe_")]
 struct ClaimData {
     /// The hash of the data whose existence is being proven.
     claim: H256,
@@ -44,10 +45,6 @@ struct ClaimData {
     effective_height: u32, //TODO get the generic block height type
 }
 
-impl UtxoData for ClaimData {
-    const TYPE_ID: [u8; 4] = *b"poe_";
-}
-
 /// Errors that can occur when checking PoE Transactions
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
 pub enum ConstraintCheckerError {
@@ -65,6 +62,16 @@ pub enum ConstraintCheckerError {
     /// The effective height of this claim is in the past,
     /// So the claim cannot be created.
     EffectiveHeightInPast,
+    /// Wrong number of peeks were provided to the constraint checker.
+    WrongNumberPeeks,
+    /// A peek data has the wrong type.
+    BadlyTypedPeek,
+    /// An evicted data has the wrong type.
+    BadlyTypedEviction,
+    /// An evicted claim is for a different hash than the winning claim, so there is no dispute.
+    DisputedClaimMismatch,
+    /// An evicted claim is not strictly newer than the winning claim, so it would have won the dispute.
+    DisputedClaimNotNewer,
 }
 
 /// Configuration items for the Proof of Existence piece when it is
@@ -93,6 +100,7 @@ impl<T: PoeConfig> SimpleConstraintChecker for PoeClaim<T> {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
         // Make sure there are no inputs
         ensure!(
@@ -120,6 +128,10 @@ impl<T: PoeConfig> SimpleConstraintChecker for PoeClaim<T> {
 
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<ClaimData>()
+    }
 }
 
 /// A constraint checker to revoke claims.
@@ -136,6 +148,7 @@ impl SimpleConstraintChecker for PoeRevoke {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
         // Make sure there are no outputs
         ensure!(
@@ -152,62 +165,72 @@ impl SimpleConstraintChecker for PoeRevoke {
 
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<ClaimData>()
+    }
 }
 
 /// A constraint checker that resolves claim disputes by keeping whichever claim came first.
 ///
-/// TODO this will work much more elegantly once peek is implemented. We only need to peek at the
-/// older winning claim because it will remain in state afterwards.
-///
-/// TODO what shall we do about the verifier? Each claimer may have given their claim a verifier
-/// such that their own private signature. Perhaps there should be a way for a constraint checker to override
-/// the verifier logic? This is a concrete case where the constraint checker verifier separation is not ideal.
-/// Another, weaker example, is when trying o implement something like sudo. Where we want a signature,
-/// but we want to authorized signer to come from the a different part of state.
+/// The winning claim is peeked at because it remains in state afterwards. The losing claims
+/// are evicted. Their verifiers are not consulted, because the claimers who lose the dispute
+/// would never agree to give up their claims.
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, TypeInfo)]
 pub struct PoeDispute;
 
 impl SimpleConstraintChecker for PoeDispute {
     type Error = ConstraintCheckerError;
 
+    // The losing claims are evicted.
+    const ALLOWS_EVICTIONS: bool = true;
+
     fn check(
         &self,
-        _input_data: &[DynamicallyTypedData],
-        _peeks: &[DynamicallyTypedData],
-        _output_data: &[DynamicallyTypedData],
+        input_data: &[DynamicallyTypedData],
+        peek_data: &[DynamicallyTypedData],
+        output_data: &[DynamicallyTypedData],
+        evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
-        todo!("implement this once we have at least peeks and maybe evictions")
-
-        // Make sure there is at least one input (once peek is ready, it will become a peek)
-        // This first input (or only peek) is the claim that will be retained.
-
-        // Make sure that all other inputs, claim the same hash as the winner.
+        // Make sure there are no inputs or outputs. A dispute only removes the losing claims.
+        ensure!(
+            input_data.is_empty(),
+            ConstraintCheckerError::WrongNumberInputs
+        );
+        ensure!(
+            output_data.is_empty(),
+            ConstraintCheckerError::WrongNumberOutputs
+        );
 
-        // Make sure that all other claims have block heights strictly greater than the winner.
+        // Make sure there is exactly one peek. This is the claim that will be retained.
+        ensure!(
+            peek_data.len() == 1,
+            ConstraintCheckerError::WrongNumberPeeks
+        );
+        let winner = peek_data[0]
+            .extract::<ClaimData>()
+            .map_err(|_| ConstraintCheckerError::BadlyTypedPeek)?;
+
+        // Make sure that all evicted claims claim the same hash as the winner, and
+        // have effective heights strictly greater than the winner.
+        for untyped_loser in evicted_data {
+            let loser = untyped_loser
+                .extract::<ClaimData>()
+                .map_err(|_| ConstraintCheckerError::BadlyTypedEviction)?;
+            ensure!(
+                loser.claim == winner.claim,
+                ConstraintCheckerError::DisputedClaimMismatch
+            );
+            ensure!(
+                loser.effective_height > winner.effective_height,
+                ConstraintCheckerError::DisputedClaimNotNewer
+            );
+        }
 
-        //TODO what to do about the verifiers on those losing claims.
+        Ok(0)
     }
-}
 
-#[allow(dead_code)]
-mod brainstorm {
-    /// One workable solution to the problem above is modifying the core transaction structure to something like this
-    struct Transaction {
-        /// A classic input that is consumed from the utxo set. Its verifier must be satisfied for the tx to be valid
-        redemptions: Vec<InputRef>,
-        /// Similar to a redemption, this is an input that is consumed from the utxo set, but its verifier need not be satisfied
-        /// In the Poe case above, the losing claims that came later would be evictions.
-        evictions: Vec<InputRef>,
-        /// Similar to an input, but it is not consumed. This is a way to read pre-existing state without removing it from the utxo set
-        /// this also indicates when transaction are not competing for state despite reading the same state, and thus commute.
-        /// TBD whether it makes sense to have a verifier check. My gut instinct is no verifier check, but it needs more careful thought.
-        peeks: Vec<InputRef>,
-        /// Newly created pieces of state to be added to the utxo set.
-        outputs: Vec<Output>,
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<ClaimData>()
     }
-
-    // Aliases so my sketch above compiles
-    type InputRef = ();
-    type Output = ();
-    use sp_std::vec::Vec;
 }
@@ -1 +1,75 @@
-//! Tests for the Proof of Existence Piece are TODO
+//! Tests for the Proof of Existence Piece.
+//! Only the dispute flow is covered so far. Tests for claiming and revoking are TODO.
+
+use super::*;
+use tuxedo_core::dynamic_typing::testing::Bogus;
+
+fn claim(byte: u8, effective_height: u32) -> DynamicallyTypedData {
+    ClaimData {
+        claim: H256::repeat_byte(byte),
+        effective_height,
+    }
+    .into()
+}
+
+#[test]
+fn dispute_happy_path() {
+    let winner = vec![claim(1, 5)];
+    let losers = vec![claim(1, 6), claim(1, 10)];
+
+    assert_eq!(PoeDispute.check(&[], &winner, &[], &losers), Ok(0));
+}
+
+#[test]
+fn dispute_requires_winner_peek() {
+    let losers = vec![claim(1, 6)];
+
+    assert_eq!(
+        PoeDispute.check(&[], &[], &[], &losers),
+        Err(ConstraintCheckerError::WrongNumberPeeks)
+    );
+}
+
+#[test]
+fn dispute_cannot_consume_inputs() {
+    let winner = vec![claim(1, 5)];
+    let loser = vec![claim(1, 6)];
+
+    assert_eq!(
+        PoeDispute.check(&loser, &winner, &[], &[]),
+        Err(ConstraintCheckerError::WrongNumberInputs)
+    );
+}
+
+#[test]
+fn dispute_loser_claims_different_hash() {
+    let winner = vec![claim(1, 5)];
+    let losers = vec![claim(2, 6)];
+
+    assert_eq!(
+        PoeDispute.check(&[], &winner, &[], &losers),
+        Err(ConstraintCheckerError::DisputedClaimMismatch)
+    );
+}
+
+#[test]
+fn dispute_loser_is_not_newer() {
+    let winner = vec![claim(1, 5)];
+    let losers = vec![claim(1, 5)];
+
+    assert_eq!(
+        PoeDispute.check(&[], &winner, &[], &losers),
+        Err(ConstraintCheckerError::DisputedClaimNotNewer)
+    );
+}
+
+#[test]
+fn dispute_loser_is_wrong_type() {
+    let winner = vec![claim(1, 5)];
+    let losers = vec![Bogus.into()];
+
+    assert_eq!(
+        PoeDispute.check(&[], &winner, &[], &losers),
+        Err(ConstraintCheckerError::BadlyTypedEviction)
+    );
+}
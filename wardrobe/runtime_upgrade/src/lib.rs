@@ -20,7 +20,7 @@ use sp_runtime::transaction_validity::TransactionPriority;
 use sp_std::vec::Vec;
 use sp_storage::well_known_keys::CODE;
 use tuxedo_core::{
-    dynamic_typing::{DynamicallyTypedData, UtxoData},
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
     ensure, SimpleConstraintChecker,
 };
 
@@ -28,17 +28,14 @@ use tuxedo_core::{
 mod tests;
 
 /// A reference to a runtime wasm blob. It is just a hash.
-#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone, UtxoData)]
+#[utxo_data(type_id = *b"upgd")]
 struct RuntimeRef {
     hash: [u8; 32],
 }
 
-impl UtxoData for RuntimeRef {
-    const TYPE_ID: [u8; 4] = *b"upgd";
-}
-
 /// Reasons that the RuntimeUpgrade constraint checker may fail
-#[derive(Debug)]
+#[derive(Debug, Encode, Decode)]
 pub enum ConstraintCheckerError {
     // Again we're duplicating these common errors. Probably going to want a
     // better way to handle these.
@@ -79,6 +76,7 @@ impl SimpleConstraintChecker for RuntimeUpgrade {
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
         // Make sure there is a single input that matches the hash of the previous runtime logic
         ensure!(
@@ -116,4 +114,8 @@ impl SimpleConstraintChecker for RuntimeUpgrade {
         //TODO Figure out a better priority
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<RuntimeRef>()
+    }
 }
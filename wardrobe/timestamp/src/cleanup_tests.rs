@@ -4,7 +4,7 @@
 use super::{
     CleanUpTimestamp, SimpleConstraintChecker, Timestamp, TimestampConfig, TimestampError,
 };
-use tuxedo_core::dynamic_typing::testing::Bogus;
+use tuxedo_core::{dynamic_typing::testing::Bogus, verifier::MockBestTimestampStorage};
 use TimestampError::*;
 
 /// The mock config always says the block number is one million.
@@ -14,6 +14,8 @@ impl TimestampConfig for AlwaysBlockMillion {
     fn block_height() -> u32 {
         1_000_000
     }
+
+    type SetBestTimestampStorage = MockBestTimestampStorage;
 }
 
 #[test]
@@ -24,11 +26,11 @@ fn cleanup_timestamp_happy_path() {
         2 * AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
     );
 
-    let inp = vec![old.into()];
+    let evicted = vec![old.into()];
     let peek = vec![newer.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &evicted),
         Ok(0),
     );
 }
@@ -36,44 +38,44 @@ fn cleanup_timestamp_happy_path() {
 #[test]
 fn cleanup_timestamp_no_peek() {
     let old = Timestamp::new(1, 1);
-    let inp = vec![old.into()];
+    let evicted = vec![old.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &[], &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &[], &[], &evicted),
         Err(CleanupRequiresOneReference)
     );
 }
 
 #[test]
-fn cleanup_timestamp_input_newer_than_reference() {
+fn cleanup_timestamp_evicted_newer_than_reference() {
     let old = Timestamp::new(1, 1);
     let newer = Timestamp::new(
         2 * AlwaysBlockMillion::MIN_TIME_BEFORE_CLEANUP,
         2 * AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
     );
 
-    let inp = vec![newer.into()];
+    let evicted = vec![newer.into()];
     let peek = vec![old.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &evicted),
         Err(DontBeSoHasty)
     );
 }
 
 #[test]
-fn cleanup_timestamp_input_not_yet_ripe_for_cleaning() {
+fn cleanup_timestamp_evicted_not_yet_ripe_for_cleaning() {
     let old = Timestamp::new(1, 1);
     let newer = Timestamp::new(
         AlwaysBlockMillion::MIN_TIME_BEFORE_CLEANUP / 2,
         AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
     );
 
-    let inp = vec![old.into()];
+    let evicted = vec![old.into()];
     let peek = vec![newer.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &evicted),
         Err(DontBeSoHasty)
     );
 }
@@ -87,19 +89,19 @@ fn cleanup_timestamp_multiple_happy_path() {
         2 * AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
     );
 
-    let inp = vec![old1.into(), old2.into()];
+    let evicted = vec![old1.into(), old2.into()];
     let peek = vec![newer.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &evicted),
         Ok(0),
     );
 }
 
 #[test]
-fn cleanup_timestamp_missing_input() {
+fn cleanup_timestamp_nothing_evicted() {
     // The logic allows cleaning up "multiple", or more precisely, zero or more,
-    // stale inputs. This test ensures that cleaning up zero is considered valid.
+    // stale timestamps. This test ensures that cleaning up zero is considered valid.
     // Of course there is little reason to do this in real life; it only wastes resources.
 
     let newer = Timestamp::new(
@@ -110,7 +112,7 @@ fn cleanup_timestamp_missing_input() {
     let peek = vec![newer.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &[]),
         Ok(0),
     );
 }
@@ -127,28 +129,28 @@ fn cleanup_timestamp_multiple_first_valid_second_invalid() {
         2 * AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
     );
 
-    let inp = vec![old.into(), supposedly_old.into()];
+    let evicted = vec![old.into(), supposedly_old.into()];
     let peek = vec![newer.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &evicted),
         Err(DontBeSoHasty)
     );
 }
 
 #[test]
-fn cleanup_timestamp_input_is_wrong_type() {
+fn cleanup_timestamp_evicted_is_wrong_type() {
     let old = Bogus;
     let newer = Timestamp::new(
         2 * AlwaysBlockMillion::MIN_TIME_BEFORE_CLEANUP,
         2 * AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
     );
 
-    let inp = vec![old.into()];
+    let evicted = vec![old.into()];
     let peek = vec![newer.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &evicted),
         Err(BadlyTyped)
     );
 }
@@ -157,11 +159,11 @@ fn cleanup_timestamp_input_is_wrong_type() {
 fn cleanup_timestamp_reference_is_wrong_type() {
     let old = Timestamp::new(1, 1);
 
-    let inp = vec![old.into()];
+    let evicted = vec![old.into()];
     let peek = vec![Bogus.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[]),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &[], &evicted),
         Err(BadlyTyped)
     );
 }
@@ -174,12 +176,29 @@ fn cleanup_timestamp_cannot_create_state() {
         2 * AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
     );
 
-    let inp = vec![old.into()];
+    let evicted = vec![old.into()];
     let peek = vec![newer.into()];
     let out = vec![Bogus.into()];
 
     assert_eq!(
-        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &out),
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&[], &peek, &out, &evicted),
         Err(CleanupCannotCreateState)
     );
 }
+
+#[test]
+fn cleanup_timestamp_cannot_consume_inputs() {
+    let old = Timestamp::new(1, 1);
+    let newer = Timestamp::new(
+        2 * AlwaysBlockMillion::MIN_TIME_BEFORE_CLEANUP,
+        2 * AlwaysBlockMillion::MIN_BLOCKS_BEFORE_CLEANUP,
+    );
+
+    let inp = vec![old.into()];
+    let peek = vec![newer.into()];
+
+    assert_eq!(
+        CleanUpTimestamp::<AlwaysBlockMillion>::default().check(&inp, &peek, &[], &[]),
+        Err(CleanupCannotConsumeInputs)
+    );
+}
@@ -6,8 +6,8 @@
 //! In each block, the block author must include a single `SetTimestamp` transaction that peeks at the
 //! Timestamp UTXO that was created in the previous block, and creates a new one with an updated timestamp.
 //!
-//! This piece currently features a prominent hack which will need to be cleaned up in due course.
-//! It abuses the UpForGrabs verifier. This should be replaced with an Unspendable verifier and an eviction workflow.
+//! Timestamp UTXOs are protected by the Unspendable verifier so they can never be consumed as regular inputs.
+//! Once they are old enough, anyone may remove them from state by evicting them in a `CleanUpTimestamp` transaction.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -22,12 +22,12 @@ use sp_runtime::transaction_validity::TransactionPriority;
 use sp_std::{vec, vec::Vec};
 use sp_timestamp::InherentError::TooFarInFuture;
 use tuxedo_core::{
-    dynamic_typing::{DynamicallyTypedData, UtxoData},
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
     ensure,
     inherents::{TuxedoInherent, TuxedoInherentAdapter},
     support_macros::{CloneNoBound, DebugNoBound, DefaultNoBound},
     types::{Output, OutputRef, Transaction},
-    verifier::UpForGrabs,
+    verifier::{SetBestTimestampStorage, Unspendable},
     ConstraintChecker, SimpleConstraintChecker, Verifier,
 };
 
@@ -41,7 +41,8 @@ const LOG_TARGET: &str = "timestamp-piece";
 
 /// A timestamp, since the unix epoch, noted at some point in the history of the chain.
 /// It also records the block height in which it was included.
-#[derive(Debug, Encode, Decode, PartialEq, Eq, Clone, Copy, Default, PartialOrd, Ord)]
+#[derive(Debug, Encode, Decode, PartialEq, Eq, Clone, Copy, Default, PartialOrd, Ord, UtxoData)]
+#[utxo_data(type_id = *b"time")]
 pub struct Timestamp {
     /// The time, in milliseconds, since the unix epoch.
     pub time: u64,
@@ -49,10 +50,6 @@ pub struct Timestamp {
     pub block: u32,
 }
 
-impl UtxoData for Timestamp {
-    const TYPE_ID: [u8; 4] = *b"time";
-}
-
 impl Timestamp {
     pub fn new(time: u64, block: u32) -> Self {
         Self { time, block }
@@ -60,12 +57,17 @@ impl Timestamp {
 }
 
 /// Options to configure the timestamp piece in your runtime.
-/// Currently we only need access to a block number.
+/// Currently we need access to a block number and a place to note the best timestamp.
 pub trait TimestampConfig {
     /// A means of getting the current block height.
     /// Probably this will be the Tuxedo Executive
     fn block_height() -> u32;
 
+    /// A means of noting each new best timestamp so that it is ambiently available to the rest
+    /// of the runtime, for example to the `TimestampLock` verifier.
+    /// Probably this will be Tuxedo core's `BestTimestampStorage`.
+    type SetBestTimestampStorage: SetBestTimestampStorage;
+
     /// The minimum amount of time by which the timestamp may be updated.
     ///
     /// The default is 2 seconds which should be slightly lower than most chains' block times.
@@ -92,7 +94,7 @@ pub trait TimestampConfig {
 }
 
 /// Reasons that setting or cleaning up the timestamp may go wrong.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Encode, Decode, Eq, PartialEq)]
 pub enum TimestampError {
     /// UTXO data has an unexpected type
     BadlyTyped,
@@ -119,6 +121,8 @@ pub enum TimestampError {
     /// You may not clean up old timestamps until they are at least the CLEANUP_AGE older than another
     /// noted timestamp on-chain.
     DontBeSoHasty,
+    /// When cleaning up old timestamps, they must be evicted rather than consumed as inputs.
+    CleanupCannotConsumeInputs,
 }
 
 /// A constraint checker for the simple act of setting a new best timetamp.
@@ -147,7 +151,7 @@ pub enum TimestampError {
 #[scale_info(skip_type_params(T))]
 pub struct SetTimestamp<T>(PhantomData<T>);
 
-impl<T: TimestampConfig + 'static, V: Verifier + From<UpForGrabs>> ConstraintChecker<V>
+impl<T: TimestampConfig + 'static, V: Verifier + From<Unspendable>> ConstraintChecker<V>
     for SetTimestamp<T>
 {
     type Error = TimestampError;
@@ -158,6 +162,7 @@ impl<T: TimestampConfig + 'static, V: Verifier + From<UpForGrabs>> ConstraintChe
         input_data: &[tuxedo_core::types::Output<V>],
         peek_data: &[tuxedo_core::types::Output<V>],
         output_data: &[tuxedo_core::types::Output<V>],
+        _evicted_data: &[tuxedo_core::types::Output<V>],
     ) -> Result<TransactionPriority, Self::Error> {
         log::debug!(
             target: LOG_TARGET,
@@ -207,15 +212,22 @@ impl<T: TimestampConfig + 'static, V: Verifier + From<UpForGrabs>> ConstraintChe
             Self::Error::PreviousTimestampWrongHeight,
         );
 
+        // SIDE EFFECT: Note the new best timestamp so it is available to the rest of the runtime
+        T::SetBestTimestampStorage::set(new_timestamp.time);
+
         Ok(0)
     }
 
     fn is_inherent(&self) -> bool {
         true
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<Timestamp>()
+    }
 }
 
-impl<V: Verifier + From<UpForGrabs>, T: TimestampConfig + 'static> TuxedoInherent<V, Self>
+impl<V: Verifier + From<Unspendable>, T: TimestampConfig + 'static> TuxedoInherent<V, Self>
     for SetTimestamp<T>
 {
     type Error = sp_timestamp::InherentError;
@@ -249,7 +261,7 @@ impl<V: Verifier + From<UpForGrabs>, T: TimestampConfig + 'static> TuxedoInheren
 
         let new_output = Output {
             payload: new_timestamp.into(),
-            verifier: UpForGrabs.into(),
+            verifier: Unspendable.into(),
         };
 
         Transaction {
@@ -257,6 +269,8 @@ impl<V: Verifier + From<UpForGrabs>, T: TimestampConfig + 'static> TuxedoInheren
             peeks: vec![old_output],
             outputs: vec![new_output],
             checker: Self::default(),
+            evictions: Vec::new(),
+            valid_until: None,
         }
     }
 
@@ -316,9 +330,11 @@ impl<V: Verifier + From<UpForGrabs>, T: TimestampConfig + 'static> TuxedoInheren
             peeks: Vec::new(),
             outputs: vec![Output {
                 payload: Timestamp::new(time, 0).into(),
-                verifier: UpForGrabs.into(),
+                verifier: Unspendable.into(),
             }],
             checker: Self::default(),
+            evictions: Vec::new(),
+            valid_until: None,
         }]
     }
 }
@@ -326,6 +342,7 @@ impl<V: Verifier + From<UpForGrabs>, T: TimestampConfig + 'static> TuxedoInheren
 /// Allows users to voluntarily clean up old timestamps by showing that there
 /// exists another timestamp that is at least the CLEANUP_AGE newer.
 ///
+/// The old timestamps are unspendable, so they are evicted rather than consumed as inputs.
 /// You can clean up multiple timestamps at once, but you only peek at a single
 /// new reference. Although it is useless to do so, it is valid for a transaction
 /// to clean up zero timestamps.
@@ -346,12 +363,22 @@ pub struct CleanUpTimestamp<T>(PhantomData<T>);
 impl<T: TimestampConfig> SimpleConstraintChecker for CleanUpTimestamp<T> {
     type Error = TimestampError;
 
+    // The old timestamps are evicted.
+    const ALLOWS_EVICTIONS: bool = true;
+
     fn check(
         &self,
         input_data: &[DynamicallyTypedData],
         peek_data: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
+        // Make sure there are no inputs. Old timestamps are evicted instead.
+        ensure!(
+            input_data.is_empty(),
+            Self::Error::CleanupCannotConsumeInputs
+        );
+
         // Make sure there at least one peek that is the new reference time.
         // We don't expect any additional peeks typically, but as above, they are harmless.
         ensure!(
@@ -368,10 +395,10 @@ impl<T: TimestampConfig> SimpleConstraintChecker for CleanUpTimestamp<T> {
             Self::Error::CleanupCannotCreateState
         );
 
-        // Make sure each input is old enough to be cleaned up
+        // Make sure each evicted timestamp is old enough to be cleaned up
         // in terms of both time and block height.
-        for input_datum in input_data {
-            let old_timestamp = input_datum
+        for evicted_datum in evicted_data {
+            let old_timestamp = evicted_datum
                 .extract::<Timestamp>()
                 .map_err(|_| Self::Error::BadlyTyped)?;
 
@@ -386,4 +413,8 @@ impl<T: TimestampConfig> SimpleConstraintChecker for CleanUpTimestamp<T> {
         }
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        utxo_type_info::<Timestamp>()
+    }
 }
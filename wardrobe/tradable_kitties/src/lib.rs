@@ -27,7 +27,7 @@ use serde::{Deserialize, Serialize};
 use sp_runtime::transaction_validity::TransactionPriority;
 use sp_std::prelude::*;
 use tuxedo_core::{
-    dynamic_typing::{DynamicallyTypedData, UtxoData},
+    dynamic_typing::{utxo_type_info, DynamicallyTypedData, UtxoData, UtxoTypeInfo},
     ensure, SimpleConstraintChecker,
 };
 
@@ -51,7 +51,9 @@ mod tests;
     Hash,
     Debug,
     TypeInfo,
+    UtxoData,
 )]
+#[utxo_data(type_id = *b"tdkt")]
 pub struct TradableKittyData {
     /// Basic `KittyData` composed from the `kitties` piece.
     pub kitty_basic_data: KittyData,
@@ -67,10 +69,6 @@ impl TryFrom<&DynamicallyTypedData> for TradableKittyData {
     }
 }
 
-impl UtxoData for TradableKittyData {
-    const TYPE_ID: [u8; 4] = *b"tdkt";
-}
-
 /// Reasons that tradable kitty opertaion may go wrong.
 #[derive(
     Serialize,
@@ -239,7 +237,7 @@ fn check_can_buy<const ID: u8>(
     );
 
     // Filtered coins are sent to MoneyConstraintChecker for money validation.
-    Ok(MoneyConstraintChecker::<0>::Spend.check(&input_coin_data, &[], &output_coin_data)?)
+    Ok(MoneyConstraintChecker::<0>::Spend.check(&input_coin_data, &[], &output_coin_data, &[])?)
 }
 
 /// Checks if updates to the prices of tradable kitties are possible or not.
@@ -322,6 +320,7 @@ impl<const ID: u8> SimpleConstraintChecker for TradableKittyConstraintChecker<ID
         input_data: &[DynamicallyTypedData],
         _peeks: &[DynamicallyTypedData],
         output_data: &[DynamicallyTypedData],
+        _evicted_data: &[DynamicallyTypedData],
     ) -> Result<TransactionPriority, Self::Error> {
         match &self {
             Self::ListKittiesForSale => {
@@ -377,4 +376,13 @@ impl<const ID: u8> SimpleConstraintChecker for TradableKittyConstraintChecker<ID
         }
         Ok(0)
     }
+
+    fn utxo_types() -> Vec<UtxoTypeInfo> {
+        [
+            utxo_type_info::<TradableKittyData>(),
+            utxo_type_info::<KittyData>(),
+            utxo_type_info::<Coin<ID>>(),
+        ]
+        .concat()
+    }
 }
@@ -34,6 +34,7 @@ fn list_kitty_for_sale_happy_path_works() {
         &[TradableKittyData::default_kitty().into()],
         &[],
         &[TradableKittyData::default_tradable_kitty().into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -52,6 +53,7 @@ fn list_kitty_for_sale_multiple_input_happy_path_works() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.into(), output2.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -70,6 +72,7 @@ fn list_kitty_for_sale_multiple_out_of_order_input_fails() {
         &[input1.into(), input2.into()],
         &[],
         &[output2.into(), output1.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -89,6 +92,7 @@ fn list_kitty_for_sale_different_num_of_input_output_path_fails() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -100,8 +104,12 @@ fn list_kitty_for_sale_different_num_of_input_output_path_fails() {
 fn list_kitty_for_sale_input_missing_path_fails() {
     let output = TradableKittyData::default_tradable_kitty();
 
-    let result =
-        TradableKittyConstraintChecker::<0>::ListKittiesForSale.check(&[], &[], &[output.into()]);
+    let result = TradableKittyConstraintChecker::<0>::ListKittiesForSale.check(
+        &[],
+        &[],
+        &[output.into()],
+        &[],
+    );
     assert_eq!(
         result,
         Err(TradeableKittyError::NumberOfInputOutputMismatch)
@@ -111,8 +119,12 @@ fn list_kitty_for_sale_input_missing_path_fails() {
 #[test]
 fn list_kitty_for_sale_out_put_missing_path_fails() {
     let input1 = TradableKittyData::default_kitty();
-    let result =
-        TradableKittyConstraintChecker::<0>::ListKittiesForSale.check(&[input1.into()], &[], &[]);
+    let result = TradableKittyConstraintChecker::<0>::ListKittiesForSale.check(
+        &[input1.into()],
+        &[],
+        &[],
+        &[],
+    );
     assert_eq!(
         result,
         Err(TradeableKittyError::NumberOfInputOutputMismatch)
@@ -131,6 +143,7 @@ fn list_kitty_for_sale_with_input_missing_fails() {
         &[],
         &[],
         &[output1.into(), output2.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -147,6 +160,7 @@ fn list_for_sale_with_wrong_output_type_amoung_valid_output_fails() {
         &[input1.into()],
         &[],
         &[Bogus.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -157,6 +171,7 @@ fn list_kitty_for_sale_with_wrong_input_type_fails() {
         &[Bogus.into()],
         &[],
         &[TradableKittyData::default_tradable_kitty().into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -174,6 +189,7 @@ fn list_for_sale_multiple_inputs_with_basic_property_changed_fails() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.clone().into(), output2.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -191,6 +207,7 @@ fn list_for_sale_with_basic_property_changed_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -208,6 +225,7 @@ fn list_for_sale_with_price_zero_fails() {
         &[input1.into()],
         &[],
         &[output1.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::KittyPriceCantBeZero));
 }
@@ -219,6 +237,7 @@ fn delist_kitty_from_sale_happy_path_works() {
         &[TradableKittyData::default_tradable_kitty().into()],
         &[],
         &[TradableKittyData::default_kitty().into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -236,6 +255,7 @@ fn delist_kitty_from_sale_multiple_input_happy_path_works() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.into(), output2.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -253,6 +273,7 @@ fn delist_kitty_from_sale_multiple_input_out_of_order_path_fails() {
         &[input1.into(), input2.into()],
         &[],
         &[output2.into(), output1.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -273,6 +294,7 @@ fn delist_kitty_from_sale_different_num_of_input_output_fails() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -287,6 +309,7 @@ fn delist_kitty_from_sale_input_missing_fails() {
         &[],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -299,6 +322,7 @@ fn delist_kitty_from_sale_out_put_missing_path_fails() {
         &[TradableKittyData::default_tradable_kitty().into()],
         &[],
         &[],
+        &[],
     );
     assert_eq!(
         result,
@@ -320,6 +344,7 @@ fn delist_kitty_from_sale_with_wrong_output_type_ampoung_valid_output_fails() {
         &[input1.into(), input2.into()],
         &[],
         &[output1.into(), Bogus.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -330,6 +355,7 @@ fn delist_from_sale_with_wrong_input_type_fails() {
         &[Bogus.into()],
         &[],
         &[TradableKittyData::default_kitty().into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -347,6 +373,7 @@ fn delist_from_sale_with_basic_property_update_fails() {
         &[input1.clone().into(), input2.into()],
         &[],
         &[output1.clone().into(), output1.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -365,6 +392,7 @@ fn update_name_happy_path_works() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -379,6 +407,7 @@ fn update_name_invalid_type_in_input_fails() {
         &[input.into(), Bogus.into()],
         &[],
         &[output.clone().into(), output.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -393,6 +422,7 @@ fn update_name_invalid_type_in_output_fails() {
         &[input.clone().into(), input.into()],
         &[],
         &[output.into(), Bogus.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -408,6 +438,7 @@ fn update_name_dna_update_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -428,6 +459,7 @@ fn update_name_free_breeding_update_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -448,6 +480,7 @@ fn update_name_num_of_breeding_updated_path_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -468,6 +501,7 @@ fn update_name_gender_updated_path_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -488,6 +522,7 @@ fn update_price_happy_path_works() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -506,6 +541,7 @@ fn update_price_multiple_input_happy_path_works() {
         &[input.into(), input1.into()],
         &[],
         &[output.into(), output1.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -524,6 +560,7 @@ fn update_price_multiple_input_out_of_order_fails() {
         &[input.into(), input1.into()],
         &[],
         &[output1.into(), output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -543,6 +580,7 @@ fn update_price_output_missing_path_fails() {
         &[input.into(), input1.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -564,6 +602,7 @@ fn update_price_input_missing_path_fails() {
         &[input.into()],
         &[],
         &[output.into(), output1.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -581,6 +620,7 @@ fn update_price_bad_input_path_fails() {
         &[Bogus.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -597,6 +637,7 @@ fn update_price_bad_output_path_fails() {
         &[input.into(), input1.into()],
         &[],
         &[output.into(), Bogus.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -613,6 +654,7 @@ fn update_price_different_dna_path_fails() {
         &[input.clone().into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -631,6 +673,7 @@ fn update_price_basic_properties_updated_path_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -647,6 +690,7 @@ fn update_price_not_updated_path_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::KittyPriceUnaltered));
 }
@@ -661,6 +705,7 @@ fn update_price_to_zero_updated_path_fails() {
         &[input.into()],
         &[],
         &[output.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::KittyPriceCantBeZero));
 }
@@ -680,6 +725,7 @@ fn buy_happy_path_single_input_coinworks() {
         &[input_kitty.into(), input_coin.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -698,6 +744,7 @@ fn buy_happy_path_multiple_input_coinworks() {
         &[input_kitty.into(), input_coin1.into(), input_coin2.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert!(result.is_ok());
 }
@@ -731,6 +778,7 @@ fn buy_path_multiple_kitty_fails() {
             output_kitty1.into(),
             output_coin.into(),
         ],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -748,6 +796,7 @@ fn buy_kityy_with_price_none_fails() {
         &[input_kitty.into(), input_coin.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::KittyPriceCantBeZero));
 }
@@ -766,6 +815,7 @@ fn buy_kityy_wrong_input_type_fails() {
         &[input_kitty.into(), input_coin.into(), Bogus.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -783,6 +833,7 @@ fn buy_kityy_wrong_output_type_fails() {
         &[input_kitty.into(), input_coin.into()],
         &[],
         &[output_kitty.into(), output_coin.into(), Bogus.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped));
 }
@@ -800,6 +851,7 @@ fn buy_kitty_less_money_than_price_of_kityy_fails() {
         &[input_kitty.into(), input_coin1.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -821,6 +873,7 @@ fn buy_kitty_coin_output_value_exceeds_input_coin_value_fails() {
         &[input_kitty.into(), input_coin1.into(), input_coin2.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -844,6 +897,7 @@ fn buy_kitty_input_zero_coin_value_fails() {
         &[input_kitty.into(), input_coin1.into(), input_coin2.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -865,6 +919,7 @@ fn buy_kitty_output_zero_coin_value_fails() {
         &[input_kitty.into(), input_coin1.into(), input_coin2.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert_eq!(
         result,
@@ -885,6 +940,7 @@ fn buy_kitty_basic_kitty_fails() {
         &[input_kitty.into(), input_coin1.into(), input_coin2.into()],
         &[],
         &[output_kitty.into(), output_coin.into()],
+        &[],
     );
     assert_eq!(result, Err(TradeableKittyError::BadlyTyped))
 }
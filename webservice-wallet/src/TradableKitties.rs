@@ -5,7 +5,9 @@ use crate::sync;
 //use crate::cli::BreedArgs;
 use tuxedo_core::{
     types::{Input, Output, OutputRef},
-    verifier::Sr25519Signature,
+    verifier::{
+        BlockHeightLock, EcdsaSignature, Ed25519Signature, Sr25519Signature, TimestampLock,
+    },
 };
 
 use crate::kitty;
@@ -17,6 +19,7 @@ use rand::distributions::Alphanumeric;
 use rand::Rng;
 use sc_keystore::LocalKeystore;
 use sled::Db;
+use sp_core::ed25519;
 use sp_core::sr25519::Public;
 use sp_runtime::traits::{BlakeTwo256, Hash};
 //use crate::kitty::get_kitty_name;
@@ -89,6 +92,8 @@ pub async fn set_kitty_property(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::UpdateProperties.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Keep a copy of the stripped encoded transaction for signing purposes
@@ -101,12 +106,27 @@ pub async fn set_kitty_property(
 
         // Construct the proof that it can be consumed
         let redeemer = match utxo.verifier {
-            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+            | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+            | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
                 let public = Public::from_h256(owner_pubkey);
                 crate::keystore::sign_with(keystore, &public, &stripped_encoded_transaction)?
             }
             OuterVerifier::UpForGrabs(_) => Vec::new(),
             OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+            OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+            OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
+            OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                let public = ed25519::Public::from_raw(owner_pubkey.0);
+                crate::keystore::sign_with_ed25519(
+                    keystore,
+                    &public,
+                    &stripped_encoded_transaction,
+                )?
+            }
+            OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                crate::keystore::sign_with_ecdsa(keystore, &owner, &stripped_encoded_transaction)?
+            }
         };
 
         // insert the proof
@@ -252,6 +272,8 @@ pub async fn breed_kitty(
         ])
             .to_vec(),
         checker: TradableKittyConstraintChecker::Breed.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Keep a copy of the stripped encoded transaction for signing purposes
@@ -264,12 +286,27 @@ pub async fn breed_kitty(
 
         // Construct the proof that it can be consumed
         let redeemer = match utxo.verifier {
-            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+            | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+            | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
                 let public = Public::from_h256(owner_pubkey);
                 crate::keystore::sign_with(keystore, &public, &stripped_encoded_transaction)?
             }
             OuterVerifier::UpForGrabs(_) => Vec::new(),
             OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+            OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+            OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
+            OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                let public = ed25519::Public::from_raw(owner_pubkey.0);
+                crate::keystore::sign_with_ed25519(
+                    keystore,
+                    &public,
+                    &stripped_encoded_transaction,
+                )?
+            }
+            OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                crate::keystore::sign_with_ecdsa(keystore, &owner, &stripped_encoded_transaction)?
+            }
         };
 
         // insert the proof
@@ -341,7 +378,9 @@ pub async fn buy_kitty(
         inputs: inputs,
         peeks: Vec::new(),
         outputs: vec![output],
-        checker: TradableKittyConstraintChecker::Buy.into()
+        checker: TradableKittyConstraintChecker::Buy.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Construct each output and then push to the transactions for Money
@@ -400,7 +439,9 @@ pub async fn buy_kitty(
 
         // Construct the proof that it can be consumed
         let redeemer = match utxo.verifier {
-            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+            | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+            | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
 
                 let public = Public::from_h256(owner_pubkey);
 
@@ -409,6 +450,19 @@ pub async fn buy_kitty(
             }
             OuterVerifier::UpForGrabs(_) => Vec::new(),
             OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+            OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+            OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
+            OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                let public = ed25519::Public::from_raw(owner_pubkey.0);
+                crate::keystore::sign_with_ed25519(
+                    keystore,
+                    &public,
+                    &stripped_encoded_transaction,
+                )?
+            }
+            OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                crate::keystore::sign_with_ecdsa(keystore, &owner, &stripped_encoded_transaction)?
+            }
         };
 
         // insert the proof
@@ -30,6 +30,8 @@ pub async fn amoeba_demo(client: &HttpClient) -> anyhow::Result<()> {
             verifier: UpForGrabs.into(),
         }],
         checker: AmoebaCreation.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Calculate the OutputRef which also serves as the storage location
@@ -80,6 +82,8 @@ pub async fn amoeba_demo(client: &HttpClient) -> anyhow::Result<()> {
             },
         ],
         checker: AmoebaMitosis.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Calculate the two OutputRefs for the daughters
@@ -7,11 +7,13 @@ use parity_scale_codec::Encode;
 use sc_keystore::LocalKeystore;
 use sp_core::{
     crypto::Pair as PairT,
+    ed25519,
     sr25519::{Pair, Public},
     //    H256,
 };
 use sp_keystore::Keystore;
 use sp_runtime::KeyTypeId;
+use tuxedo_core::verifier::{EcdsaOwner, EcdsaSignature, SimpleVerifier};
 
 use crate::get_local_keystore;
 use sp_core::H256;
@@ -48,6 +50,58 @@ pub fn sign_with(
     Ok(sig.encode())
 }
 
+/// Sign a given message with the ed25519 private key that corresponds to the given public key.
+///
+/// Returns an error if the keystore itself errors, or does not contain the requested key.
+pub fn sign_with_ed25519(
+    keystore: &LocalKeystore,
+    public: &ed25519::Public,
+    message: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    let sig = keystore
+        .ed25519_sign(KEY_TYPE, public, message)?
+        .ok_or(anyhow!("Key doesn't exist in keystore"))?;
+
+    Ok(sig.encode())
+}
+
+/// Sign a given message with the ecdsa private key that belongs to the given owner.
+///
+/// When the owner is an Ethereum address, the keystore's ecdsa keys are searched for one whose
+/// address matches, and the message is signed Ethereum-style over its Keccak-256 hash.
+///
+/// Returns an error if the keystore itself errors, or does not contain the requested key.
+pub fn sign_with_ecdsa(
+    keystore: &LocalKeystore,
+    owner: &EcdsaOwner,
+    message: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    match owner {
+        EcdsaOwner::PublicKey(public) => {
+            let sig = keystore
+                .ecdsa_sign(KEY_TYPE, public, message)?
+                .ok_or(anyhow!("Key doesn't exist in keystore"))?;
+
+            Ok(sig.encode())
+        }
+        EcdsaOwner::EthereumAddress(address) => {
+            let msg_hash = sp_core::hashing::keccak_256(message);
+            let verifier = EcdsaSignature::new_ethereum(*address);
+
+            for public in keystore.ecdsa_public_keys(KEY_TYPE) {
+                let Some(sig) = keystore.ecdsa_sign_prehashed(KEY_TYPE, &public, &msg_hash)? else {
+                    continue;
+                };
+                if verifier.verify(message, 0, sig.as_ref()) {
+                    return Ok(sig.encode());
+                }
+            }
+
+            Err(anyhow!("No key for address {:?} in keystore", address))
+        }
+    }
+}
+
 /// Insert the private key associated with the given seed into the keystore for later use.
 pub fn insert_key(keystore: &LocalKeystore, seed: &str) -> anyhow::Result<()> {
     // We need to provide a public key to the keystore manually, so let's calculate it.
@@ -7,7 +7,9 @@ use serde::{Deserialize, Serialize};
 use tuxedo_core::{
     dynamic_typing::UtxoData,
     types::{Input, Output, OutputRef},
-    verifier::Sr25519Signature,
+    verifier::{
+        BlockHeightLock, EcdsaSignature, Ed25519Signature, Sr25519Signature, TimestampLock,
+    },
 };
 
 use crate::get_blockchain_node_endpoint;
@@ -19,6 +21,7 @@ use rand::distributions::Alphanumeric;
 use rand::Rng;
 //use sc_keystore::LocalKeystore;
 use sled::Db;
+use sp_core::ed25519;
 use sp_core::sr25519::Public;
 use sp_core::H256;
 use sp_runtime::traits::{BlakeTwo256, Hash};
@@ -100,6 +103,10 @@ fn print_new_output(transaction: &Transaction) -> anyhow::Result<()> {
 }
 
 async fn send_signed_tx(transaction: &Transaction, client: &HttpClient) -> anyhow::Result<()> {
+    if let Err(e) = crate::rpc::dry_run_transaction(transaction, client).await? {
+        return Err(anyhow!("Kitty transaction would be rejected: {:?}", e));
+    }
+
     // Encode the transaction
     let spawn_hex = hex::encode(transaction.encode());
     let params = rpc_params![spawn_hex];
@@ -119,6 +126,10 @@ async fn send_unsigned_tx(
     transaction: &mut Transaction,
     client: &HttpClient,
 ) -> anyhow::Result<()> {
+    if let Err(e) = crate::rpc::dry_run_transaction(transaction, client).await? {
+        return Err(anyhow!("Kitty transaction would be rejected: {:?}", e));
+    }
+
     // Encode the transaction
     let spawn_hex = hex::encode(transaction.encode());
     let params = rpc_params![spawn_hex];
@@ -259,6 +270,8 @@ pub async fn create_kitty(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: FreeKittyConstraintChecker::Create.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     send_unsigned_tx(&mut transaction, &client).await?;
@@ -438,12 +451,27 @@ async fn send_tx_with_signed_inputs(
 
                 // Construct the proof that it can be consumed
                 let redeemer = match utxo.verifier {
-                    OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+                    OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+                    | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+                    | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
                         let public = Public::from_h256(owner_pubkey);
                         crate::keystore::sign_with(ks, &public, &stripped_encoded_transaction)?
                     }
                     OuterVerifier::UpForGrabs(_) => Vec::new(),
                     OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+                    OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+                    OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
+                    OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                        let public = ed25519::Public::from_raw(owner_pubkey.0);
+                        crate::keystore::sign_with_ed25519(
+                            ks,
+                            &public,
+                            &stripped_encoded_transaction,
+                        )?
+                    }
+                    OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                        crate::keystore::sign_with_ecdsa(ks, &owner, &stripped_encoded_transaction)?
+                    }
                 };
                 // insert the proof
                 input.redeemer = redeemer;
@@ -508,6 +536,8 @@ pub async fn create_txn_for_list_kitty(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::ListKittiesForSale.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
     transaction = add_redeemer_signed_with_local_ks(transaction.clone()).await?;
     let response = TransactionResponse {
@@ -554,6 +584,8 @@ pub async fn create_txn_for_delist_kitty(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::DelistKittiesFromSale.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
     transaction = add_redeemer_signed_with_local_ks(transaction.clone()).await?;
     let response = TransactionResponse {
@@ -606,6 +638,8 @@ pub async fn create_txn_for_kitty_name_update(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: FreeKittyConstraintChecker::UpdateKittiesName.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
     transaction = add_redeemer_signed_with_local_ks(transaction.clone()).await?;
     let response = TransactionResponse {
@@ -660,6 +694,8 @@ pub async fn create_txn_for_td_kitty_name_update(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::UpdateKittiesName.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
     transaction = add_redeemer_signed_with_local_ks(transaction.clone()).await?;
     let response = TransactionResponse {
@@ -711,6 +747,8 @@ pub async fn create_txn_for_td_kitty_price_update(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::UpdateKittiesPrice.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
     transaction = add_redeemer_signed_with_local_ks(transaction.clone()).await?;
     let response = TransactionResponse {
@@ -812,6 +850,8 @@ pub async fn create_txn_for_breed_kitty(
         ])
             .to_vec(),
         checker: FreeKittyConstraintChecker::Breed.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
     transaction = add_redeemer_signed_with_local_ks(transaction.clone()).await?;
     let response = TransactionResponse {
@@ -863,6 +903,8 @@ pub async fn create_txn_for_buy_kitty(
         peeks: Vec::new(),
         outputs: vec![output],
         checker: TradableKittyConstraintChecker::Buy.into(),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
     // Construct each output and then push to the transactions for Money
@@ -949,12 +991,31 @@ pub async fn add_redeemer_signed_with_local_ks(
 
         // Construct the proof that it can be consumed
         let redeemer = match utxo.unwrap().verifier {
-            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey }) => {
+            OuterVerifier::Sr25519Signature(Sr25519Signature { owner_pubkey })
+            | OuterVerifier::BlockHeightLock(BlockHeightLock { owner_pubkey, .. })
+            | OuterVerifier::TimestampLock(TimestampLock { owner_pubkey, .. }) => {
                 let public = Public::from_h256(owner_pubkey);
                 crate::keystore::sign_with(&local_keystore, &public, &stripped_encoded_transaction)
             }
             OuterVerifier::UpForGrabs(_) => Ok(Vec::new()),
             OuterVerifier::ThresholdMultiSignature(_) => todo!(),
+            OuterVerifier::HashTimeLock(_) => anyhow::bail!("unsupported verifier"),
+            OuterVerifier::Unspendable(_) => anyhow::bail!("unspendable output"),
+            OuterVerifier::Ed25519Signature(Ed25519Signature { owner_pubkey }) => {
+                let public = ed25519::Public::from_raw(owner_pubkey.0);
+                crate::keystore::sign_with_ed25519(
+                    &local_keystore,
+                    &public,
+                    &stripped_encoded_transaction,
+                )
+            }
+            OuterVerifier::EcdsaSignature(EcdsaSignature { owner }) => {
+                crate::keystore::sign_with_ecdsa(
+                    &local_keystore,
+                    &owner,
+                    &stripped_encoded_transaction,
+                )
+            }
         };
         // insert the proof
         input.redeemer = redeemer.expect("redeemer can be created");
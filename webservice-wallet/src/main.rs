@@ -329,6 +329,30 @@ pub fn pretty_print_verifier(v: &OuterVerifier) {
                 string_sigs, multi_sig.threshold
             );
         }
+        OuterVerifier::BlockHeightLock(lock) => println!(
+            "owned by {}, locked until block {}",
+            lock.owner_pubkey, lock.unlock_height
+        ),
+        OuterVerifier::TimestampLock(lock) => println!(
+            "owned by {}, locked until timestamp {}",
+            lock.owner_pubkey, lock.unlock_time
+        ),
+        OuterVerifier::HashTimeLock(htlc) => println!(
+            "claimable by {} with the preimage of {:?}, refundable to {} from block {}",
+            htlc.recipient_pubkey, htlc.hash_lock, htlc.refunder_pubkey, htlc.refund_height
+        ),
+        OuterVerifier::Unspendable(_) => println!("that can never be spent"),
+        OuterVerifier::Ed25519Signature(ed25519_signature) => {
+            println! {"owned by ed25519 key {}", ed25519_signature.owner_pubkey}
+        }
+        OuterVerifier::EcdsaSignature(ecdsa_signature) => match &ecdsa_signature.owner {
+            tuxedo_core::verifier::EcdsaOwner::PublicKey(public) => {
+                println!("owned by ecdsa key {}", public)
+            }
+            tuxedo_core::verifier::EcdsaOwner::EthereumAddress(address) => {
+                println!("owned by ethereum address {:?}", address)
+            }
+        },
     }
 }
 
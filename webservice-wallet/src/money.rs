@@ -29,9 +29,15 @@ pub async fn mint_coins(client: &HttpClient, amount: u128, public_key: H256) ->
             }),
         )
             .into()],
-        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Mint),
+        checker: OuterConstraintChecker::Money(MoneyConstraintChecker::Mint.into()),
+        evictions: Vec::new(),
+        valid_until: None,
     };
 
+    if let Err(e) = crate::rpc::dry_run_transaction(&transaction, client).await? {
+        return Err(anyhow!("Mint transaction would be rejected: {:?}", e));
+    }
+
     let spawn_hex = hex::encode(transaction.encode());
     let params = rpc_params![spawn_hex];
     let _spawn_response: Result<String, _> = client.request("author_submitExtrinsic", params).await;